@@ -1,7 +1,17 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
 using System.Buffers;
+using System.Numerics;
 using System.Runtime.InteropServices;
+using System.Text;
+
+using Google.Protobuf;
+
+using ConnectionRequestProto = ConnectionRequest.ConnectionRequest;
+using NodeAddress = ConnectionRequest.NodeAddress;
+using Routes = CommandRequest.Routes;
+using TlsMode = ConnectionRequest.TlsMode;
+using Transaction = CommandRequest.Transaction;
 
 namespace Glide;
 
@@ -9,47 +19,95 @@ public class AsyncClient : IDisposable
 {
     #region public methods
     public AsyncClient(string host, uint port, bool useTLS)
+        : this(new ConnectionRequestProto
+        {
+            Addresses = { new NodeAddress { Host = host, Port = port } },
+            TlsMode = useTLS ? TlsMode.SecureTls : TlsMode.NoTls,
+        })
+    {
+    }
+
+    /// Creates a client configured by a `ConnectionRequest`, which covers cluster mode, authentication, read-from strategy,
+    /// reconnection retries and Pub/Sub subscriptions.
+    ///
+    /// All the clients share a runtime, which is started by the first client with `workerThreads` worker threads, or a
+    /// worker thread per CPU if `workerThreads` is 0. The `workerThreads` of later clients are ignored while it's running.
+    public AsyncClient(ConnectionRequestProto request, uint workerThreads = 0)
     {
         _successCallbackDelegate = SuccessCallback;
         nint successCallbackPointer = Marshal.GetFunctionPointerForDelegate(_successCallbackDelegate);
         _failureCallbackDelegate = FailureCallback;
         nint failureCallbackPointer = Marshal.GetFunctionPointerForDelegate(_failureCallbackDelegate);
-        _clientPointer = CreateClientFfi(host, port, useTLS, successCallbackPointer, failureCallbackPointer);
-        if (_clientPointer == IntPtr.Zero)
+        byte[] requestBytes = request.ToByteArray();
+        IntPtr responsePointer = CreateClientFfi(requestBytes, (nuint)requestBytes.Length, successCallbackPointer, failureCallbackPointer, workerThreads);
+        try
         {
-            throw new Exception("Failed creating a client");
+            ConnectionResponse response = Marshal.PtrToStructure<ConnectionResponse>(responsePointer);
+            if (response.ClientPointer == IntPtr.Zero)
+            {
+                throw new ConnectionException(Marshal.PtrToStringUTF8(response.ErrorMessage) ?? "Failed creating a client");
+            }
+            _clientPointer = response.ClientPointer;
+        }
+        finally
+        {
+            FreeConnectionResponseFfi(responsePointer);
         }
     }
 
-    private async Task<string?> Command(IntPtr[] args, int argsCount, RequestType requestType)
+    private async Task<object?> Command(string[] arguments, RequestType requestType, Routes? route = null)
     {
-        // We need to pin the array in place, in order to ensure that the GC doesn't move it while the operation is running.
-        GCHandle pinnedArray = GCHandle.Alloc(args, GCHandleType.Pinned);
-        IntPtr pointer = pinnedArray.AddrOfPinnedObject();
-        Message<string> message = _messageContainer.GetMessageForCall(args, argsCount);
-        CommandFfi(_clientPointer, (ulong)message.Index, (int)requestType, pointer, (uint)argsCount);
-        string? result = await message;
-        pinnedArray.Free();
+        int argsCount = arguments.Length;
+        IntPtr[] args = _arrayPool.Rent(argsCount);
+        ulong[] argLengths = _lengthsPool.Rent(argsCount);
+        for (int i = 0; i < argsCount; i++)
+        {
+            (args[i], argLengths[i]) = AllocateArgument(arguments[i]);
+        }
+        byte[] routeBytes = route?.ToByteArray() ?? [];
+        // The arguments are copied by the native client before the call returns. The argument pointers themselves are freed once the message completes.
+        Message<object> message = _messageContainer.GetMessageForCall(args, argsCount);
+        CommandFfi(_clientPointer, (ulong)message.Index, (int)requestType, args, argLengths, (uint)argsCount, routeBytes, (nuint)routeBytes.Length);
+        _lengthsPool.Return(argLengths);
+        object? result = await message;
+        _arrayPool.Return(args);
         return result;
     }
 
-    public async Task<string?> SetAsync(string key, string value)
+    public async Task<string?> SetAsync(string key, string value) =>
+        (string?)await Command([key, value], RequestType.Set);
+
+    public async Task<string?> GetAsync(string key) =>
+        (string?)await Command([key], RequestType.Get);
+
+    /// Executes a command by its arguments, e.g. `["CLIENT", "LIST"]`. If `route` is null, the command is routed by its keys.
+    /// Returns the typed response: a string, long, double, bool, BigInteger, object array, dictionary, set, or null.
+    public Task<object?> CustomCommand(string[] args, Routes? route = null) =>
+        Command(args, RequestType.CustomCommand, route);
+
+    /// Executes the commands of `transaction` atomically. Returns the response of each command,
+    /// or null if the transaction was aborted because a watched key was modified.
+    public async Task<object?[]?> ExecAsync(Transaction transaction, Routes? route = null)
     {
-        IntPtr[] args = _arrayPool.Rent(2);
-        args[0] = Marshal.StringToHGlobalAnsi(key);
-        args[1] = Marshal.StringToHGlobalAnsi(value);
-        string? result = await Command(args, 2, RequestType.Set);
-        _arrayPool.Return(args);
-        return result;
+        byte[] transactionBytes = transaction.ToByteArray();
+        byte[] routeBytes = route?.ToByteArray() ?? [];
+        Message<object> message = _messageContainer.GetMessageForCall([], 0);
+        TransactionFfi(_clientPointer, (ulong)message.Index, transactionBytes, (nuint)transactionBytes.Length, routeBytes, (nuint)routeBytes.Length);
+        return (object?[]?)await message;
     }
 
-    public async Task<string?> GetAsync(string key)
+    /// Closes the client once its inflight requests complete. Requests that are still running after `drainTimeout` are cancelled.
+    /// If `drainTimeout` is null, the client waits for all inflight requests to complete.
+    public async Task CloseAsync(TimeSpan? drainTimeout = null)
     {
-        IntPtr[] args = _arrayPool.Rent(1);
-        args[0] = Marshal.StringToHGlobalAnsi(key);
-        string? result = await Command(args, 1, RequestType.Get);
-        _arrayPool.Return(args);
-        return result;
+        if (_clientPointer == IntPtr.Zero)
+        {
+            return;
+        }
+        Message<object> message = _messageContainer.GetMessageForCall([], 0);
+        DrainClientFfi(_clientPointer, (ulong)message.Index, (uint)(drainTimeout?.TotalMilliseconds ?? 0));
+        _ = await message;
+        Dispose();
     }
 
     public void Dispose()
@@ -67,24 +125,85 @@ public class AsyncClient : IDisposable
 
     #region private methods
 
-    private void SuccessCallback(ulong index, IntPtr str)
+    /// Copies the UTF-8 bytes of `arg` to unmanaged memory, which is freed once the message completes.
+    private static (IntPtr, ulong) AllocateArgument(string arg)
     {
-        string? result = str == IntPtr.Zero ? null : Marshal.PtrToStringAnsi(str);
+        byte[] bytes = Encoding.UTF8.GetBytes(arg);
+        IntPtr pointer = Marshal.AllocHGlobal(bytes.Length);
+        Marshal.Copy(bytes, 0, pointer, bytes.Length);
+        return (pointer, (ulong)bytes.Length);
+    }
+
+    private void SuccessCallback(ulong index, IntPtr response)
+    {
+        // The response is owned by the callback, so it's converted and freed before the work is offloaded.
+        object? result = ConvertResponse(response);
+        FreeCommandResponseFfi(response);
         // Work needs to be offloaded from the calling thread, because otherwise we might starve the client's thread pool.
         _ = Task.Run(() =>
         {
-            Message<string> message = _messageContainer.GetMessage((int)index);
+            Message<object> message = _messageContainer.GetMessage((int)index);
             message.SetResult(result);
         });
     }
 
-    private void FailureCallback(ulong index) =>
+    private void FailureCallback(ulong index, IntPtr errorMessage, RequestErrorType errorType, RequestErrorKind errorKind, IntPtr errorCode, IntPtr errorAddress)
+    {
+        string message = Marshal.PtrToStringUTF8(errorMessage) ?? "Operation failed";
+        FreeErrorMessageFfi(errorMessage);
+        string? code = TakeErrorDetail(errorCode);
+        string? address = TakeErrorDetail(errorAddress);
         // Work needs to be offloaded from the calling thread, because otherwise we might starve the client's thread pool.
-        Task.Run(() =>
+        _ = Task.Run(() =>
         {
-            Message<string> message = _messageContainer.GetMessage((int)index);
-            message.SetException(new Exception("Operation failed"));
+            Message<object> callMessage = _messageContainer.GetMessage((int)index);
+            callMessage.SetException(new RequestException(message, errorType, errorKind, code, address));
         });
+    }
+
+    /// Copies and frees an error code or address passed to the failure callback, which is null if it's missing.
+    private static string? TakeErrorDetail(IntPtr detail)
+    {
+        string? result = Marshal.PtrToStringUTF8(detail);
+        FreeErrorMessageFfi(detail);
+        return result;
+    }
+
+    private static string ReadString(IntPtr pointer, long length) =>
+        pointer == IntPtr.Zero ? string.Empty : Marshal.PtrToStringUTF8(pointer, (int)length);
+
+    private static IEnumerable<CommandResponse> ReadElements(IntPtr elements, long length)
+    {
+        int size = Marshal.SizeOf<CommandResponse>();
+        for (long i = 0; i < length; i++)
+        {
+            yield return Marshal.PtrToStructure<CommandResponse>(elements + (nint)(i * size));
+        }
+    }
+
+    private static IEnumerable<CommandResponse> ReadElements(CommandResponse response) =>
+        ReadElements(response.ArrayValue, response.ArrayValueLen);
+
+    private static object? ConvertResponse(IntPtr pointer) =>
+        pointer == IntPtr.Zero ? null : ConvertResponse(Marshal.PtrToStructure<CommandResponse>(pointer));
+
+    private static object? ConvertResponse(CommandResponse response) => response.ResponseType switch
+    {
+        ResponseType.Null => null,
+        ResponseType.Int => response.IntValue,
+        ResponseType.Float => response.FloatValue,
+        ResponseType.Bool => response.BoolValue,
+        ResponseType.String => ReadString(response.StringValue, response.StringValueLen),
+        ResponseType.BigNumber => BigInteger.Parse(ReadString(response.StringValue, response.StringValueLen)),
+        ResponseType.Array or ResponseType.Push => ReadElements(response).Select(ConvertResponse).ToArray(),
+        ResponseType.Sets => ReadElements(response.SetsValue, response.SetsValueLen).Select(ConvertResponse).ToHashSet(),
+        ResponseType.Map => ReadElements(response).ToDictionary(
+            entry => ConvertResponse(entry.MapKey)!,
+            entry => ConvertResponse(entry.MapValue)),
+        // Attributes are out-of-band metadata, so only the value they are attached to is returned.
+        ResponseType.Attribute => ConvertResponse(response.AttributeData),
+        _ => throw new RequestException($"Unexpected response type {response.ResponseType}", RequestErrorType.Unspecified),
+    };
 
     ~AsyncClient() => Dispose();
     #endregion private methods
@@ -97,29 +216,92 @@ public class AsyncClient : IDisposable
 
     /// Held as a measure to prevent the delegate being garbage collected. These are delegated once
     /// and held in order to prevent the cost of marshalling on each function call.
-    private readonly StringAction _successCallbackDelegate;
+    private readonly ResponseAction _successCallbackDelegate;
 
     /// Raw pointer to the underlying native client.
     private IntPtr _clientPointer;
-    private readonly MessageContainer<string> _messageContainer = new();
+    private readonly MessageContainer<object> _messageContainer = new();
     private readonly ArrayPool<IntPtr> _arrayPool = ArrayPool<IntPtr>.Shared;
+    private readonly ArrayPool<ulong> _lengthsPool = ArrayPool<ulong>.Shared;
 
     #endregion private fields
 
     #region FFI function declarations
 
-    private delegate void StringAction(ulong index, IntPtr str);
-    private delegate void FailureAction(ulong index);
+    private delegate void ResponseAction(ulong index, IntPtr response);
+    private delegate void FailureAction(ulong index, IntPtr errorMessage, RequestErrorType errorType, RequestErrorKind errorKind, IntPtr errorCode, IntPtr errorAddress);
     [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "command")]
-    private static extern void CommandFfi(IntPtr client, ulong index, int requestType, IntPtr args, uint argCount);
+    private static extern void CommandFfi(IntPtr client, ulong index, int requestType, IntPtr[] args, ulong[] argLengths, uint argCount, byte[] route, nuint routeLength);
+
+    [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "transaction")]
+    private static extern void TransactionFfi(IntPtr client, ulong index, byte[] transaction, nuint transactionLength, byte[] route, nuint routeLength);
 
-    private delegate void IntAction(IntPtr arg);
     [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "create_client")]
-    private static extern IntPtr CreateClientFfi(string host, uint port, bool useTLS, IntPtr successCallback, IntPtr failureCallback);
+    private static extern IntPtr CreateClientFfi(byte[] connectionRequest, nuint connectionRequestLength, IntPtr successCallback, IntPtr failureCallback, nuint workerThreads);
+
+    [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "free_connection_response")]
+    private static extern void FreeConnectionResponseFfi(IntPtr connectionResponse);
+
+    [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "free_command_response")]
+    private static extern void FreeCommandResponseFfi(IntPtr response);
+
+    [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "free_error_message")]
+    private static extern void FreeErrorMessageFfi(IntPtr errorMessage);
 
     [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "close_client")]
     private static extern void CloseClientFfi(IntPtr client);
 
+    [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "drain_client")]
+    private static extern void DrainClientFfi(IntPtr client, ulong index, uint drainTimeoutMs);
+
+    #endregion
+
+    #region native types
+
+    [StructLayout(LayoutKind.Sequential)]
+    private struct ConnectionResponse
+    {
+        public IntPtr ClientPointer;
+        public IntPtr ErrorMessage;
+    }
+
+    private enum ResponseType
+    {
+        Null = 0,
+        Int = 1,
+        Float = 2,
+        Bool = 3,
+        String = 4,
+        Array = 5,
+        Map = 6,
+        Sets = 7,
+        BigNumber = 8,
+        Attribute = 9,
+        Push = 10,
+    }
+
+    /// Mirrors `CommandResponse` in the glide-ffi crate.
+    [StructLayout(LayoutKind.Sequential)]
+    private struct CommandResponse
+    {
+        public ResponseType ResponseType;
+        public long IntValue;
+        public double FloatValue;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool BoolValue;
+        public IntPtr StringValue;
+        public long StringValueLen;
+        public IntPtr ArrayValue;
+        public long ArrayValueLen;
+        public IntPtr MapKey;
+        public IntPtr MapValue;
+        public IntPtr SetsValue;
+        public long SetsValueLen;
+        public IntPtr KindValue;
+        public long KindValueLen;
+        public IntPtr AttributeData;
+    }
+
     #endregion
 
     #region RequestType
@@ -0,0 +1,74 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+namespace Glide;
+
+/// The type of a request failure, as reported by the native client.
+public enum RequestErrorType
+{
+    Unspecified = 0,
+    ExecAbort = 1,
+    Timeout = 2,
+    Disconnect = 3,
+    Cancelled = 4,
+}
+
+/// The kind of a request failure, as reported by the native client. New kinds may be added in later versions.
+public enum RequestErrorKind
+{
+    Unknown = 0,
+    ResponseError = 1,
+    ParseError = 2,
+    AuthenticationFailed = 3,
+    TypeError = 4,
+    ExecAbortError = 5,
+    BusyLoadingError = 6,
+    NoScriptError = 7,
+    InvalidClientConfig = 8,
+    Moved = 9,
+    Ask = 10,
+    TryAgain = 11,
+    ClusterDown = 12,
+    CrossSlot = 13,
+    MasterDown = 14,
+    IoError = 15,
+    FatalSendError = 16,
+    FatalReceiveError = 17,
+    ClientError = 18,
+    ExtensionError = 19,
+    ReadOnly = 20,
+    MasterNameNotFoundBySentinel = 21,
+    NoValidReplicasFoundBySentinel = 22,
+    EmptySentinelList = 23,
+    NotBusy = 24,
+    AllConnectionsUnavailable = 25,
+    ConnectionNotFoundForRoute = 26,
+    Resp3NotSupported = 27,
+    NotAllSlotsCovered = 28,
+    UserOperationError = 29,
+    RequestCancelled = 30,
+    InflightRequestsLimit = 31,
+}
+
+/// A request that failed. `ErrorType` tells apart failures that may be handled differently, such as timeouts and disconnects.
+/// `Code` is the error code sent by the server, e.g. `MOVED` or `WRONGTYPE`, and `Address` is the address that a `MOVED` or
+/// `ASK` error redirects to. Both are null when they don't apply.
+public class RequestException(
+    string message,
+    RequestErrorType errorType,
+    RequestErrorKind errorKind = RequestErrorKind.Unknown,
+    string? code = null,
+    string? address = null) : Exception(message)
+{
+    public RequestErrorType ErrorType { get; } = errorType;
+
+    public RequestErrorKind ErrorKind { get; } = errorKind;
+
+    public string? Code { get; } = code;
+
+    public string? Address { get; } = address;
+}
+
+/// The client couldn't connect to the server with the given configuration.
+public class ConnectionException(string message) : Exception(message)
+{
+}
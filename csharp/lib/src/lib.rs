@@ -1,15 +1,17 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
-use glide_core::client;
-use glide_core::client::Client as GlideClient;
+use glide_core::command_request::Transaction;
 use glide_core::request_type::RequestType;
-use redis::{FromRedisValue, RedisResult};
+use glide_ffi::{
+    bytes_from_raw, get_command, get_route, get_transaction, parse_protobuf, Callbacks,
+    ClientAdapter, CommandResponse, FailureCallback, RuntimeConfig, SuccessCallback,
+};
+use std::slice::from_raw_parts;
 use std::{
     ffi::{c_void, CStr, CString},
     os::raw::c_char,
+    time::Duration,
 };
-use tokio::runtime::Builder;
-use tokio::runtime::Runtime;
 
 pub enum Level {
     Error = 0,
@@ -20,121 +22,225 @@ pub enum Level {
     Off = 5,
 }
 
-pub struct Client {
-    client: GlideClient,
-    success_callback: unsafe extern "C" fn(usize, *const c_char) -> (),
-    failure_callback: unsafe extern "C" fn(usize) -> (), // TODO - add specific error codes
-    runtime: Runtime,
+/// The result of [`create_client`]. Exactly one of `client_ptr` and `error_message` is set.
+/// It must be freed with [`free_connection_response`].
+#[repr(C)]
+pub struct ConnectionResponse {
+    client_ptr: *const c_void,
+    error_message: *const c_char,
 }
 
-fn create_connection_request(host: String, port: u32, use_tls: bool) -> client::ConnectionRequest {
-    let address_info = client::NodeAddress {
-        host,
-        port: port as u16,
+/// Creates a new client, configured by a serialized `connection_request.proto` `ConnectionRequest`.
+/// All callbacks should be offloaded to separate threads in order not to exhaust the client's thread pool.
+/// The responses and error messages passed to the callbacks must be freed with [`free_command_response`] and [`free_error_message`].
+///
+/// All the C# clients share a single runtime, which is started by the first client with `worker_threads` worker threads,
+/// or a worker thread per CPU if `worker_threads` is 0. The runtime's size can't be changed while it's running.
+///
+/// # Safety
+///
+/// * `connection_request_bytes` must point to `connection_request_len` consecutive properly initialized bytes, owned by the caller.
+/// * The returned `ConnectionResponse` must be freed with [`free_connection_response`], and its `client_ptr` with [`close_client`].
+#[no_mangle]
+pub unsafe extern "C" fn create_client(
+    connection_request_bytes: *const u8,
+    connection_request_len: usize,
+    success_callback: SuccessCallback,
+    failure_callback: FailureCallback,
+    worker_threads: usize,
+) -> *const ConnectionResponse {
+    let request_bytes = unsafe { bytes_from_raw(connection_request_bytes, connection_request_len) };
+    let callbacks = Callbacks {
+        success: success_callback,
+        failure: failure_callback,
     };
-    let addresses = vec![address_info];
-    client::ConnectionRequest {
-        addresses,
-        tls_mode: if use_tls {
-            Some(client::TlsMode::SecureTls)
-        } else {
-            Some(client::TlsMode::NoTls)
+    let runtime_config = RuntimeConfig {
+        thread_name: "GLIDE for Redis C# thread".to_string(),
+        worker_threads: (worker_threads > 0).then_some(worker_threads),
+    };
+    let response = match ClientAdapter::create(request_bytes, callbacks, &runtime_config, None) {
+        Err(err) => {
+            logger_core::log_error("create client", &err);
+            ConnectionResponse {
+                client_ptr: std::ptr::null(),
+                error_message: CString::new(err).unwrap_or_default().into_raw(),
+            }
+        }
+        Ok(client_ptr) => ConnectionResponse {
+            client_ptr,
+            error_message: std::ptr::null(),
         },
-        ..Default::default()
+    };
+    Box::into_raw(Box::new(response))
+}
+
+/// Frees a `ConnectionResponse` returned from [`create_client`]. The client itself isn't freed.
+///
+/// # Safety
+///
+/// * `connection_response_ptr` must be obtained from [`create_client`], and can only be freed once.
+#[no_mangle]
+pub unsafe extern "C" fn free_connection_response(
+    connection_response_ptr: *mut ConnectionResponse,
+) {
+    assert!(!connection_response_ptr.is_null());
+    let connection_response = unsafe { Box::from_raw(connection_response_ptr) };
+    if !connection_response.error_message.is_null() {
+        drop(unsafe { CString::from_raw(connection_response.error_message as *mut c_char) });
     }
 }
 
-fn create_client_internal(
-    host: *const c_char,
-    port: u32,
-    use_tls: bool,
-    success_callback: unsafe extern "C" fn(usize, *const c_char) -> (),
-    failure_callback: unsafe extern "C" fn(usize) -> (),
-) -> RedisResult<Client> {
-    let host_cstring = unsafe { CStr::from_ptr(host) };
-    let host_string = host_cstring.to_str()?.to_string();
-    let request = create_connection_request(host_string, port, use_tls);
-    let runtime = Builder::new_multi_thread()
-        .enable_all()
-        .thread_name("GLIDE for Redis C# thread")
-        .build()?;
-    let _runtime_handle = runtime.enter();
-    let client = runtime.block_on(GlideClient::new(request, None)).unwrap(); // TODO - handle errors.
-    Ok(Client {
-        client,
-        success_callback,
-        failure_callback,
-        runtime,
-    })
+/// Frees a response passed to the success callback. Null responses are ignored.
+///
+/// # Safety
+///
+/// * `response` must be null, or obtained from the success callback. It can only be freed once.
+#[no_mangle]
+pub unsafe extern "C" fn free_command_response(response: *mut CommandResponse) {
+    unsafe { glide_ffi::free_command_response(response) };
 }
 
-/// Creates a new client to the given address. The success callback needs to copy the given string synchronously, since it will be dropped by Rust once the callback returns. All callbacks should be offloaded to separate threads in order not to exhaust the client's thread pool.
+/// Frees an error message passed to the failure callback.
+///
+/// # Safety
+///
+/// * `error_message` must be obtained from the failure callback. It can only be freed once.
 #[no_mangle]
-pub extern "C" fn create_client(
-    host: *const c_char,
-    port: u32,
-    use_tls: bool,
-    success_callback: unsafe extern "C" fn(usize, *const c_char) -> (),
-    failure_callback: unsafe extern "C" fn(usize) -> (),
-) -> *const c_void {
-    match create_client_internal(host, port, use_tls, success_callback, failure_callback) {
-        Err(_) => std::ptr::null(), // TODO - log errors
-        Ok(client) => Box::into_raw(Box::new(client)) as *const c_void,
-    }
+pub unsafe extern "C" fn free_error_message(error_message: *mut c_char) {
+    unsafe { glide_ffi::free_error_message(error_message) };
+}
+
+/// Frees the client. Requests that are still running are dropped without calling their callbacks.
+///
+/// # Safety
+///
+/// * `client_ptr` must be obtained from [`create_client`], and can only be closed once.
+#[no_mangle]
+pub unsafe extern "C" fn close_client(client_ptr: *const c_void) {
+    unsafe { ClientAdapter::destroy(client_ptr) };
 }
 
+/// Gracefully closes the client without freeing it. New commands fail, and inflight commands are given up to `drain_timeout_ms`
+/// milliseconds to complete before being cancelled. A `drain_timeout_ms` of 0 waits for all inflight commands. The success callback is
+/// called with a null response once the client is closed. `close_client` still needs to be called afterwards to free the client.
+///
+/// # Safety
+///
+/// * `client_ptr` must be obtained from [`create_client`], and must be valid until the callback is called.
 #[no_mangle]
-pub extern "C" fn close_client(client_ptr: *const c_void) {
-    let client_ptr = unsafe { Box::from_raw(client_ptr as *mut Client) };
-    let _runtime_handle = client_ptr.runtime.enter();
-    drop(client_ptr);
+pub unsafe extern "C" fn drain_client(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    drain_timeout_ms: u32,
+) {
+    let client = unsafe { ClientAdapter::from_ptr(client_ptr) };
+    let drain_timeout =
+        (drain_timeout_ms != 0).then(|| Duration::from_millis(drain_timeout_ms.into()));
+    client.drain(callback_index, drain_timeout);
+}
+
+/// Returns the `arg_count` arguments at `args`, whose lengths are at `args_len`.
+///
+/// # Safety
+///
+/// * `args` and `args_len` must point to `arg_count` elements, and each argument must point to the matching number of bytes.
+unsafe fn command_args<'a>(
+    args: *const usize,
+    args_len: *const u64,
+    arg_count: u32,
+) -> Vec<&'a [u8]> {
+    if arg_count == 0 {
+        return Vec::new();
+    }
+    let arg_ptrs = unsafe { from_raw_parts(args, arg_count as usize) };
+    let arg_lens = unsafe { from_raw_parts(args_len, arg_count as usize) };
+    arg_ptrs
+        .iter()
+        .zip(arg_lens)
+        .map(|(arg_ptr, arg_len)| unsafe {
+            from_raw_parts(*arg_ptr as *const u8, *arg_len as usize)
+        })
+        .collect()
 }
 
-/// Expects that key and value will be kept valid until the callback is called.
+/// Executes a command. `args` and `args_len` hold `arg_count` pointers to the arguments and their lengths,
+/// and `route_bytes` is a serialized `command_request.proto` `Routes`, or empty for the default route.
+///
+/// # Safety
+///
+/// * `client_ptr` must be obtained from [`create_client`], and must be valid until the callback is called.
+/// * `args` and `args_len` must point to `arg_count` elements, and each argument must point to the matching number of bytes.
+/// * `route_bytes` must point to `route_bytes_len` consecutive bytes, unless `route_bytes_len` is 0.
+/// * The arguments and the route are copied before this function returns, so the caller may free them afterwards.
 #[no_mangle]
-pub extern "C" fn command(
+pub unsafe extern "C" fn command(
     client_ptr: *const c_void,
     callback_index: usize,
     request_type: RequestType,
-    args: *const *mut c_char,
+    args: *const usize,
+    args_len: *const u64,
     arg_count: u32,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
 ) {
-    let client = unsafe { Box::leak(Box::from_raw(client_ptr as *mut Client)) };
-
-    // The safety of these needs to be ensured by the calling code. Cannot dispose of the pointer before all operations have completed.
-    let ptr_address = client_ptr as usize;
-    let args_address = args as usize;
-
-    let mut client_clone = client.client.clone();
-    client.runtime.spawn(async move {
-        let Some(mut cmd) = request_type.get_command() else {
-            unsafe {
-                let client = Box::leak(Box::from_raw(ptr_address as *mut Client));
-                (client.failure_callback)(callback_index); // TODO - report errors
-                return;
-            }
-        };
+    let client = unsafe { ClientAdapter::from_ptr(client_ptr) };
 
-        let args_slice = unsafe {
-            std::slice::from_raw_parts(args_address as *const *mut c_char, arg_count as usize)
-        };
-        for arg in args_slice {
-            let c_str = unsafe { CStr::from_ptr(*arg as *mut c_char) };
-            cmd.arg(c_str.to_bytes());
-        }
+    let args = unsafe { command_args(args, args_len, arg_count) };
+    let request = get_command(request_type, args).and_then(|cmd| {
+        let route = get_route(
+            unsafe { bytes_from_raw(route_bytes, route_bytes_len) },
+            Some(&cmd),
+        )?;
+        Ok((cmd, route))
+    });
+    let (cmd, route) = match request {
+        Ok(request) => request,
+        Err(err) => return client.report(callback_index, Err(err)),
+    };
 
-        let result = client_clone
-            .send_command(&cmd, None)
-            .await
-            .and_then(Option::<CString>::from_owned_redis_value);
-        unsafe {
-            let client = Box::leak(Box::from_raw(ptr_address as *mut Client));
-            match result {
-                Ok(None) => (client.success_callback)(callback_index, std::ptr::null()),
-                Ok(Some(c_str)) => (client.success_callback)(callback_index, c_str.as_ptr()),
-                Err(_) => (client.failure_callback)(callback_index), // TODO - report errors
-            };
-        }
+    client.execute(callback_index, |mut client| async move {
+        client.send_command(&cmd, route).await
+    });
+}
+
+/// Executes a transaction. `transaction_bytes` is a serialized `command_request.proto` `Transaction`, and `route_bytes`
+/// is a serialized `Routes`, or empty for the default route. The response is an array with the result of each command,
+/// or null if the transaction was aborted by a `WATCH`.
+///
+/// # Safety
+///
+/// * `client_ptr` must be obtained from [`create_client`], and must be valid until the callback is called.
+/// * `transaction_bytes` and `route_bytes` must point to the given number of consecutive bytes, unless the length is 0.
+///   They are copied before this function returns.
+#[no_mangle]
+pub unsafe extern "C" fn transaction(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    transaction_bytes: *const u8,
+    transaction_bytes_len: usize,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
+) {
+    let client = unsafe { ClientAdapter::from_ptr(client_ptr) };
+
+    let request = parse_protobuf::<Transaction>(unsafe {
+        bytes_from_raw(transaction_bytes, transaction_bytes_len)
+    })
+    .and_then(|transaction| get_transaction(&transaction))
+    .and_then(|pipeline| {
+        let route = get_route(
+            unsafe { bytes_from_raw(route_bytes, route_bytes_len) },
+            None,
+        )?;
+        Ok((pipeline, route))
+    });
+    let (pipeline, route) = match request {
+        Ok(request) => request,
+        Err(err) => return client.report(callback_index, Err(err)),
+    };
+
+    client.execute(callback_index, |mut client| async move {
+        client.send_transaction(&pipeline, route).await
     });
 }
 
@@ -207,3 +313,20 @@ pub unsafe extern "C" fn init(level: Option<Level>, file_name: *const c_char) ->
         logger_level.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args() {
+        let args: [&[u8]; 2] = [b"key", b"value"];
+        let ptrs = args.map(|arg| arg.as_ptr() as usize);
+        let lens = args.map(|arg| arg.len() as u64);
+        assert_eq!(
+            unsafe { command_args(ptrs.as_ptr(), lens.as_ptr(), 2) },
+            args.to_vec()
+        );
+        assert!(unsafe { command_args(std::ptr::null(), std::ptr::null(), 0) }.is_empty());
+    }
+}
@@ -111,4 +111,17 @@ public class GetAndSet : IClassFixture<IntegrationTestBase>
 
         Task.WaitAll([.. operations]);
     }
+
+    [Fact]
+    public async Task RequestErrorHasKindAndCode()
+    {
+        using AsyncClient client = new("localhost", TestConfiguration.STANDALONE_PORTS[0], false);
+        string key = Guid.NewGuid().ToString();
+        Assert.Equal("OK", await client.SetAsync(key, "value"));
+
+        RequestException exception = await Assert.ThrowsAsync<RequestException>(() => client.CustomCommand(["LPUSH", key, "value"]));
+        Assert.Equal(RequestErrorKind.ExtensionError, exception.ErrorKind);
+        Assert.Equal("WRONGTYPE", exception.Code);
+        Assert.Null(exception.Address);
+    }
 }
@@ -0,0 +1,357 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use crate::request::parse_protobuf;
+use crate::value::{
+    error_detail_into_raw, error_message_into_raw, value_to_command_response, CommandResponse,
+};
+use glide_core::client::Client as GlideClient;
+use glide_core::connection_request;
+use glide_core::errors::{self, RequestErrorKind, RequestErrorType};
+use glide_core::runtime_registry::{RuntimeConfig, SharedRuntime};
+use glide_core::ConnectionRequest;
+use redis::{ErrorKind, PushInfo, RedisResult, Value};
+use std::ffi::c_void;
+use std::future::Future;
+use std::os::raw::c_char;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// Success callback that is called when a command succeeds.
+///
+/// The callback should be offloaded to a separate thread in order not to exhaust the client's thread pool.
+///
+/// `index` is a baton-pass back to the caller language to uniquely identify the promise.
+/// `message` is the value returned by the command, or null for operations without a value. It is owned by the caller,
+/// and must be freed with [`free_command_response`](crate::free_command_response).
+pub type SuccessCallback =
+    unsafe extern "C" fn(index: usize, message: *const CommandResponse) -> ();
+
+/// Failure callback that is called when a command fails.
+///
+/// The callback should be offloaded to a separate thread in order not to exhaust the client's thread pool.
+///
+/// `index` is a baton-pass back to the caller language to uniquely identify the promise.
+/// `error_message` is owned by the caller, and must be freed with [`free_error_message`](crate::free_error_message).
+/// `error_type` is the type of error returned by glide-core, depending on the `RedisError` returned.
+/// `error_kind` is the kind of the `RedisError`.
+/// `error_code` is the error code sent by the server, e.g. `MOVED` or `WRONGTYPE`, or null if there is none.
+/// `error_address` is the address that a `MOVED` or `ASK` error redirects to, or null for other errors.
+/// Non-null `error_code` and `error_address` are owned by the caller, and must be freed with
+/// [`free_error_message`](crate::free_error_message).
+pub type FailureCallback = unsafe extern "C" fn(
+    index: usize,
+    error_message: *const c_char,
+    error_type: RequestErrorType,
+    error_kind: RequestErrorKind,
+    error_code: *const c_char,
+    error_address: *const c_char,
+) -> ();
+
+/// Push callback that is called for every push notification received by the client, such as Pub/Sub messages.
+///
+/// `client_ptr` is the handle of the client that received the notification.
+/// `message` is a `Push` response, whose `kind_value` is the push kind (e.g. `message`, `pmessage` or `subscribe`), and whose `array_value` is the notification data.
+/// The `message` is owned by the caller, and must be freed with [`free_command_response`](crate::free_command_response).
+///
+/// Ordering and backpressure:
+/// * Notifications are delivered one at a time, in the order they were received from each connection.
+///   In cluster mode there is no ordering between notifications received from different nodes.
+/// * The callback is called on one of the client's worker threads. It should hand the message off and return quickly,
+///   rather than process it synchronously.
+/// * Notifications are buffered in an unbounded queue until the callback accepts them, so they are never dropped,
+///   but a callback that can't keep up with the incoming notifications causes unbounded memory growth.
+/// * Notifications that arrive before the client is created, such as the confirmations of the subscriptions
+///   configured in the `ConnectionRequest`, are delivered once the client is created.
+pub type PushCallback =
+    unsafe extern "C" fn(client_ptr: usize, message: *const CommandResponse) -> ();
+
+/// The callbacks that report the results of a client's requests.
+#[derive(Clone, Copy)]
+pub struct Callbacks {
+    pub success: SuccessCallback,
+    pub failure: FailureCallback,
+}
+
+impl Callbacks {
+    /// Passes `result` to the success or the failure callback, along with `index`.
+    pub fn report(&self, index: usize, result: RedisResult<Value>) {
+        match result {
+            Ok(value) => {
+                let response = Box::into_raw(Box::new(value_to_command_response(value)));
+                unsafe { (self.success)(index, response) };
+            }
+            Err(err) => {
+                let message = error_message_into_raw(&err);
+                let code = error_detail_into_raw(errors::server_error_code(&err));
+                let address = error_detail_into_raw(errors::error_address(&err));
+                unsafe {
+                    (self.failure)(
+                        index,
+                        message,
+                        errors::error_type(&err),
+                        errors::error_kind(&err),
+                        code,
+                        address,
+                    )
+                };
+            }
+        }
+    }
+
+    /// Calls the success callback with a null response, for operations that don't return a value.
+    pub fn report_done(&self, index: usize) {
+        unsafe { (self.success)(index, std::ptr::null()) };
+    }
+}
+
+/// A client, along with the runtime it runs on and the callbacks that report its results.
+///
+/// The runtime is shared with the other clients whose [`RuntimeConfig`] has the same thread name, and is shut down
+/// once the last of them is destroyed.
+///
+/// Wrappers hand out the pointer returned from [`ClientAdapter::create`] as the client handle.
+pub struct ClientAdapter {
+    client: GlideClient,
+    callbacks: Callbacks,
+    runtime: SharedRuntime,
+}
+
+impl ClientAdapter {
+    /// Creates a client configured by a serialized `ConnectionRequest`, and returns its handle.
+    /// The handle must be freed with [`ClientAdapter::destroy`].
+    ///
+    /// If `push_callback` is set, push notifications are delivered to it. See [`PushCallback`].
+    pub fn create(
+        connection_request_bytes: &[u8],
+        callbacks: Callbacks,
+        runtime_config: &RuntimeConfig,
+        push_callback: Option<PushCallback>,
+    ) -> Result<*const c_void, String> {
+        let request =
+            parse_protobuf::<connection_request::ConnectionRequest>(connection_request_bytes)
+                .map_err(|err| errors::error_message(&err))?;
+        let runtime = SharedRuntime::acquire(runtime_config).map_err(|err| {
+            let redis_error = err.into();
+            errors::error_message(&redis_error)
+        })?;
+
+        let (push_tx, push_rx) = match push_callback {
+            Some(_) => {
+                let (push_tx, push_rx) = mpsc::unbounded_channel();
+                (Some(push_tx), Some(push_rx))
+            }
+            None => (None, None),
+        };
+        let client = runtime
+            .block_on(GlideClient::new(ConnectionRequest::from(request), push_tx))
+            .map_err(|err| err.to_string())?;
+
+        let adapter = Box::into_raw(Box::new(ClientAdapter {
+            client,
+            callbacks,
+            runtime,
+        }));
+        if let (Some(push_callback), Some(push_rx)) = (push_callback, push_rx) {
+            // The delivery task only needs the handle of the client, which exists only now.
+            // Notifications received until this point are buffered in the channel.
+            let client_ptr = adapter as usize;
+            unsafe { &*adapter }.runtime.spawn(push_delivery_loop(
+                push_rx,
+                push_callback,
+                client_ptr,
+            ));
+        }
+        Ok(adapter as *const c_void)
+    }
+
+    /// Returns the client of a handle.
+    ///
+    /// # Safety
+    ///
+    /// * `client_ptr` must be a handle returned from [`ClientAdapter::create`], which wasn't destroyed yet.
+    pub unsafe fn from_ptr<'a>(client_ptr: *const c_void) -> &'a ClientAdapter {
+        assert!(!client_ptr.is_null());
+        unsafe { &*(client_ptr as *const ClientAdapter) }
+    }
+
+    /// Frees the client of a handle. Requests that are still running are dropped without calling their callbacks.
+    ///
+    /// # Safety
+    ///
+    /// * `client_ptr` must be a handle returned from [`ClientAdapter::create`]. It can only be destroyed once.
+    pub unsafe fn destroy(client_ptr: *const c_void) {
+        assert!(!client_ptr.is_null());
+        let adapter = unsafe { Box::from_raw(client_ptr as *mut ClientAdapter) };
+        let ClientAdapter {
+            client, runtime, ..
+        } = *adapter;
+        // The connections are dropped in the runtime's context, since dropping them might spawn tasks.
+        let runtime_handle = runtime.handle().enter();
+        drop(client);
+        drop(runtime_handle);
+        drop(runtime);
+    }
+
+    pub fn client(&self) -> &GlideClient {
+        &self.client
+    }
+
+    pub fn callbacks(&self) -> Callbacks {
+        self.callbacks
+    }
+
+    pub fn runtime(&self) -> &SharedRuntime {
+        &self.runtime
+    }
+
+    /// Runs `request` on the client's runtime, and reports its result with `index`.
+    pub fn execute<F, Fut>(&self, index: usize, request: F)
+    where
+        F: FnOnce(GlideClient) -> Fut,
+        Fut: Future<Output = RedisResult<Value>> + Send + 'static,
+    {
+        let callbacks = self.callbacks;
+        let request = request(self.client.clone());
+        self.runtime.spawn(async move {
+            callbacks.report(index, request.await);
+        });
+    }
+
+    /// Runs `request` on the client's runtime, and blocks the calling thread until it completes.
+    ///
+    /// The request is bounded by the client's request timeout, like any other request, so the call doesn't block
+    /// for longer than that. It can be called concurrently from any number of threads, but not from an async
+    /// context, such as one of the client's callbacks.
+    pub fn execute_blocking<F, Fut>(&self, request: F) -> RedisResult<Value>
+    where
+        F: FnOnce(GlideClient) -> Fut,
+        Fut: Future<Output = RedisResult<Value>> + Send + 'static,
+    {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return Err((
+                ErrorKind::ClientError,
+                "Blocking requests can't be sent from an async context",
+            )
+                .into());
+        }
+        let request = request(self.client.clone());
+        let (result_tx, result_rx) = oneshot::channel();
+        self.runtime.spawn(async move {
+            let _ = result_tx.send(request.await);
+        });
+        result_rx.blocking_recv().unwrap_or_else(|_| {
+            Err((
+                ErrorKind::RequestCancelled,
+                "The request was dropped before completing",
+            )
+                .into())
+        })
+    }
+
+    /// Reports a result that is known without running a request, such as an invalid request.
+    pub fn report(&self, index: usize, result: RedisResult<Value>) {
+        self.callbacks.report(index, result);
+    }
+
+    /// Gracefully closes the client, without freeing it. See [`GlideClient::close`].
+    /// The success callback is called with a null response once the client is closed.
+    pub fn drain(&self, index: usize, drain_timeout: Option<Duration>) {
+        let callbacks = self.callbacks;
+        let client = self.client.clone();
+        self.runtime.spawn(async move {
+            client.close(drain_timeout).await;
+            callbacks.report_done(index);
+        });
+    }
+}
+
+async fn push_delivery_loop(
+    mut push_rx: mpsc::UnboundedReceiver<PushInfo>,
+    push_callback: PushCallback,
+    client_ptr: usize,
+) {
+    // The loop ends once all the senders, which are owned by the client's connections, are dropped.
+    while let Some(push_msg) = push_rx.recv().await {
+        let message = value_to_command_response(Value::Push {
+            kind: push_msg.kind,
+            data: push_msg.data,
+        });
+        unsafe { push_callback(client_ptr, Box::into_raw(Box::new(message))) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{recording_callbacks, wait_for_result, CallbackResult, RecordedError};
+    use redis::ErrorKind;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn test_callbacks_report_results() {
+        let callbacks = recording_callbacks();
+        callbacks.report(1, Ok(Value::Int(5)));
+        callbacks.report_done(2);
+        callbacks.report(
+            3,
+            Err((ErrorKind::RequestCancelled, "Request cancelled").into()),
+        );
+
+        assert_eq!(
+            wait_for_result(1, TIMEOUT),
+            Some(CallbackResult::Success(Some(Value::Int(5))))
+        );
+        assert_eq!(
+            wait_for_result(2, TIMEOUT),
+            Some(CallbackResult::Success(None))
+        );
+        assert_eq!(
+            wait_for_result(3, TIMEOUT),
+            Some(CallbackResult::Failure(RecordedError {
+                message: errors::error_message(
+                    &(ErrorKind::RequestCancelled, "Request cancelled").into()
+                ),
+                error_type: RequestErrorType::Cancelled as u32,
+                error_kind: RequestErrorKind::RequestCancelled as u32,
+                code: None,
+                address: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_callbacks_report_error_details() {
+        let callbacks = recording_callbacks();
+        callbacks.report(
+            4,
+            Err((
+                ErrorKind::Moved,
+                "An error was signalled by the server",
+                "123 127.0.0.1:6380".to_string(),
+            )
+                .into()),
+        );
+
+        let Some(CallbackResult::Failure(error)) = wait_for_result(4, TIMEOUT) else {
+            panic!("expected a failure");
+        };
+        assert_eq!(error.error_kind, RequestErrorKind::Moved as u32);
+        assert_eq!(error.code.as_deref(), Some("MOVED"));
+        assert_eq!(error.address.as_deref(), Some("127.0.0.1:6380"));
+    }
+
+    #[test]
+    fn test_create_fails_on_invalid_request() {
+        let result = ClientAdapter::create(
+            &[0xff],
+            recording_callbacks(),
+            &RuntimeConfig {
+                thread_name: "glide-ffi test".to_string(),
+                worker_threads: Some(1),
+            },
+            None,
+        );
+        assert!(result.unwrap_err().contains("ConnectionRequest"));
+    }
+}
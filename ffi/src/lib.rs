@@ -0,0 +1,26 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Building blocks for wrappers that call glide-core through a C ABI.
+//!
+//! A wrapper crate exports its own `extern "C"` functions, and uses this crate for everything but the
+//! marshalling of its arguments: creating clients with their own runtimes, parsing protobuf requests,
+//! reporting results through callbacks, and converting `redis::Value`s into [`CommandResponse`]s.
+//!
+//! The C declarations of the shared types are generated with `cbindgen --config cbindgen.toml --crate glide-ffi --output glide_ffi.h`.
+//! Wrappers that generate their own header with `parse_deps` should include `glide-ffi` in their parsed crates.
+
+mod client;
+mod request;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod value;
+
+pub use client::{Callbacks, ClientAdapter, FailureCallback, PushCallback, SuccessCallback};
+pub use glide_core::runtime_registry::RuntimeConfig;
+pub use request::{
+    bytes_from_raw, get_cluster_scan, get_command, get_route, get_transaction, parse_protobuf,
+};
+pub use value::{
+    free_command_response, free_command_result, free_error_message, value_to_command_response,
+    CommandResponse, CommandResult, ResponseType,
+};
@@ -0,0 +1,175 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use glide_core::cluster_scan_container::get_cluster_scan_cursor;
+use glide_core::command_request::{command, ClusterScan, Routes, Transaction};
+use glide_core::request_type::RequestType;
+use protobuf::MessageFull;
+use redis::cluster_routing::RoutingInfo;
+use redis::{ClusterScanArgs, Cmd, ErrorKind, Pipeline, RedisError, RedisResult, ScanStateRC};
+
+fn invalid_request(description: String) -> RedisError {
+    (
+        ErrorKind::ClientError,
+        "Received invalid request",
+        description,
+    )
+        .into()
+}
+
+/// Returns the bytes at `ptr`. A zero `len` returns an empty slice, even if `ptr` is null.
+///
+/// # Safety
+///
+/// * Unless `len` is 0, `ptr` must point to `len` consecutive properly initialized bytes, which are valid for the lifetime `'a`.
+pub unsafe fn bytes_from_raw<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(ptr, len) }
+    }
+}
+
+/// Parses a serialized protobuf message.
+pub fn parse_protobuf<T: MessageFull>(bytes: &[u8]) -> RedisResult<T> {
+    T::parse_from_bytes(bytes).map_err(|err| {
+        invalid_request(format!(
+            "Failed to parse `{}`: {err}",
+            T::descriptor().name()
+        ))
+    })
+}
+
+/// Returns the routing info of a serialized `Routes` message. Empty bytes route the request by its keys.
+pub fn get_route(route_bytes: &[u8], cmd: Option<&Cmd>) -> RedisResult<Option<RoutingInfo>> {
+    if route_bytes.is_empty() {
+        return Ok(None);
+    }
+    let route = parse_protobuf::<Routes>(route_bytes)?;
+    Ok(glide_core::routes::get_route(Some(route), cmd)?)
+}
+
+/// Builds the command of `request_type` with the given arguments.
+pub fn get_command<'a>(
+    request_type: RequestType,
+    args: impl IntoIterator<Item = &'a [u8]>,
+) -> RedisResult<Cmd> {
+    let Some(mut cmd) = request_type.get_command() else {
+        return Err(invalid_request(format!(
+            "Received invalid request type: {}",
+            request_type as u32
+        )));
+    };
+    for arg in args {
+        cmd.arg(arg);
+    }
+    if cmd.args_iter().next().is_none() {
+        return Err(invalid_request(
+            "Received command without a command name or arguments".into(),
+        ));
+    }
+    Ok(cmd)
+}
+
+/// Builds an atomic pipeline from the commands of `transaction`.
+pub fn get_transaction(transaction: &Transaction) -> RedisResult<Pipeline> {
+    let mut pipeline = Pipeline::with_capacity(transaction.commands.len());
+    pipeline.atomic();
+    for request in &transaction.commands {
+        let args = match &request.args {
+            Some(command::Args::ArgsArray(args)) => &args.args,
+            // Pointers to Rust-owned argument vectors are only produced by the socket based wrappers.
+            _ => {
+                return Err(invalid_request(
+                    "Transaction commands must have an arguments array".into(),
+                ))
+            }
+        };
+        let request_type: RequestType = request.request_type.into();
+        pipeline.add_command(get_command(
+            request_type,
+            args.iter().map(|arg| arg.as_ref()),
+        )?);
+    }
+    Ok(pipeline)
+}
+
+/// Returns the scan state and arguments of a cluster scan iteration. An empty cursor starts a new scan.
+pub fn get_cluster_scan(scan: ClusterScan) -> RedisResult<(ScanStateRC, ClusterScanArgs)> {
+    let cursor = scan.cursor.to_string();
+    let scan_state = if cursor.is_empty() {
+        ScanStateRC::new()
+    } else {
+        get_cluster_scan_cursor(cursor)?
+    };
+    let mut args_builder =
+        ClusterScanArgs::builder().allow_non_covered_slots(scan.allow_non_covered_slots);
+    if let Some(match_pattern) = scan.match_pattern {
+        args_builder = args_builder.with_match_pattern::<Vec<u8>>(match_pattern.to_vec());
+    }
+    if let Some(count) = scan.count {
+        args_builder = args_builder.with_count(count as u32);
+    }
+    if let Some(object_type) = scan.object_type {
+        args_builder = args_builder.with_object_type(object_type.to_string().into());
+    }
+    Ok((scan_state, args_builder.build()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glide_core::command_request::{Command, SimpleRoutes};
+    use protobuf::Message;
+    use redis::cluster_routing::SingleNodeRoutingInfo;
+
+    fn set_command(args: Option<command::Args>) -> Command {
+        let mut command = Command::new();
+        command.request_type = glide_core::command_request::RequestType::Set.into();
+        command.args = args;
+        command
+    }
+
+    #[test]
+    fn test_get_command() {
+        let cmd = get_command(RequestType::Get, [b"key".as_slice()]).unwrap();
+        assert_eq!(
+            cmd.get_packed_command(),
+            b"*2\r\n$3\r\nGET\r\n$3\r\nkey\r\n"
+        );
+        assert!(get_command(RequestType::CustomCommand, []).is_err());
+    }
+
+    #[test]
+    fn test_get_route() {
+        assert_eq!(get_route(&[], None).unwrap(), None);
+
+        let mut route = Routes::new();
+        route.set_simple_routes(SimpleRoutes::Random);
+        assert_eq!(
+            get_route(&route.write_to_bytes().unwrap(), None).unwrap(),
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
+        );
+
+        let err = get_route(&[0xff], None).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+    }
+
+    #[test]
+    fn test_get_transaction() {
+        let mut args = command::ArgsArray::new();
+        args.args = vec!["key".into(), "value".into()];
+        let mut transaction = Transaction::new();
+        transaction
+            .commands
+            .push(set_command(Some(command::Args::ArgsArray(args))));
+
+        let pipeline = get_transaction(&transaction).unwrap();
+        assert_eq!(
+            pipeline.get_packed_pipeline(),
+            b"*1\r\n$5\r\nMULTI\r\n*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n*1\r\n$4\r\nEXEC\r\n"
+        );
+
+        transaction.commands.push(set_command(None));
+        assert!(get_transaction(&transaction).is_err());
+    }
+}
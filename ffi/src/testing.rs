@@ -0,0 +1,213 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! A test harness for wrappers: callbacks that record the results they receive, and conversion of
+//! [`CommandResponse`]s back into `redis::Value`s, so tests can assert on the values a wrapper would see.
+//!
+//! Results are recorded in a process-wide table keyed by the callback index, so concurrent tests must use distinct indices.
+
+use crate::client::Callbacks;
+use crate::value::{free_command_response, free_error_message, CommandResponse, ResponseType};
+use redis::{PushKind, Value, VerbatimFormat};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::slice::from_raw_parts;
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use glide_core::errors::{RequestErrorKind, RequestErrorType};
+
+/// A result received by the recording callbacks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallbackResult {
+    /// The success callback was called. A null response is recorded as `None`.
+    Success(Option<Value>),
+    /// The failure callback was called.
+    Failure(RecordedError),
+}
+
+/// The arguments the failure callback was called with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedError {
+    pub message: String,
+    /// The numeric `RequestErrorType`.
+    pub error_type: u32,
+    /// The numeric `RequestErrorKind`.
+    pub error_kind: u32,
+    pub code: Option<String>,
+    pub address: Option<String>,
+}
+
+static RESULTS: Mutex<Option<HashMap<usize, CallbackResult>>> = Mutex::new(None);
+static RESULT_RECORDED: Condvar = Condvar::new();
+
+fn record(index: usize, result: CallbackResult) {
+    let mut results = RESULTS.lock().unwrap();
+    results
+        .get_or_insert_with(HashMap::new)
+        .insert(index, result);
+    RESULT_RECORDED.notify_all();
+}
+
+unsafe extern "C" fn recording_success_callback(index: usize, message: *const CommandResponse) {
+    let value = (!message.is_null()).then(|| command_response_to_value(unsafe { &*message }));
+    unsafe { free_command_response(message as *mut CommandResponse) };
+    record(index, CallbackResult::Success(value));
+}
+
+unsafe extern "C" fn recording_failure_callback(
+    index: usize,
+    error_message: *const c_char,
+    error_type: RequestErrorType,
+    error_kind: RequestErrorKind,
+    error_code: *const c_char,
+    error_address: *const c_char,
+) {
+    let message = unsafe { take_error_string(error_message) }.unwrap_or_default();
+    let code = unsafe { take_error_string(error_code) };
+    let address = unsafe { take_error_string(error_address) };
+    record(
+        index,
+        CallbackResult::Failure(RecordedError {
+            message,
+            error_type: error_type as u32,
+            error_kind: error_kind as u32,
+            code,
+            address,
+        }),
+    );
+}
+
+/// Copies and frees a string passed to the failure callback, or returns `None` if it's null.
+unsafe fn take_error_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    let string = unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe { free_error_message(ptr as *mut c_char) };
+    Some(string)
+}
+
+/// Callbacks that record the results they receive. See [`wait_for_result`].
+pub fn recording_callbacks() -> Callbacks {
+    Callbacks {
+        success: recording_success_callback,
+        failure: recording_failure_callback,
+    }
+}
+
+/// Waits up to `timeout` for a result with the given callback index, and removes it from the recorded results.
+pub fn wait_for_result(index: usize, timeout: Duration) -> Option<CallbackResult> {
+    let results = RESULTS.lock().unwrap();
+    let (mut results, _) = RESULT_RECORDED
+        .wait_timeout_while(results, timeout, |results| {
+            !results
+                .as_ref()
+                .is_some_and(|results| results.contains_key(&index))
+        })
+        .unwrap();
+    results.as_mut()?.remove(&index)
+}
+
+fn bytes(ptr: *const c_char, len: i64) -> Vec<u8> {
+    if ptr.is_null() {
+        return Vec::new();
+    }
+    unsafe { from_raw_parts(ptr as *const u8, len as usize) }.to_vec()
+}
+
+fn elements(ptr: *const CommandResponse, len: i64) -> &'static [CommandResponse] {
+    if ptr.is_null() {
+        return &[];
+    }
+    unsafe { from_raw_parts(ptr, len as usize) }
+}
+
+fn text(ptr: *const c_char, len: i64) -> String {
+    String::from_utf8_lossy(&bytes(ptr, len)).into_owned()
+}
+
+fn map_entries(response: &CommandResponse) -> Vec<(Value, Value)> {
+    elements(response.array_value, response.array_value_len)
+        .iter()
+        .map(|entry| unsafe {
+            (
+                command_response_to_value(&*entry.map_key),
+                command_response_to_value(&*entry.map_value),
+            )
+        })
+        .collect()
+}
+
+fn push_kind(kind: String) -> PushKind {
+    match kind.as_str() {
+        "invalidate" => PushKind::Invalidate,
+        "message" => PushKind::Message,
+        "pmessage" => PushKind::PMessage,
+        "smessage" => PushKind::SMessage,
+        "unsubscribe" => PushKind::Unsubscribe,
+        "punsubscribe" => PushKind::PUnsubscribe,
+        "sunsubscribe" => PushKind::SUnsubscribe,
+        "subscribe" => PushKind::Subscribe,
+        "psubscribe" => PushKind::PSubscribe,
+        "ssubscribe" => PushKind::SSubscribe,
+        "disconnection" => PushKind::Disconnection,
+        "topology_change" => PushKind::TopologyChange,
+        _ => PushKind::Other(kind),
+    }
+}
+
+/// Converts a response back into the value it was created from. Simple strings and `OK` are
+/// returned as bulk strings, since wrappers can't tell them apart.
+pub fn command_response_to_value(response: &CommandResponse) -> Value {
+    match response.response_type {
+        ResponseType::Null => Value::Nil,
+        ResponseType::Int => Value::Int(response.int_value),
+        ResponseType::Float => Value::Double(response.float_value),
+        ResponseType::Bool => Value::Boolean(response.bool_value),
+        ResponseType::String if response.kind_value.is_null() => {
+            Value::BulkString(bytes(response.string_value, response.string_value_len))
+        }
+        ResponseType::String => Value::VerbatimString {
+            format: match text(response.kind_value, response.kind_value_len).as_str() {
+                "txt" => VerbatimFormat::Text,
+                "mkd" => VerbatimFormat::Markdown,
+                format => VerbatimFormat::Unknown(format.to_string()),
+            },
+            text: text(response.string_value, response.string_value_len),
+        },
+        ResponseType::BigNumber => {
+            let number = text(response.string_value, response.string_value_len);
+            redis::parse_redis_value(format!("({number}\r\n").as_bytes())
+                .expect("Invalid big number")
+        }
+        ResponseType::Array => Value::Array(
+            elements(response.array_value, response.array_value_len)
+                .iter()
+                .map(command_response_to_value)
+                .collect(),
+        ),
+        ResponseType::Sets => Value::Set(
+            elements(response.sets_value, response.sets_value_len)
+                .iter()
+                .map(command_response_to_value)
+                .collect(),
+        ),
+        ResponseType::Map => Value::Map(map_entries(response)),
+        ResponseType::Attribute => Value::Attribute {
+            data: Box::new(command_response_to_value(unsafe {
+                &*response.attribute_data
+            })),
+            attributes: map_entries(response),
+        },
+        ResponseType::Push => Value::Push {
+            kind: push_kind(text(response.kind_value, response.kind_value_len)),
+            data: elements(response.array_value, response.array_value_len)
+                .iter()
+                .map(command_response_to_value)
+                .collect(),
+        },
+    }
+}
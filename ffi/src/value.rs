@@ -0,0 +1,485 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use glide_core::errors::{self, RequestErrorKind, RequestErrorType};
+use redis::{RedisError, RedisResult, Value};
+use std::ffi::CString;
+use std::os::raw::{c_char, c_double};
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseType {
+    #[default]
+    Null = 0,
+    Int = 1,
+    Float = 2,
+    Bool = 3,
+    String = 4,
+    Array = 5,
+    Map = 6,
+    Sets = 7,
+    /// An arbitrary precision integer, passed in `string_value` as its decimal representation.
+    BigNumber = 8,
+    /// A value with out-of-band attributes. See `attribute_data`.
+    Attribute = 9,
+    /// A push notification. The kind is passed in `kind_value`, and the data in `array_value`.
+    Push = 10,
+}
+
+/// The struct represents the response of the command.
+///
+/// It will have one of the value populated depending on the return type of the command.
+/// Lengths are 64 bit on all platforms, so the layout is the same for every wrapper.
+///
+/// The struct is owned by the caller once it is passed to a callback, and is freed with [`free_command_response`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct CommandResponse {
+    pub response_type: ResponseType,
+    pub int_value: i64,
+    pub float_value: c_double,
+    pub bool_value: bool,
+
+    /// Below two values are related to each other.
+    /// `string_value` represents the string.
+    /// `string_value_len` represents the length of the string.
+    pub string_value: *mut c_char,
+    pub string_value_len: i64,
+
+    /// Below two values are related to each other.
+    /// `array_value` represents the array of CommandResponse.
+    /// `array_value_len` represents the length of the array.
+    pub array_value: *mut CommandResponse,
+    pub array_value_len: i64,
+
+    /// Below two values represent the Map structure inside CommandResponse.
+    /// The map is transformed into an array of (map_key: CommandResponse, map_value: CommandResponse) and passed in `array_value`.
+    /// These are represented as pointers as the map can be null (optionally present).
+    pub map_key: *mut CommandResponse,
+    pub map_value: *mut CommandResponse,
+
+    /// Below two values are related to each other.
+    /// `sets_value` represents the set of CommandResponse.
+    /// `sets_value_len` represents the length of the set.
+    pub sets_value: *mut CommandResponse,
+    pub sets_value_len: i64,
+
+    /// Below two values are related to each other.
+    /// `kind_value` represents the format of a verbatim string (e.g. `txt`), or the kind of a push notification (e.g. `message`).
+    /// `kind_value_len` represents the length of the string.
+    pub kind_value: *mut c_char,
+    pub kind_value_len: i64,
+
+    /// The value that the attributes of an `Attribute` response are attached to.
+    /// The attributes themselves are passed in `array_value`, in the same layout as a `Map`.
+    pub attribute_data: *mut CommandResponse,
+}
+
+impl Default for CommandResponse {
+    fn default() -> Self {
+        CommandResponse {
+            response_type: ResponseType::default(),
+            int_value: 0,
+            float_value: 0.0,
+            bool_value: false,
+            string_value: std::ptr::null_mut(),
+            string_value_len: 0,
+            array_value: std::ptr::null_mut(),
+            array_value_len: 0,
+            map_key: std::ptr::null_mut(),
+            map_value: std::ptr::null_mut(),
+            sets_value: std::ptr::null_mut(),
+            sets_value_len: 0,
+            kind_value: std::ptr::null_mut(),
+            kind_value_len: 0,
+            attribute_data: std::ptr::null_mut(),
+        }
+    }
+}
+
+fn vec_into_raw<T>(vec: Vec<T>) -> (*mut T, i64) {
+    let slice = Box::into_raw(vec.into_boxed_slice());
+    (slice as *mut T, slice.len() as i64)
+}
+
+/// # Safety
+///
+/// `ptr` and `len` must be null, or returned from `vec_into_raw`. They can only be freed once.
+unsafe fn vec_from_raw<T>(ptr: *mut T, len: i64) -> Vec<T> {
+    if ptr.is_null() {
+        return Vec::new();
+    }
+    unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len as usize)) }.into_vec()
+}
+
+fn string_response(bytes: Vec<u8>, response_type: ResponseType) -> CommandResponse {
+    let (string_value, string_value_len) = vec_into_raw(bytes);
+    CommandResponse {
+        response_type,
+        string_value: string_value as *mut c_char,
+        string_value_len,
+        ..Default::default()
+    }
+}
+
+fn convert_values(values: Vec<Value>) -> (*mut CommandResponse, i64) {
+    vec_into_raw(values.into_iter().map(value_to_command_response).collect())
+}
+
+/// Converts map entries into an array of `CommandResponse`s, each holding a single `map_key` and `map_value`.
+fn convert_map_entries(map: Vec<(Value, Value)>) -> (*mut CommandResponse, i64) {
+    vec_into_raw(
+        map.into_iter()
+            .map(|(key, value)| CommandResponse {
+                map_key: Box::into_raw(Box::new(value_to_command_response(key))),
+                map_value: Box::into_raw(Box::new(value_to_command_response(value))),
+                ..Default::default()
+            })
+            .collect(),
+    )
+}
+
+fn with_kind(mut response: CommandResponse, kind: String) -> CommandResponse {
+    let (kind_value, kind_value_len) = vec_into_raw(kind.into_bytes());
+    response.kind_value = kind_value as *mut c_char;
+    response.kind_value_len = kind_value_len;
+    response
+}
+
+/// Converts `value` into a `CommandResponse` without losing type information.
+/// The returned response must be freed with [`free_command_response`], after being boxed.
+pub fn value_to_command_response(value: Value) -> CommandResponse {
+    match value {
+        Value::Nil => CommandResponse::default(),
+        Value::Int(int_value) => CommandResponse {
+            response_type: ResponseType::Int,
+            int_value,
+            ..Default::default()
+        },
+        Value::Double(float_value) => CommandResponse {
+            response_type: ResponseType::Float,
+            float_value,
+            ..Default::default()
+        },
+        Value::Boolean(bool_value) => CommandResponse {
+            response_type: ResponseType::Bool,
+            bool_value,
+            ..Default::default()
+        },
+        Value::BulkString(bytes) => string_response(bytes, ResponseType::String),
+        Value::SimpleString(text) => string_response(text.into_bytes(), ResponseType::String),
+        Value::Okay => string_response(b"OK".to_vec(), ResponseType::String),
+        // Verbatim strings are passed as strings, so callers that expect a string don't have to handle them separately.
+        Value::VerbatimString { format, text } => with_kind(
+            string_response(text.into_bytes(), ResponseType::String),
+            format.to_string(),
+        ),
+        Value::BigNumber(number) => {
+            string_response(number.to_string().into_bytes(), ResponseType::BigNumber)
+        }
+        Value::Array(values) => {
+            let (array_value, array_value_len) = convert_values(values);
+            CommandResponse {
+                response_type: ResponseType::Array,
+                array_value,
+                array_value_len,
+                ..Default::default()
+            }
+        }
+        Value::Set(values) => {
+            let (sets_value, sets_value_len) = convert_values(values);
+            CommandResponse {
+                response_type: ResponseType::Sets,
+                sets_value,
+                sets_value_len,
+                ..Default::default()
+            }
+        }
+        Value::Map(map) => {
+            let (array_value, array_value_len) = convert_map_entries(map);
+            CommandResponse {
+                response_type: ResponseType::Map,
+                array_value,
+                array_value_len,
+                ..Default::default()
+            }
+        }
+        Value::Attribute { data, attributes } => {
+            let (array_value, array_value_len) = convert_map_entries(attributes);
+            CommandResponse {
+                response_type: ResponseType::Attribute,
+                array_value,
+                array_value_len,
+                attribute_data: Box::into_raw(Box::new(value_to_command_response(*data))),
+                ..Default::default()
+            }
+        }
+        Value::Push { kind, data } => {
+            let (array_value, array_value_len) = convert_values(data);
+            with_kind(
+                CommandResponse {
+                    response_type: ResponseType::Push,
+                    array_value,
+                    array_value_len,
+                    ..Default::default()
+                },
+                kind.to_string(),
+            )
+        }
+    }
+}
+
+fn free_command_response_elements(response: CommandResponse) {
+    unsafe {
+        drop(vec_from_raw(
+            response.string_value,
+            response.string_value_len,
+        ));
+        drop(vec_from_raw(response.kind_value, response.kind_value_len));
+        for element in vec_from_raw(response.array_value, response.array_value_len)
+            .into_iter()
+            .chain(vec_from_raw(response.sets_value, response.sets_value_len))
+        {
+            free_command_response_elements(element);
+        }
+        for nested in [
+            response.map_key,
+            response.map_value,
+            response.attribute_data,
+        ] {
+            free_command_response(nested);
+        }
+    }
+}
+
+/// Deallocates a boxed `CommandResponse` and everything it points to. Null pointers are ignored.
+///
+/// # Safety
+///
+/// * `command_response_ptr` must be null, or obtained from `Box::into_raw` of a response returned from [`value_to_command_response`],
+///   such as the responses passed to [`SuccessCallback`](crate::SuccessCallback) and [`PushCallback`](crate::PushCallback).
+/// * A response can only be freed once.
+pub unsafe fn free_command_response(command_response_ptr: *mut CommandResponse) {
+    if !command_response_ptr.is_null() {
+        free_command_response_elements(*unsafe { Box::from_raw(command_response_ptr) });
+    }
+}
+
+/// Converts an error into a C string, owned by the caller. It must be freed with [`free_error_message`].
+pub(crate) fn error_message_into_raw(err: &RedisError) -> *mut c_char {
+    CString::new(errors::error_message(err))
+        .unwrap_or_else(|_| c"Couldn't convert error message to CString".into())
+        .into_raw()
+}
+
+/// Converts an optional error detail into a C string, owned by the caller, or null if it's missing.
+/// It must be freed with [`free_error_message`].
+pub(crate) fn error_detail_into_raw(detail: Option<&str>) -> *mut c_char {
+    detail
+        .and_then(|detail| CString::new(detail).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// The result of a blocking request. Exactly one of `response` and `error_message` is set.
+///
+/// The struct is owned by the caller, and is freed with [`free_command_result`].
+#[repr(C)]
+pub struct CommandResult {
+    pub response: *mut CommandResponse,
+    pub error_message: *mut c_char,
+    /// The type of the error. Only meaningful if `error_message` is set.
+    pub error_type: RequestErrorType,
+    /// The kind of the error. Only meaningful if `error_message` is set.
+    pub error_kind: RequestErrorKind,
+    /// The error code sent by the server, e.g. `MOVED` or `WRONGTYPE`, or null if there is none.
+    pub error_code: *mut c_char,
+    /// The address that a `MOVED` or `ASK` error redirects to, or null for other errors.
+    pub error_address: *mut c_char,
+}
+
+impl From<RedisResult<Value>> for CommandResult {
+    fn from(result: RedisResult<Value>) -> Self {
+        match result {
+            Ok(value) => CommandResult {
+                response: Box::into_raw(Box::new(value_to_command_response(value))),
+                error_message: std::ptr::null_mut(),
+                error_type: RequestErrorType::Unspecified,
+                error_kind: RequestErrorKind::Unknown,
+                error_code: std::ptr::null_mut(),
+                error_address: std::ptr::null_mut(),
+            },
+            Err(err) => CommandResult {
+                response: std::ptr::null_mut(),
+                error_message: error_message_into_raw(&err),
+                error_type: errors::error_type(&err),
+                error_kind: errors::error_kind(&err),
+                error_code: error_detail_into_raw(errors::server_error_code(&err)),
+                error_address: error_detail_into_raw(errors::error_address(&err)),
+            },
+        }
+    }
+}
+
+/// Deallocates a boxed `CommandResult`, along with its response and error details. Null pointers are ignored.
+///
+/// # Safety
+///
+/// * `command_result_ptr` must be null, or obtained from `Box::into_raw` of a [`CommandResult`]. It can only be freed once.
+pub unsafe fn free_command_result(command_result_ptr: *mut CommandResult) {
+    if !command_result_ptr.is_null() {
+        let command_result = unsafe { Box::from_raw(command_result_ptr) };
+        unsafe {
+            free_command_response(command_result.response);
+            free_error_message(command_result.error_message);
+            free_error_message(command_result.error_code);
+            free_error_message(command_result.error_address);
+        }
+    }
+}
+
+/// Deallocates an error message, code or address passed to a [`FailureCallback`](crate::FailureCallback). Null pointers are ignored.
+///
+/// # Safety
+///
+/// * `error_message` must be null, or obtained from a [`FailureCallback`](crate::FailureCallback). It can only be freed once.
+pub unsafe fn free_error_message(error_message: *mut c_char) {
+    if !error_message.is_null() {
+        drop(unsafe { CString::from_raw(error_message) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::command_response_to_value;
+
+    fn parse(resp: &[u8]) -> Value {
+        redis::parse_redis_value(resp).unwrap()
+    }
+
+    fn round_trip(value: Value) -> Value {
+        let response = Box::into_raw(Box::new(value_to_command_response(value)));
+        let converted = command_response_to_value(unsafe { &*response });
+        unsafe { free_command_response(response) };
+        converted
+    }
+
+    #[test]
+    fn test_values_round_trip() {
+        for value in [
+            Value::Nil,
+            Value::Int(-5),
+            Value::Double(1.5),
+            Value::Boolean(true),
+            Value::BulkString(b"bulk\0string".to_vec()),
+            parse(b"=15\r\ntxt:Some string\r\n"),
+            parse(b"(3492890328409238509324850943850943825024385\r\n"),
+            Value::Array(vec![Value::Int(1), Value::Nil]),
+            Value::Set(vec![Value::Int(3)]),
+            Value::Map(vec![(
+                Value::BulkString(b"key".to_vec()),
+                Value::Array(vec![Value::Double(2.5)]),
+            )]),
+            Value::Attribute {
+                data: Box::new(Value::BulkString(b"data".to_vec())),
+                attributes: vec![(Value::BulkString(b"ttl".to_vec()), Value::Int(3600))],
+            },
+            Value::Push {
+                kind: redis::PushKind::Message,
+                data: vec![
+                    Value::BulkString(b"channel".to_vec()),
+                    Value::BulkString(b"payload".to_vec()),
+                ],
+            },
+        ] {
+            assert_eq!(round_trip(value.clone()), value);
+        }
+    }
+
+    #[test]
+    fn test_simple_strings_are_converted_to_strings() {
+        assert_eq!(
+            round_trip(Value::SimpleString("simple".into())),
+            Value::BulkString(b"simple".to_vec())
+        );
+        assert_eq!(round_trip(Value::Okay), Value::BulkString(b"OK".to_vec()));
+    }
+
+    #[test]
+    fn test_verbatim_string_is_a_string_with_format() {
+        let response = Box::into_raw(Box::new(value_to_command_response(parse(
+            b"=15\r\ntxt:Some string\r\n",
+        ))));
+        let (response_type, format) = unsafe {
+            let response = &*response;
+            (
+                response.response_type,
+                std::slice::from_raw_parts(
+                    response.kind_value as *const u8,
+                    response.kind_value_len as usize,
+                ),
+            )
+        };
+        assert_eq!(response_type, ResponseType::String);
+        assert_eq!(format, b"txt");
+        unsafe { free_command_response(response) };
+    }
+
+    #[test]
+    fn test_command_result() {
+        let result = Box::into_raw(Box::new(CommandResult::from(Ok(Value::Int(3)))));
+        unsafe {
+            assert!((*result).error_message.is_null());
+            assert_eq!(
+                command_response_to_value(&*(*result).response),
+                Value::Int(3)
+            );
+            free_command_result(result);
+        }
+
+        let err: RedisError = (redis::ErrorKind::ExecAbortError, "Transaction aborted").into();
+        let result = Box::into_raw(Box::new(CommandResult::from(Err(err))));
+        unsafe {
+            assert!((*result).response.is_null());
+            assert!(matches!((*result).error_type, RequestErrorType::ExecAbort));
+            assert_eq!((*result).error_kind, RequestErrorKind::ExecAbortError);
+            assert_eq!(
+                std::ffi::CStr::from_ptr((*result).error_code).to_str(),
+                Ok("EXECABORT")
+            );
+            assert!((*result).error_address.is_null());
+            assert!(std::ffi::CStr::from_ptr((*result).error_message)
+                .to_string_lossy()
+                .contains("Transaction aborted"));
+            free_command_result(result);
+        }
+
+        let err: RedisError = (
+            redis::ErrorKind::Moved,
+            "An error was signalled by the server",
+            "123 127.0.0.1:6380".to_string(),
+        )
+            .into();
+        let result = Box::into_raw(Box::new(CommandResult::from(Err(err))));
+        unsafe {
+            assert_eq!((*result).error_kind, RequestErrorKind::Moved);
+            assert_eq!(
+                std::ffi::CStr::from_ptr((*result).error_code).to_str(),
+                Ok("MOVED")
+            );
+            assert_eq!(
+                std::ffi::CStr::from_ptr((*result).error_address).to_str(),
+                Ok("127.0.0.1:6380")
+            );
+            free_command_result(result);
+        }
+    }
+
+    #[test]
+    fn test_free_null_pointers() {
+        unsafe {
+            free_command_response(std::ptr::null_mut());
+            free_command_result(std::ptr::null_mut());
+            free_error_message(std::ptr::null_mut());
+        }
+    }
+}
@@ -7,11 +7,12 @@ use crate::connection::{
     resp2_is_pub_sub_state_cleared, resp3_is_pub_sub_state_cleared, ConnectionAddr, ConnectionInfo,
     Msg, RedisConnectionInfo,
 };
+use crate::parser::StreamedValue;
 #[cfg(feature = "tokio-comp")]
 use crate::parser::ValueCodec;
 use crate::types::{ErrorKind, FromRedisValue, RedisError, RedisFuture, RedisResult, Value};
 use crate::{from_owned_redis_value, ProtocolVersion, ToRedisArgs};
-use ::tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use ::tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 #[cfg(feature = "tokio-comp")]
 use ::tokio::net::lookup_host;
 use combine::{parser::combinator::AnySendSyncPartialState, stream::PointerOffset};
@@ -84,6 +85,84 @@ where
         crate::parser::parse_redis_value_async(&mut self.decoder, &mut self.con).await
     }
 
+    /// Sends the command, and streams the payload of a bulk string reply to `on_chunk` in chunks of up to `chunk_size` bytes,
+    /// instead of buffering the whole value in memory. This is meant for commands such as `GET` or `GETRANGE` on very large values.
+    ///
+    /// Replies that aren't bulk strings are returned as [`StreamedValue::Value`]. If `on_chunk` returns an error,
+    /// the rest of the value is drained from the connection and the error is returned.
+    ///
+    /// Streaming is only supported on this dedicated connection. The multiplexed and cluster connections share
+    /// their reader between concurrent requests, so they, and the clients built on them, always buffer whole values.
+    pub async fn req_streamed<F>(
+        &mut self,
+        cmd: &Cmd,
+        chunk_size: usize,
+        mut on_chunk: F,
+    ) -> RedisResult<StreamedValue>
+    where
+        F: FnMut(&[u8]) -> RedisResult<()>,
+    {
+        if self.pubsub {
+            self.exit_pubsub().await?;
+        }
+        self.buf.clear();
+        cmd.write_packed_command(&mut self.buf);
+        self.con.write_all(&self.buf).await?;
+        loop {
+            match crate::parser::parse_redis_value_streamed_async(
+                &mut self.decoder,
+                &mut self.con,
+                chunk_size,
+                &mut on_chunk,
+            )
+            .await?
+            {
+                StreamedValue::Value(Value::Push { .. }) => continue,
+                value => return Ok(value),
+            }
+        }
+    }
+
+    /// Sends the command with an additional trailing argument of `arg_len` bytes, which are copied from `arg_reader`
+    /// to the connection in chunks, instead of being buffered in memory. This is meant for commands such as `SET` or `APPEND`
+    /// with very large values.
+    ///
+    /// If `arg_reader` ends before `arg_len` bytes were read, an error is returned, and the connection must not be used again,
+    /// since the server is still waiting for the rest of the argument.
+    ///
+    /// Like [`Connection::req_streamed`], this is only supported on a dedicated connection.
+    pub async fn req_with_streamed_arg<R>(
+        &mut self,
+        cmd: &Cmd,
+        arg_len: usize,
+        arg_reader: &mut R,
+    ) -> RedisResult<Value>
+    where
+        R: AsyncRead + Unpin,
+    {
+        if self.pubsub {
+            self.exit_pubsub().await?;
+        }
+        self.buf.clear();
+        cmd.write_packed_command_with_streamed_arg(&mut self.buf, arg_len);
+        self.con.write_all(&self.buf).await?;
+        let copied = ::tokio::io::copy(&mut arg_reader.take(arg_len as u64), &mut self.con).await?;
+        if copied != arg_len as u64 {
+            return Err(RedisError::from((
+                ErrorKind::IoError,
+                "Streamed argument ended early",
+                format!("expected {arg_len} bytes, got {copied}"),
+            )));
+        }
+        self.con.write_all(b"\r\n").await?;
+        loop {
+            match self.read_response().await? {
+                Value::Push { .. } => continue,
+                val => return Ok(val),
+            }
+        }
+    }
+
     /// Brings [`Connection`] out of `PubSub` mode.
     ///
     /// This will unsubscribe this [`Connection`] from all subscriptions.
@@ -79,6 +79,27 @@ pub trait ConnectionLike {
         count: usize,
     ) -> RedisFuture<'a, Vec<Value>>;
 
+    /// Sends the commands of `cmd` in a single write, and reads the result of each of them.
+    /// Unlike `req_packed_commands`, the error of a command doesn't fail the others.
+    /// Connections that can't write several requests at once send the commands one by one.
+    /// The pipeline shouldn't be atomic.
+    #[doc(hidden)]
+    fn req_packed_commands_separately<'a>(
+        &'a mut self,
+        cmd: &'a crate::Pipeline,
+    ) -> RedisFuture<'a, Vec<RedisResult<Value>>>
+    where
+        Self: Send,
+    {
+        Box::pin(async move {
+            let mut results = Vec::new();
+            for cmd in cmd.cmd_iter() {
+                results.push(self.req_packed_command(cmd).await);
+            }
+            Ok(results)
+        })
+    }
+
     /// Returns the database this connection is bound to.  Note that this
     /// information might be unreliable because it's initially cached and
     /// also might be incorrect if the connection like object is not
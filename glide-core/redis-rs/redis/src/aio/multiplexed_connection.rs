@@ -72,6 +72,8 @@ struct PipelineMessage<S> {
     output: PipelineOutput,
     // If `None`, this is a single request, not a pipeline of multiple requests.
     pipeline_response_count: Option<usize>,
+    // The outputs of the single requests that follow the first request in `input`, each receiving its own response.
+    following_outputs: Vec<PipelineOutput>,
 }
 
 /// Wrapper around a `Stream + Sink` where each item sent through the `Sink` results in one or more
@@ -241,18 +243,20 @@ where
             input,
             output,
             pipeline_response_count,
+            following_outputs,
         }: PipelineMessage<SinkItem>,
     ) -> Result<(), Self::Error> {
         // If there is nothing to receive our output we do not need to send the message as it is
         // ambiguous whether the message will be sent anyway. Helps shed some load on the
         // connection.
-        if output.is_closed() {
+        if output.is_closed() && following_outputs.iter().all(|output| output.is_closed()) {
             return Ok(());
         }
 
         let self_ = self.as_mut().project();
 
         if let Some(err) = self_.error.take() {
+            send_error_to_following_outputs(following_outputs, &err);
             let _ = output.send(Err(err));
             return Err(());
         }
@@ -266,9 +270,16 @@ where
                 };
 
                 self_.in_flight.push_back(entry);
+                self_
+                    .in_flight
+                    .extend(following_outputs.into_iter().map(|output| InFlight {
+                        output,
+                        response_aggregate: ResponseAggregate::SingleCommand,
+                    }));
                 Ok(())
             }
             Err(err) => {
+                send_error_to_following_outputs(following_outputs, &err);
                 let _ = output.send(Err(err));
                 Err(())
             }
@@ -306,6 +317,16 @@ where
     }
 }
 
+fn send_error_to_following_outputs(outputs: Vec<PipelineOutput>, err: &RedisError) {
+    for output in outputs {
+        let _ = output.send(Err(RedisError::from((
+            err.kind(),
+            "Failed to send the request to the server",
+            err.to_string(),
+        ))));
+    }
+}
+
 impl<SinkItem> Pipeline<SinkItem>
 where
     SinkItem: Send + 'static,
@@ -369,18 +390,50 @@ where
                 input,
                 pipeline_response_count,
                 output: sender,
+                following_outputs: Vec::new(),
             })
             .await
-            .map_err(|err| {
-                // If an error occurs here, it means the request never reached the server, as guaranteed
-                // by the 'send' function. Since the server did not receive the data, it is safe to retry
-                // the request.
-                RedisError::from((
-                    crate::ErrorKind::FatalSendError,
-                    "Failed to send the request to the server",
-                    err.to_string(),
-                ))
-            })?;
+            .map_err(fatal_send_error)?;
+        Self::receive(receiver, timeout).await
+    }
+
+    // Sends `input`, which holds `request_count` single requests, and returns the result of each of them.
+    async fn send_recv_each(
+        &mut self,
+        input: SinkItem,
+        request_count: usize,
+        timeout: Duration,
+    ) -> Vec<RedisResult<Value>> {
+        let (mut senders, receivers): (Vec<_>, Vec<_>) =
+            (0..request_count).map(|_| oneshot::channel()).unzip();
+        let output = senders.remove(0);
+        if let Err(err) = self
+            .sender
+            .send(PipelineMessage {
+                input,
+                pipeline_response_count: None,
+                output,
+                following_outputs: senders,
+            })
+            .await
+        {
+            let err = err.to_string();
+            return (0..request_count)
+                .map(|_| Err(fatal_send_error(err.clone())))
+                .collect();
+        }
+        futures_util::future::join_all(
+            receivers
+                .into_iter()
+                .map(|receiver| Self::receive(receiver, timeout)),
+        )
+        .await
+    }
+
+    async fn receive(
+        receiver: oneshot::Receiver<RedisResult<Value>>,
+        timeout: Duration,
+    ) -> Result<Value, RedisError> {
         match Runtime::locate().timeout(timeout, receiver).await {
             Ok(Ok(result)) => result,
             Ok(Err(err)) => {
@@ -408,6 +461,16 @@ where
     }
 }
 
+// If an error occurs when sending a message to the pipeline, it means the request never reached the server, as
+// guaranteed by the 'send' function. Since the server did not receive the data, it is safe to retry the request.
+fn fatal_send_error(err: impl ToString) -> RedisError {
+    RedisError::from((
+        crate::ErrorKind::FatalSendError,
+        "Failed to send the request to the server",
+        err.to_string(),
+    ))
+}
+
 /// A connection object which can be cloned, allowing requests to be be sent concurrently
 /// on the same underlying connection (tcp/unix socket).
 #[derive(Clone)]
@@ -575,6 +638,38 @@ impl MultiplexedConnection {
         }
     }
 
+    /// Sends the commands of `cmd` in a single write, and reads the result of each of them.
+    /// Unlike [`Self::send_packed_commands`], the error of a command doesn't fail the others.
+    pub async fn send_packed_commands_separately(
+        &mut self,
+        cmd: &crate::Pipeline,
+    ) -> Vec<RedisResult<Value>> {
+        let request_count = cmd.cmd_iter().count();
+        if request_count == 0 {
+            return Vec::new();
+        }
+        let input = cmd
+            .cmd_iter()
+            .flat_map(|cmd| cmd.get_packed_command())
+            .collect();
+        let results = self
+            .pipeline
+            .send_recv_each(input, request_count, self.response_timeout)
+            .await;
+        if self.protocol != ProtocolVersion::RESP2
+            && results
+                .iter()
+                .any(|result| matches!(result, Err(e) if e.is_connection_dropped()))
+        {
+            // Notify the PushManager that the connection was lost
+            self.push_manager.try_send_raw(&Value::Push {
+                kind: PushKind::Disconnection,
+                data: vec![],
+            });
+        }
+        results
+    }
+
     /// Sets `PushManager` of connection
     pub async fn set_push_manager(&mut self, push_manager: PushManager) {
         self.push_manager = push_manager.clone();
@@ -702,6 +797,13 @@ impl ConnectionLike for MultiplexedConnection {
         (async move { self.send_packed_commands(cmd, offset, count).await }).boxed()
     }
 
+    fn req_packed_commands_separately<'a>(
+        &'a mut self,
+        cmd: &'a crate::Pipeline,
+    ) -> RedisFuture<'a, Vec<RedisResult<Value>>> {
+        (async move { Ok(self.send_packed_commands_separately(cmd).await) }).boxed()
+    }
+
     fn get_db(&self) -> i64 {
         self.db
     }
@@ -782,3 +884,43 @@ impl MultiplexedConnection {
         self.push_manager.clone()
     }
 }
+
+#[cfg(all(test, feature = "tokio-comp"))]
+mod tests {
+    use super::*;
+    use ::tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn requests_sent_in_one_write_get_their_own_results() {
+        let (client, mut server) = ::tokio::io::duplex(1024);
+        let codec = ValueCodec::default()
+            .framed(client)
+            .and_then(|msg| async move { msg });
+        let (mut pipeline, driver) = Pipeline::new(codec, None);
+        ::tokio::spawn(driver);
+
+        let mut input = cmd("ASKING").get_packed_command();
+        input.extend(cmd("INCR").arg("key").get_packed_command());
+        input.extend(cmd("LPUSH").arg("key").arg("value").get_packed_command());
+        let expected_input = input.clone();
+        let server = async move {
+            let mut buffer = vec![0; 1024];
+            let read = server.read(&mut buffer).await.unwrap();
+            assert_eq!(&buffer[..read], expected_input.as_slice());
+            server
+                .write_all(b"+OK\r\n:1\r\n-WRONGTYPE Operation against a key holding the wrong kind of value\r\n")
+                .await
+                .unwrap();
+            server
+        };
+        let (results, _server) = futures_util::join!(
+            pipeline.send_recv_each(input, 3, Duration::from_secs(5)),
+            server
+        );
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], Ok(Value::Okay));
+        assert_eq!(results[1], Ok(Value::Int(1)));
+        assert_eq!(results[2].as_ref().unwrap_err().code(), Some("WRONGTYPE"));
+    }
+}
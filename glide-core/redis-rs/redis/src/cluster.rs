@@ -35,14 +35,16 @@
 //!     .expire(key, 60).ignore()
 //!     .query(&mut connection).unwrap();
 //! ```
-pub use crate::cluster_client::{ClusterClient, ClusterClientBuilder};
+pub use crate::cluster_client::{AddressMapper, ClusterClient, ClusterClientBuilder};
 use crate::cluster_pipeline::UNROUTABLE_ERROR;
 pub use crate::cluster_pipeline::{cluster_pipe, ClusterPipeline};
 use crate::cluster_routing::{
-    MultipleNodeRoutingInfo, ResponsePolicy, Routable, SingleNodeRoutingInfo,
+    MultipleNodeRoutingInfo, ResponsePolicy, Routable, SingleNodeRoutingInfo, SlotAddr,
 };
 use crate::cluster_slotmap::SlotMap;
-use crate::cluster_topology::parse_and_count_slots;
+use crate::cluster_topology::{
+    map_topology_addresses, parse_and_count_shards, parse_and_count_slots,
+};
 use crate::cmd::{cmd, Cmd};
 use crate::connection::{
     connect, Connection, ConnectionAddr, ConnectionInfo, ConnectionLike, RedisConnectionInfo,
@@ -381,13 +383,35 @@ where
             "didn't get any slots from server".to_string(),
         )));
         for (addr, conn) in samples {
-            let value = conn.req_command(&slot_cmd())?;
             let addr = addr.split(':').next().ok_or(RedisError::from((
                 ErrorKind::ClientError,
                 "can't parse node address",
             )))?;
-            match parse_and_count_slots(&value, self.cluster_params.tls, addr).map(|slots_data| {
-                SlotMap::new(slots_data.1, self.cluster_params.read_from_replicas.clone())
+            let shards = if self.cluster_params.use_cluster_shards {
+                match conn.req_command(&shards_cmd()) {
+                    Ok(value) => Some(value),
+                    // Servers older than 7.0 don't support `CLUSTER SHARDS`.
+                    Err(err) if err.kind() == ErrorKind::ResponseError => None,
+                    Err(err) => return Err(err),
+                }
+            } else {
+                None
+            };
+            let parsed = match shards {
+                Some(value) => parse_and_count_shards(&value, self.cluster_params.tls, addr),
+                None => {
+                    let value = conn.req_command(&slot_cmd())?;
+                    parse_and_count_slots(&value, self.cluster_params.tls, addr)
+                        .map(|(count, slots)| (count, slots, Default::default()))
+                }
+            };
+            match parsed.map(|(_, mut slots, nodes_info)| {
+                let nodes_info = match &self.cluster_params.address_mapper {
+                    Some(mapper) => map_topology_addresses(mapper, &mut slots, nodes_info),
+                    None => nodes_info,
+                };
+                SlotMap::new(slots, self.cluster_params.read_from_replicas.clone())
+                    .with_nodes_info(nodes_info)
             }) {
                 Ok(new_slots) => {
                     result = Ok(new_slots);
@@ -713,6 +737,7 @@ where
                         Redirect::Moved(addr) => (addr, false),
                         Redirect::Ask(addr) => (addr, true),
                     };
+                    let addr = self.cluster_params.map_address(&addr);
                     let conn = self.get_connection_by_addr(&mut connections, &addr)?;
                     if is_asking {
                         // if we are in asking mode we want to feed a single
@@ -727,11 +752,18 @@ where
                         SingleNodeRoutingInfo::SpecificNode(route) => {
                             self.get_connection(&mut connections, route)?
                         }
+                        // Replication offsets aren't tracked by this client, so only the primary is known to qualify.
+                        SingleNodeRoutingInfo::SpecificNodeAtOffset { route, .. } => self
+                            .get_connection(
+                                &mut connections,
+                                &Route::new(route.slot(), SlotAddr::Master),
+                            )?,
                         SingleNodeRoutingInfo::RandomPrimary => {
                             self.get_connection(&mut connections, &Route::new_random_primary())?
                         }
                         SingleNodeRoutingInfo::ByAddress { host, port } => {
-                            let address = format!("{host}:{port}");
+                            let address =
+                                self.cluster_params.map_address(&format!("{host}:{port}"));
                             let conn = self.get_connection_by_addr(&mut connections, &address)?;
                             (address, conn)
                         }
@@ -1031,6 +1063,12 @@ pub(crate) fn slot_cmd() -> Cmd {
     cmd
 }
 
+pub(crate) fn shards_cmd() -> Cmd {
+    let mut cmd = Cmd::new();
+    cmd.arg("CLUSTER").arg("SHARDS");
+    cmd
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,6 +5,7 @@ use crate::cluster_topology::TopologyHash;
 use dashmap::DashMap;
 use futures::FutureExt;
 use rand::seq::IteratorRandom;
+use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -139,6 +140,10 @@ pub(crate) struct ConnectionsContainer<Connection> {
     pub(crate) slot_map: SlotMap,
     read_from_replica_strategy: ReadFromReplicaStrategy,
     topology_hash: TopologyHash,
+    /// Replicas that lag too far behind their primary to serve reads.
+    pub(crate) stale_replicas: HashSet<String>,
+    /// The last known replication offset of each node, if replication offsets are tracked.
+    pub(crate) replication_offsets: HashMap<String, i64>,
 }
 
 impl<Connection> Drop for ConnectionsContainer<Connection> {
@@ -155,6 +160,8 @@ impl<Connection> Default for ConnectionsContainer<Connection> {
             slot_map: Default::default(),
             read_from_replica_strategy: ReadFromReplicaStrategy::AlwaysFromPrimary,
             topology_hash: 0,
+            stale_replicas: HashSet::new(),
+            replication_offsets: HashMap::new(),
         }
     }
 }
@@ -182,6 +189,8 @@ where
             slot_map,
             read_from_replica_strategy,
             topology_hash,
+            stale_replicas: HashSet::new(),
+            replication_offsets: HashMap::new(),
         }
     }
 
@@ -219,9 +228,22 @@ where
         self.connection_for_address(address).is_some() && self.slot_map.is_primary(address)
     }
 
+    /// Returns true if the replica can serve reads. If `min_offset` is set, the replica's replication offset
+    /// must be known to have reached it.
+    fn is_replica_available_for_reads(&self, replica: &str, min_offset: Option<i64>) -> bool {
+        self.slot_map.is_available_for_reads(replica)
+            && !self.stale_replicas.contains(replica)
+            && min_offset.map_or(true, |min_offset| {
+                self.replication_offsets
+                    .get(replica)
+                    .map_or(false, |offset| *offset >= min_offset)
+            })
+    }
+
     fn round_robin_read_from_replica(
         &self,
         slot_map_value: &SlotMapValue,
+        min_offset: Option<i64>,
     ) -> Option<ConnectionAndAddress<Connection>> {
         let addrs = &slot_map_value.addrs;
         let initial_index = slot_map_value.last_used_replica.load(Ordering::Relaxed);
@@ -234,8 +256,11 @@ where
                 return self.connection_for_address(addrs.primary().as_str());
             }
             let index = (initial_index + check_count) % addrs.replicas().len();
-            if let Some(connection) = self.connection_for_address(addrs.replicas()[index].as_str())
-            {
+            let replica = &addrs.replicas()[index];
+            if !self.is_replica_available_for_reads(replica, min_offset) {
+                continue;
+            }
+            if let Some(connection) = self.connection_for_address(replica.as_str()) {
                 let _ = slot_map_value.last_used_replica.compare_exchange_weak(
                     initial_index,
                     index,
@@ -253,6 +278,38 @@ where
         &self,
         slot_map_value: &SlotMapValue,
         client_az: String,
+        min_offset: Option<i64>,
+    ) -> Option<ConnectionAndAddress<Connection>> {
+        self.round_robin_read_from_same_az_replica(slot_map_value, &client_az, min_offset)
+            // Attempt a fallback to any available replica or primary if needed.
+            .or_else(|| self.round_robin_read_from_replica(slot_map_value, min_offset))
+    }
+
+    /// Returns the connection of a replica in the same availability zone as `client_az` in round robin strategy if exists,
+    /// if not, the primary's connection if it's in the same availability zone,
+    /// and otherwise falls back to any available replica or primary.
+    pub(crate) fn round_robin_read_from_az_replica_or_primary(
+        &self,
+        slot_map_value: &SlotMapValue,
+        client_az: String,
+        min_offset: Option<i64>,
+    ) -> Option<ConnectionAndAddress<Connection>> {
+        self.round_robin_read_from_same_az_replica(slot_map_value, &client_az, min_offset)
+            .or_else(|| {
+                let primary = slot_map_value.addrs.primary();
+                let (address, connection_details) =
+                    self.connection_details_for_address(primary.as_str())?;
+                (self.az_for_address(&address).as_deref() == Some(client_az.as_str()))
+                    .then_some((address, connection_details.conn))
+            })
+            .or_else(|| self.round_robin_read_from_replica(slot_map_value, min_offset))
+    }
+
+    fn round_robin_read_from_same_az_replica(
+        &self,
+        slot_map_value: &SlotMapValue,
+        client_az: &str,
+        min_offset: Option<i64>,
     ) -> Option<ConnectionAndAddress<Connection>> {
         let addrs = &slot_map_value.addrs;
         let initial_index = slot_map_value.last_used_replica.load(Ordering::Relaxed);
@@ -262,19 +319,21 @@ where
             retries = retries.saturating_add(1);
             // Looped through all replicas; no connected replica found in the same availability zone.
             if retries > addrs.replicas().len() {
-                // Attempt a fallback to any available replica or primary if needed.
-                return self.round_robin_read_from_replica(slot_map_value);
+                return None;
             }
 
             // Calculate index based on initial index and check count.
             let index = (initial_index + retries) % addrs.replicas().len();
             let replica = &addrs.replicas()[index];
+            if !self.is_replica_available_for_reads(replica, min_offset) {
+                continue;
+            }
 
             // Check if this replica’s availability zone matches the user’s availability zone.
             if let Some((address, connection_details)) =
                 self.connection_details_for_address(replica.as_str())
             {
-                if self.az_for_address(&address) == Some(client_az.clone()) {
+                if self.az_for_address(&address).as_deref() == Some(client_az) {
                     // Attempt to update `latest_used_replica` with the index of this replica.
                     let _ = slot_map_value.last_used_replica.compare_exchange_weak(
                         initial_index,
@@ -288,7 +347,11 @@ where
         }
     }
 
-    fn lookup_route(&self, route: &Route) -> Option<ConnectionAndAddress<Connection>> {
+    fn lookup_route(
+        &self,
+        route: &Route,
+        min_offset: Option<i64>,
+    ) -> Option<ConnectionAndAddress<Connection>> {
         let slot_map_value = self.slot_map.slot_value_for_route(route)?;
         let addrs = &slot_map_value.addrs;
         if addrs.replicas().is_empty() {
@@ -304,12 +367,19 @@ where
                     self.connection_for_address(addrs.primary().as_str())
                 }
                 ReadFromReplicaStrategy::RoundRobin => {
-                    self.round_robin_read_from_replica(slot_map_value)
+                    self.round_robin_read_from_replica(slot_map_value, min_offset)
                 }
                 ReadFromReplicaStrategy::AZAffinity(az) => self
                     .round_robin_read_from_replica_with_az_awareness(
                         slot_map_value,
                         az.to_string(),
+                        min_offset,
+                    ),
+                ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary(az) => self
+                    .round_robin_read_from_az_replica_or_primary(
+                        slot_map_value,
+                        az.to_string(),
+                        min_offset,
                     ),
             },
             // when the user strategy per command is replica_preffered
@@ -318,8 +388,15 @@ where
                     .round_robin_read_from_replica_with_az_awareness(
                         slot_map_value,
                         az.to_string(),
+                        min_offset,
                     ),
-                _ => self.round_robin_read_from_replica(slot_map_value),
+                ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary(az) => self
+                    .round_robin_read_from_az_replica_or_primary(
+                        slot_map_value,
+                        az.to_string(),
+                        min_offset,
+                    ),
+                _ => self.round_robin_read_from_replica(slot_map_value, min_offset),
             },
         }
     }
@@ -328,9 +405,27 @@ where
         &self,
         route: &Route,
     ) -> Option<ConnectionAndAddress<Connection>> {
-        self.lookup_route(route).or_else(|| {
+        self.connection_for_route_with_min_offset(route, None)
+    }
+
+    /// Like [`Self::connection_for_route`], but reads are only routed to replicas whose replication offset
+    /// is known to have reached `min_offset`, falling back to the primary.
+    pub(crate) fn connection_for_route_at_offset(
+        &self,
+        route: &Route,
+        min_offset: i64,
+    ) -> Option<ConnectionAndAddress<Connection>> {
+        self.connection_for_route_with_min_offset(route, Some(min_offset))
+    }
+
+    fn connection_for_route_with_min_offset(
+        &self,
+        route: &Route,
+        min_offset: Option<i64>,
+    ) -> Option<ConnectionAndAddress<Connection>> {
+        self.lookup_route(route, min_offset).or_else(|| {
             if route.slot_addr() != SlotAddr::Master {
-                self.lookup_route(&Route::new(route.slot(), SlotAddr::Master))
+                self.lookup_route(&Route::new(route.slot(), SlotAddr::Master), None)
             } else {
                 None
             }
@@ -572,6 +667,8 @@ mod tests {
             connection_map,
             read_from_replica_strategy: ReadFromReplicaStrategy::AZAffinity("use-1a".to_string()),
             topology_hash: 0,
+            stale_replicas: HashSet::new(),
+            replication_offsets: HashMap::new(),
         }
     }
 
@@ -628,6 +725,8 @@ mod tests {
             connection_map,
             read_from_replica_strategy: strategy,
             topology_hash: 0,
+            stale_replicas: HashSet::new(),
+            replication_offsets: HashMap::new(),
         }
     }
 
@@ -718,6 +817,90 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn get_connection_for_replica_route_skips_loading_replicas() {
+        let mut container = create_container();
+        let slot_map = std::mem::take(&mut container.slot_map);
+        container.slot_map = slot_map.with_nodes_info(std::collections::HashMap::from([(
+            "replica3-1".to_string(),
+            crate::cluster_topology::NodeInfo {
+                id: "replica3-1-id".to_string(),
+                role: crate::cluster_topology::NodeRole::Replica,
+                health: crate::cluster_topology::NodeHealth::Loading,
+                replication_offset: 0,
+            },
+        )]));
+
+        for _ in 0..3 {
+            assert_eq!(
+                32,
+                container
+                    .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                    .unwrap()
+                    .1
+            );
+        }
+    }
+
+    #[test]
+    fn get_connection_for_replica_route_skips_stale_replicas() {
+        let mut container = create_container();
+        container.stale_replicas = HashSet::from(["replica3-1".to_string()]);
+
+        for _ in 0..3 {
+            assert_eq!(
+                32,
+                container
+                    .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                    .unwrap()
+                    .1
+            );
+        }
+
+        // Reads fall back to the primary when all of the shard's replicas are stale.
+        container.stale_replicas.insert("replica3-2".to_string());
+        assert_eq!(
+            3,
+            container
+                .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                .unwrap()
+                .1
+        );
+    }
+
+    #[test]
+    fn get_connection_for_replica_route_at_offset() {
+        let mut container = create_container();
+        container.replication_offsets = HashMap::from([
+            ("primary3".to_string(), 200),
+            ("replica3-1".to_string(), 100),
+            ("replica3-2".to_string(), 200),
+        ]);
+
+        for _ in 0..3 {
+            assert_eq!(
+                32,
+                container
+                    .connection_for_route_at_offset(
+                        &Route::new(2001, SlotAddr::ReplicaOptional),
+                        150
+                    )
+                    .unwrap()
+                    .1
+            );
+        }
+
+        // Replicas whose offset isn't known don't qualify.
+        container.replication_offsets.remove("replica3-2");
+        assert_eq!(
+            3,
+            container
+                .connection_for_route_at_offset(&Route::new(2001, SlotAddr::ReplicaOptional), 150)
+                .unwrap()
+                .1
+        );
+    }
+
     #[test]
     fn get_primary_connection_for_replica_route_if_no_replicas_were_added() {
         let container = create_container();
@@ -888,6 +1071,41 @@ mod tests {
         assert_eq!(addresses, vec![31, 31, 33, 33]);
     }
 
+    #[test]
+    fn get_connection_for_az_affinity_replicas_and_primary_route() {
+        let mut container = create_container_with_az_strategy(false);
+        container.read_from_replica_strategy =
+            ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary("use-1a".to_string());
+
+        // A replica in the same zone is preferred over the primary.
+        let address = container
+            .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+            .unwrap()
+            .1;
+        assert!(address == 31 || address == 33);
+
+        // No replica of the shard is in the same zone, and neither is the primary.
+        assert_eq!(
+            21,
+            container
+                .connection_for_route(&Route::new(1002, SlotAddr::ReplicaOptional))
+                .unwrap()
+                .1
+        );
+
+        container.connection_map.insert(
+            "primary2".into(),
+            create_cluster_node(2, false, Some("use-1a".to_string())),
+        );
+        assert_eq!(
+            2,
+            container
+                .connection_for_route(&Route::new(1002, SlotAddr::ReplicaOptional))
+                .unwrap()
+                .1
+        );
+    }
+
     #[test]
     fn get_connection_by_address() {
         let container = create_container();
@@ -33,8 +33,10 @@ use crate::{
     client::GlideConnectionOptions,
     cluster_routing::{Routable, RoutingInfo, ShardUpdateResult},
     cluster_topology::{
-        calculate_topology, get_slot, SlotRefreshState, DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES,
-        DEFAULT_REFRESH_SLOTS_RETRY_BASE_DURATION_MILLIS, DEFAULT_REFRESH_SLOTS_RETRY_BASE_FACTOR,
+        calculate_topology, diff_slot_maps, get_slot, NodeConnectionState, ReplicationState,
+        SlotMigration, SlotRefreshState, TopologyEvent, TopologyResponse,
+        DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES, DEFAULT_REFRESH_SLOTS_RETRY_BASE_DURATION_MILLIS,
+        DEFAULT_REFRESH_SLOTS_RETRY_BASE_FACTOR, SLOT_SIZE,
     },
     cmd,
     commands::cluster_scan::{cluster_scan, ClusterScanArgs, ScanStateRC},
@@ -47,7 +49,7 @@ use std::{
     net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::{
-        atomic::{self, AtomicUsize, Ordering},
+        atomic::{self, AtomicBool, AtomicUsize},
         Arc, Mutex,
     },
     task::{self, Poll},
@@ -59,19 +61,20 @@ use tokio::task::JoinHandle;
 
 #[cfg(feature = "tokio-comp")]
 use crate::aio::DisconnectNotifier;
-use telemetrylib::Telemetry;
+use telemetrylib::{Telemetry, TrafficStats, TrafficStatsSnapshot};
 
 use crate::{
     aio::{get_socket_addrs, ConnectionLike, MultiplexedConnection, Runtime},
-    cluster::slot_cmd,
+    cluster::{shards_cmd, slot_cmd},
     cluster_async::connections_logic::{
         get_host_and_port_from_addr, get_or_create_conn, ConnectionFuture, RefreshConnectionType,
     },
-    cluster_client::{ClusterParams, RetryParams},
+    cluster_client::{ClusterParams, ReplicationCheck, RetryParams},
     cluster_routing::{
         self, MultipleNodeRoutingInfo, Redirect, ResponsePolicy, Route, SingleNodeRoutingInfo,
-        SlotAddr,
+        Slot, SlotAddr,
     },
+    cluster_slotmap::SlotMap,
     connection::{PubSubSubscriptionInfo, PubSubSubscriptionKind},
     push_manager::PushInfo,
     Cmd, ConnectionInfo, ErrorKind, IntoConnectionInfo, RedisError, RedisFuture, RedisResult,
@@ -95,7 +98,7 @@ use futures::{future::BoxFuture, prelude::*, ready};
 use pin_project_lite::pin_project;
 use std::sync::RwLock as StdRwLock;
 use tokio::sync::{
-    mpsc,
+    broadcast, mpsc,
     oneshot::{self, Receiver},
     RwLock as TokioRwLock,
 };
@@ -109,11 +112,18 @@ use crate::types::RetryMethod;
 
 pub(crate) const MUTEX_READ_ERR: &str = "Failed to obtain read lock. Poisoned mutex?";
 const MUTEX_WRITE_ERR: &str = "Failed to obtain write lock. Poisoned mutex?";
+// Subscribers that fall further behind than this miss the oldest events.
+const TOPOLOGY_EVENTS_CAPACITY: usize = 128;
 /// This represents an async Cluster connection. It stores the
 /// underlying connections maintained for each node in the cluster, as well
 /// as common parameters for connecting to nodes and executing commands.
 #[derive(Clone)]
-pub struct ClusterConnection<C = MultiplexedConnection>(mpsc::Sender<Message<C>>);
+pub struct ClusterConnection<C = MultiplexedConnection>(
+    mpsc::Sender<Message<C>>,
+    Arc<Notify>,
+    broadcast::Sender<TopologyEvent>,
+    Option<Arc<TrafficStats>>,
+);
 
 impl<C> ClusterConnection<C>
 where
@@ -127,19 +137,55 @@ where
         ClusterConnInner::new(initial_nodes, cluster_params, push_sender)
             .await
             .map(|inner| {
+                let topology_events = inner.inner.topology_events.clone();
+                let traffic_stats = inner.inner.traffic_stats.clone();
                 let (tx, mut rx) = mpsc::channel::<Message<_>>(100);
+                let close_notifier = Arc::new(Notify::new());
+                let closed = close_notifier.clone();
                 let stream = async move {
-                    let _ = stream::poll_fn(move |cx| rx.poll_recv(cx))
+                    let forward = stream::poll_fn(move |cx| rx.poll_recv(cx))
                         .map(Ok)
-                        .forward(inner)
-                        .await;
+                        .forward(inner);
+                    let closed = closed.notified();
+                    futures::pin_mut!(forward, closed);
+                    let _ = futures::future::select(forward, closed).await;
                 };
                 #[cfg(feature = "tokio-comp")]
                 tokio::spawn(stream);
-                ClusterConnection(tx)
+                ClusterConnection(tx, close_notifier, topology_events, traffic_stats)
             })
     }
 
+    /// Subscribes to the changes in the cluster's topology observed by this connection: slots that moved between primaries,
+    /// failovers, nodes that joined or left the cluster, and lost or re-established node connections.
+    ///
+    /// Only events that happen after the call are received. A subscriber that falls behind receives
+    /// [`broadcast::error::RecvError::Lagged`] and misses the oldest events.
+    pub fn subscribe_topology_events(&self) -> broadcast::Receiver<TopologyEvent> {
+        self.2.subscribe()
+    }
+
+    /// Returns the traffic sent to each slot and node so far, if traffic stats were enabled with
+    /// [`ClusterClientBuilder::traffic_stats`](crate::cluster::ClusterClientBuilder::traffic_stats).
+    pub fn traffic_stats(&self) -> Option<TrafficStatsSnapshot> {
+        self.3
+            .as_ref()
+            .map(|traffic_stats| traffic_stats.snapshot())
+    }
+
+    /// Clears the traffic stats collected thus far.
+    pub fn reset_traffic_stats(&self) {
+        if let Some(traffic_stats) = &self.3 {
+            traffic_stats.reset();
+        }
+    }
+
+    /// Stops the background task of the connection, which closes the connections to all cluster nodes,
+    /// including those shared with other clones of this connection. Requests sent after this call fail.
+    pub fn close(&self) {
+        self.1.notify_one();
+    }
+
     /// Special handling for `SCAN` command, using `cluster_scan_with_pattern`.
     /// It is a special case of [`cluster_scan`], with an additional match pattern.
     /// Perform a `SCAN` command on a cluster, using scan state object in order to handle changes in topology
@@ -391,8 +437,23 @@ pub(crate) struct InnerCore<C> {
     subscriptions_by_address: TokioRwLock<HashMap<String, PubSubSubscriptionInfo>>,
     unassigned_subscriptions: TokioRwLock<PubSubSubscriptionInfo>,
     glide_connection_options: GlideConnectionOptions,
+    topology_events: broadcast::Sender<TopologyEvent>,
+    // The nodes whose loss of connection was reported, so that only their reconnection is reported too.
+    disconnected_nodes: Mutex<HashSet<String>>,
+    traffic_stats: Option<Arc<TrafficStats>>,
+    // The slots learned from `ASK` redirects to be migrating, until the topology shows the migration completed.
+    // `CLUSTER SHARDS` doesn't report the importing and migrating states of slots, so they're only learned from
+    // redirects, and `TRYAGAIN` errors during migrations are retried with backoff as any `WaitAndRetry` error.
+    slot_migrations: Mutex<HashMap<u16, SlotMigration>>,
+    // The `ASK`-redirected commands waiting to be sent to each node, in a single write.
+    ask_batches: Mutex<HashMap<String, Vec<AskBatchEntry>>>,
+    // Set once a node rejects `CLUSTER SHARDS`, so that the following refreshes use `CLUSTER SLOTS` right away.
+    cluster_shards_unsupported: AtomicBool,
 }
 
+// A command and where to send its result. The outer error is set when the node couldn't be connected.
+type AskBatchEntry = (Arc<Cmd>, oneshot::Sender<RedisResult<RedisResult<Value>>>);
+
 pub(crate) type Core<C> = Arc<InnerCore<C>>;
 
 impl<C> InnerCore<C>
@@ -410,6 +471,77 @@ where
             .map_err(|_| RedisError::from((ErrorKind::ClientError, MUTEX_READ_ERR)))
     }
 
+    fn is_proxy_mode(&self) -> bool {
+        self.cluster_params.read().expect(MUTEX_READ_ERR).proxy_mode
+    }
+
+    /// Maps an address reported by the cluster, such as a `MOVED` or `ASK` redirect target,
+    /// to the address used as the connection's key.
+    fn map_address(&self, address: &str) -> String {
+        self.cluster_params
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .map_address(address)
+    }
+
+    fn record_slot_migration(&self, slot: u16, source: &str, target: String) {
+        Telemetry::incr_total_ask_redirections(1);
+        let previous = self.slot_migrations.lock().unwrap().insert(
+            slot,
+            SlotMigration {
+                source: source.to_string(),
+                target,
+            },
+        );
+        if previous.is_none() {
+            Telemetry::incr_migrating_slots(1);
+        }
+    }
+
+    /// Forgets the migrations of the slots that are no longer owned by the node they were migrated from.
+    fn complete_slot_migrations(&self) {
+        let connections = self.conn_lock.read().expect(MUTEX_READ_ERR);
+        let mut slot_migrations = self.slot_migrations.lock().unwrap();
+        let migrating_slots = slot_migrations.len();
+        slot_migrations.retain(|slot, migration| {
+            connections
+                .slot_map
+                .shard_addrs_for_slot(*slot)
+                .map_or(false, |addrs| *addrs.primary() == migration.source)
+        });
+        let completed = migrating_slots - slot_migrations.len();
+        if completed > 0 {
+            debug!("{completed} slot migrations completed");
+            Telemetry::decr_migrating_slots(completed);
+        }
+    }
+
+    fn send_topology_events(&self, events: impl IntoIterator<Item = TopologyEvent>) {
+        for event in events {
+            if let TopologyEvent::NodeRemoved(address) = &event {
+                self.disconnected_nodes.lock().unwrap().remove(address);
+            }
+            // Sending fails only when there are no subscribers.
+            let _ = self.topology_events.send(event);
+        }
+    }
+
+    fn set_node_connection_state(&self, address: &str, state: NodeConnectionState) {
+        let changed = {
+            let mut disconnected_nodes = self.disconnected_nodes.lock().unwrap();
+            match state {
+                NodeConnectionState::Connected => disconnected_nodes.remove(address),
+                NodeConnectionState::Disconnected => disconnected_nodes.insert(address.to_string()),
+            }
+        };
+        if changed {
+            self.send_topology_events([TopologyEvent::ConnectionStateChanged {
+                address: address.to_string(),
+                state,
+            }]);
+        }
+    }
+
     fn set_cluster_param<F>(&self, f: F) -> Result<(), RedisError>
     where
         F: FnOnce(&mut ClusterParams),
@@ -491,6 +623,8 @@ pub(crate) struct ClusterConnInner<C> {
     refresh_error: Option<RedisError>,
     // Handler of the periodic check task.
     periodic_checks_handler: Option<JoinHandle<()>>,
+    #[cfg(feature = "tokio-comp")]
+    replication_check_handler: Option<JoinHandle<()>>,
     // Handler of fast connection validation task
     connections_validation_handler: Option<JoinHandle<()>>,
 }
@@ -516,8 +650,15 @@ impl<C> Dispose for ClusterConnInner<C> {
             handle.abort()
         }
 
+        #[cfg(feature = "tokio-comp")]
+        if let Some(handle) = self.replication_check_handler {
+            handle.abort()
+        }
+
         // Reduce the number of clients
         Telemetry::decr_total_clients(1);
+        // Stop counting the migrations observed by this client
+        Telemetry::decr_migrating_slots(self.inner.slot_migrations.lock().unwrap().len());
     }
 }
 
@@ -561,6 +702,10 @@ impl<C> From<InternalSingleNodeRouting<C>> for InternalRoutingInfo<C> {
 pub(crate) enum InternalSingleNodeRouting<C> {
     Random,
     SpecificNode(Route),
+    SpecificNodeAtOffset {
+        route: Route,
+        min_offset: i64,
+    },
     ByAddress(String),
     Connection {
         address: String,
@@ -585,6 +730,9 @@ impl<C> From<SingleNodeRoutingInfo> for InternalSingleNodeRouting<C> {
             SingleNodeRoutingInfo::SpecificNode(route) => {
                 InternalSingleNodeRouting::SpecificNode(route)
             }
+            SingleNodeRoutingInfo::SpecificNodeAtOffset { route, min_offset } => {
+                InternalSingleNodeRouting::SpecificNodeAtOffset { route, min_offset }
+            }
             SingleNodeRoutingInfo::RandomPrimary => {
                 InternalSingleNodeRouting::SpecificNode(Route::new_random_primary())
             }
@@ -626,7 +774,8 @@ fn route_for_pipeline(pipeline: &crate::Pipeline) -> RedisResult<Option<Route>>
         match cluster_routing::RoutingInfo::for_routable(cmd) {
             Some(cluster_routing::RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)) => None,
             Some(cluster_routing::RoutingInfo::SingleNode(
-                SingleNodeRoutingInfo::SpecificNode(route),
+                SingleNodeRoutingInfo::SpecificNode(route)
+                | SingleNodeRoutingInfo::SpecificNodeAtOffset { route, .. },
             )) => Some(route),
             Some(cluster_routing::RoutingInfo::SingleNode(
                 SingleNodeRoutingInfo::RandomPrimary,
@@ -659,6 +808,16 @@ fn route_for_pipeline(pipeline: &crate::Pipeline) -> RedisResult<Option<Route>>
     )
 }
 
+/// Returns the number of bytes in the arguments of `cmd`.
+fn args_bytes(cmd: &Cmd) -> u64 {
+    cmd.args_iter()
+        .map(|arg| match arg {
+            crate::cmd::Arg::Simple(arg) => arg.len() as u64,
+            crate::cmd::Arg::Cursor => 0,
+        })
+        .sum()
+}
+
 fn boxed_sleep(duration: Duration) -> BoxFuture<'static, ()> {
     Box::pin(tokio::time::sleep(duration))
 }
@@ -687,7 +846,8 @@ impl From<String> for OperationTarget {
 /// Represents a node to which a `MOVED` or `ASK` error redirects.
 #[derive(Clone, Debug)]
 pub(crate) struct RedirectNode {
-    /// The address of the redirect node.
+    /// The address of the redirect node, as reported by the server. It's mapped by the
+    /// [`crate::cluster::AddressMapper`], if one is configured, before it's used.
     pub address: String,
     /// The slot of the redirect node.
     pub slot: u16,
@@ -891,20 +1051,28 @@ impl<C> Future for Request<C> {
                 .into();
             }
             RequestStateProj::UpdateMoved { future } => {
-                if let Err(err) = ready!(future.poll(cx)) {
-                    // Updating the slot map based on the MOVED error is an optimization.
-                    // If it fails, proceed by retrying the request with the redirected node,
-                    // and allow the slot refresh task to correct the slot map.
-                    info!(
-                        "Failed to update the slot map based on the received MOVED error.
-                        Error: {err:?}"
-                    );
-                }
-                if let Some(request) = self.project().request.take() {
-                    return Next::Retry { request }.into();
-                } else {
+                let update_result = ready!(future.poll(cx));
+                let Some(request) = self.project().request.take() else {
                     return Next::Done.into();
-                }
+                };
+                return match update_result {
+                    // Updating the slot map based on the MOVED error spares a full slot refresh.
+                    Ok(()) => Next::Retry { request }.into(),
+                    // Otherwise, proceed by retrying the request with the redirected node,
+                    // and let a slot refresh correct the slot map.
+                    Err(err) => {
+                        info!(
+                            "Failed to update the slot map based on the received MOVED error.
+                            Error: {err:?}"
+                        );
+                        Next::RefreshSlots {
+                            request: Some(request),
+                            sleep_duration: None,
+                            moved_redirect: None,
+                        }
+                        .into()
+                    }
+                };
             }
             _ => panic!("Request future must be Some"),
         };
@@ -999,14 +1167,21 @@ impl<C> Future for Request<C> {
                             err.redirect_node()
                                 .map(|(node, _slot)| Redirect::Moved(node.to_string())),
                         );
+                        // A node that redirects to itself contradicts the slot map in a way the
+                        // redirect alone can't patch, so the slot map is refreshed in full.
+                        let moved_redirect = RedirectNode::from_option_tuple(redirect_node)
+                            .filter(|redirect| redirect.address != address);
                         Next::RefreshSlots {
                             request: Some(request),
                             sleep_duration: None,
-                            moved_redirect: RedirectNode::from_option_tuple(redirect_node),
+                            moved_redirect,
                         }
                         .into()
                     }
                     RetryMethod::WaitAndRetry => {
+                        if err.kind() == ErrorKind::TryAgain {
+                            Telemetry::incr_total_tryagain_retries(1);
+                        }
                         let sleep_duration = this.retry_params.wait_time_for_retry(request.retry);
                         // Sleep and retry.
                         this.future.set(RequestState::Sleep {
@@ -1088,6 +1263,7 @@ where
         let discover_az = matches!(
             cluster_params.read_from_replicas,
             crate::cluster_slotmap::ReadFromReplicaStrategy::AZAffinity(_)
+                | crate::cluster_slotmap::ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary(_)
         );
 
         let glide_connection_options = GlideConnectionOptions {
@@ -1126,6 +1302,19 @@ where
             ),
             subscriptions_by_address: TokioRwLock::new(Default::default()),
             glide_connection_options,
+            topology_events: broadcast::channel(TOPOLOGY_EVENTS_CAPACITY).0,
+            disconnected_nodes: Mutex::new(HashSet::new()),
+            traffic_stats: cluster_params.traffic_stats.map(|config| {
+                Arc::new(match config.hot_keys {
+                    Some((capacity, sample_rate)) => {
+                        TrafficStats::with_hot_keys(capacity, sample_rate)
+                    }
+                    None => TrafficStats::default(),
+                })
+            }),
+            slot_migrations: Mutex::new(HashMap::new()),
+            ask_batches: Mutex::new(HashMap::new()),
+            cluster_shards_unsupported: AtomicBool::new(false),
         });
         let mut connection = ClusterConnInner {
             inner,
@@ -1133,6 +1322,8 @@ where
             refresh_error: None,
             state: ConnectionState::PollComplete,
             periodic_checks_handler: None,
+            #[cfg(feature = "tokio-comp")]
+            replication_check_handler: None,
             connections_validation_handler: None,
         };
         Self::refresh_slots_and_subscriptions_with_retries(
@@ -1141,7 +1332,8 @@ where
         )
         .await?;
 
-        if let Some(duration) = topology_checks_interval {
+        // Behind a proxy, the topology reported by the cluster is the proxy itself, so it isn't checked.
+        if let Some(duration) = topology_checks_interval.filter(|_| !cluster_params.proxy_mode) {
             let periodic_task =
                 ClusterConnInner::periodic_topology_check(connection.inner.clone(), duration);
             #[cfg(feature = "tokio-comp")]
@@ -1161,6 +1353,13 @@ where
             }
         }
 
+        #[cfg(feature = "tokio-comp")]
+        if let Some(replication_check) = cluster_params.replication_check {
+            connection.replication_check_handler = Some(tokio::spawn(
+                ClusterConnInner::replication_check(connection.inner.clone(), replication_check),
+            ));
+        }
+
         // New client added
         Telemetry::incr_total_clients(1);
         Ok(Disposable::new(connection))
@@ -1347,6 +1546,7 @@ where
             // connection object might be present despite the transport being closed
             if con.is_closed() {
                 // transport is closed, need to refresh
+                inner.set_node_connection_state(addr, NodeConnectionState::Disconnected);
                 addrs_to_refresh.push(addr.clone());
             }
         }
@@ -1415,6 +1615,7 @@ where
         while let Some(result) = tasks.next().await {
             match result {
                 (address, Ok(node)) => {
+                    inner.set_node_connection_state(&address, NodeConnectionState::Connected);
                     let connections_container = inner.conn_lock.read().expect(MUTEX_READ_ERR);
                     connections_container.replace_or_add_connection_for_address(address, node);
                 }
@@ -1423,6 +1624,7 @@ where
                         "Failed to refresh connection for node {}. Error: `{:?}`",
                         address, err
                     );
+                    inner.set_node_connection_state(&address, NodeConnectionState::Disconnected);
                 }
             }
         }
@@ -1556,21 +1758,45 @@ where
     async fn refresh_slots_and_subscriptions_with_retries(
         inner: Arc<InnerCore<C>>,
         policy: &RefreshPolicy,
+    ) -> RedisResult<()> {
+        // Ensure only a single slot refresh operation occurs at a time,
+        // the callers requesting a refresh while it's in progress wait for it and share its result.
+        let refresh = {
+            let mut in_progress = inner
+                .slot_refresh_state
+                .in_progress
+                .lock()
+                .expect(MUTEX_WRITE_ERR);
+            match in_progress.as_ref().and_then(|refresh| refresh.upgrade()) {
+                Some(refresh) => refresh,
+                None => {
+                    let refresh =
+                        Self::refresh_slots_and_subscriptions(inner.clone(), policy.clone())
+                            .map(|res| res.map_err(Arc::new))
+                            .boxed()
+                            .shared();
+                    // Only a weak reference is kept, since the refresh holds a reference to `inner`.
+                    *in_progress = refresh.downgrade();
+                    refresh
+                }
+            }
+        };
+        refresh
+            .await
+            .map_err(|err| err.clone_mostly("Failed to refresh slots"))
+    }
+
+    async fn refresh_slots_and_subscriptions(
+        inner: Arc<InnerCore<C>>,
+        policy: RefreshPolicy,
     ) -> RedisResult<()> {
         let SlotRefreshState {
             in_progress,
             last_run,
             rate_limiter,
         } = &inner.slot_refresh_state;
-        // Ensure only a single slot refresh operation occurs at a time
-        if in_progress
-            .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
-            .is_err()
-        {
-            return Ok(());
-        }
         let mut should_refresh_slots = true;
-        if *policy == RefreshPolicy::Throttable {
+        if policy == RefreshPolicy::Throttable {
             // Check if the current slot refresh is triggered before the wait duration has passed
             let last_run_rlock = last_run.read().await;
             if let Some(last_run_time) = *last_run_rlock {
@@ -1616,7 +1842,7 @@ where
             })
             .await;
         }
-        in_progress.store(false, Ordering::Relaxed);
+        *in_progress.lock().expect(MUTEX_WRITE_ERR) = None;
 
         Self::refresh_pubsub_subscriptions(inner).await;
 
@@ -1684,6 +1910,88 @@ where
         }
     }
 
+    #[cfg(feature = "tokio-comp")]
+    async fn replication_check(inner: Arc<InnerCore<C>>, replication_check: ReplicationCheck) {
+        loop {
+            let _ = boxed_sleep(replication_check.check_interval).await;
+            Self::update_replication_states(inner.clone(), replication_check.max_offset_lag).await;
+        }
+    }
+
+    /// Queries `INFO REPLICATION` on every node and records the replication offsets of the nodes that answered.
+    /// If `max_offset_lag` is set, also marks the replicas that are either disconnected from their primary
+    /// or lag behind it by more than `max_offset_lag` bytes.
+    /// Shards whose primary couldn't be queried keep all their replicas available.
+    async fn update_replication_states(inner: Arc<InnerCore<C>>, max_offset_lag: Option<u64>) {
+        let (shards, nodes) = {
+            let conn_lock = inner.conn_lock.read().expect(MUTEX_READ_ERR);
+            let shards: Vec<(Arc<String>, Vec<Arc<String>>)> = conn_lock
+                .slot_map
+                .nodes_map()
+                .iter()
+                .filter(|entry| **entry.key() == *entry.value().primary())
+                .map(|entry| (entry.value().primary(), entry.value().replicas().clone()))
+                .collect();
+            let nodes: Vec<_> = shards
+                .iter()
+                .flat_map(|(primary, replicas)| std::iter::once(primary).chain(replicas))
+                .filter_map(|addr| {
+                    conn_lock.node_for_address(addr).map(|node| {
+                        (
+                            addr.clone(),
+                            node.get_connection(&ConnectionType::PreferManagement),
+                        )
+                    })
+                })
+                .collect();
+            (shards, nodes)
+        };
+
+        let states: HashMap<Arc<String>, ReplicationState> =
+            futures::future::join_all(nodes.into_iter().map(|(addr, conn)| async move {
+                let mut conn: C = conn.await;
+                let state = conn
+                    .req_packed_command(crate::cmd::cmd("INFO").arg("REPLICATION"))
+                    .await
+                    .and_then(|value| InfoDict::from_redis_value(&value))
+                    .ok()
+                    .and_then(|info| ReplicationState::from_info(&info));
+                (addr, state)
+            }))
+            .await
+            .into_iter()
+            .filter_map(|(addr, state)| state.map(|state| (addr, state)))
+            .collect();
+
+        let mut stale_replicas = HashSet::new();
+        for (primary, replicas) in shards {
+            let (Some(primary_state), Some(max_offset_lag)) =
+                (states.get(&primary), max_offset_lag)
+            else {
+                continue;
+            };
+            for replica in replicas {
+                // A replica that didn't answer is left to the connection validation to handle.
+                if states
+                    .get(&replica)
+                    .map_or(false, |state| state.is_stale(primary_state, max_offset_lag))
+                {
+                    stale_replicas.insert(replica.to_string());
+                }
+            }
+        }
+
+        let mut conn_lock = inner.conn_lock.write().expect(MUTEX_WRITE_ERR);
+        conn_lock.replication_offsets = states
+            .into_iter()
+            .map(|(addr, state)| (addr.to_string(), state.offset))
+            .collect();
+        if conn_lock.stale_replicas != stale_replicas {
+            info!("Replicas excluded from reads due to staleness: {stale_replicas:?}");
+            conn_lock.stale_replicas = stale_replicas;
+        }
+    }
+
     async fn refresh_pubsub_subscriptions(inner: Arc<InnerCore<C>>) {
         if inner.cluster_params.read().expect(MUTEX_READ_ERR).protocol
             != crate::types::ProtocolVersion::RESP3
@@ -1774,6 +2082,9 @@ where
     /// topology view differs from the one currently stored in the connection manager.
     /// Returns true if change was detected, otherwise false.
     async fn check_for_topology_diff(inner: Arc<InnerCore<C>>) -> bool {
+        if inner.is_proxy_mode() {
+            return false;
+        }
         let num_of_nodes = inner.conn_lock.read().expect(MUTEX_READ_ERR).len();
         let num_of_nodes_to_query = std::cmp::max(num_of_nodes.ilog2() as usize, 1);
         let (res, failed_connections) = calculate_topology_from_random_nodes(
@@ -1822,13 +2133,32 @@ where
         let num_of_nodes = inner.conn_lock.read().expect(MUTEX_READ_ERR).len();
         const MAX_REQUESTED_NODES: usize = 10;
         let num_of_nodes_to_query = std::cmp::min(num_of_nodes, MAX_REQUESTED_NODES);
-        let (new_slots, topology_hash) =
+        let (new_slots, topology_hash) = if inner.is_proxy_mode() {
+            (Self::proxy_slot_map(&inner).await, 0)
+        } else {
             calculate_topology_from_random_nodes(&inner, num_of_nodes_to_query, curr_retry)
                 .await
-                .0?;
+                .0?
+        };
         // Create a new connection vector of the found nodes
         let nodes = new_slots.all_node_addresses();
         let nodes_len = nodes.len();
+        // Nodes whose address changed are recognized by their ids. They're connected in their new address,
+        // and their connections in the previous address are kept until the new connections succeed.
+        let previous_addresses: HashMap<String, String> = {
+            let connections = inner.conn_lock.read().expect(MUTEX_READ_ERR);
+            let previous_addresses_by_node_id = connections.slot_map.addresses_by_node_id();
+            nodes
+                .iter()
+                .filter_map(|addr| {
+                    let node_id = new_slots.node_info(addr)?.id.as_str();
+                    let previous_address = *previous_addresses_by_node_id.get(node_id)?;
+                    (previous_address != addr.as_str())
+                        .then(|| (addr.to_string(), previous_address.to_string()))
+                })
+                .collect()
+        };
+        let previous_addresses = &previous_addresses;
         let addresses_and_connections_iter = stream::iter(nodes)
             .fold(
                 Vec::with_capacity(nodes_len),
@@ -1888,8 +2218,29 @@ where
                         inner.glide_connection_options.clone(),
                     )
                     .await;
-                    if let Ok(node) = node {
-                        connections.0.insert(addr, node);
+                    match node {
+                        Ok(node) => {
+                            inner.set_node_connection_state(&addr, NodeConnectionState::Connected);
+                            connections.0.insert(addr, node);
+                        }
+                        Err(_) => {
+                            inner.set_node_connection_state(
+                                &addr,
+                                NodeConnectionState::Disconnected,
+                            );
+                            if let Some((previous_address, node)) =
+                                previous_addresses.get(&addr).and_then(|previous_address| {
+                                    inner
+                                        .conn_lock
+                                        .read()
+                                        .expect(MUTEX_READ_ERR)
+                                        .node_for_address(previous_address)
+                                        .map(|node| (previous_address.clone(), node))
+                                })
+                            {
+                                connections.0.insert(previous_address, node);
+                            }
+                        }
                     }
                     connections
                 },
@@ -1897,20 +2248,84 @@ where
             .await;
 
         info!("refresh_slots found nodes:\n{new_connections}");
+        // The changes are found before taking the write lock, so that requests aren't blocked while the slot maps
+        // are compared. Changes made to the slot map in the meantime, such as MOVED patches, are replaced below anyway.
+        let topology_events = diff_slot_maps(
+            &inner.conn_lock.read().expect(MUTEX_READ_ERR).slot_map,
+            &new_slots,
+        );
         // Reset the current slot map and connection vector with the new ones
         let mut write_guard = inner.conn_lock.write().expect(MUTEX_WRITE_ERR);
         let read_from_replicas = inner
             .get_cluster_param(|params| params.read_from_replicas.clone())
             .expect(MUTEX_READ_ERR);
+        let stale_replicas = std::mem::take(&mut write_guard.stale_replicas);
+        let replication_offsets = std::mem::take(&mut write_guard.replication_offsets);
         *write_guard = ConnectionsContainer::new(
             new_slots,
             new_connections,
             read_from_replicas,
             topology_hash,
         );
+        write_guard.stale_replicas = stale_replicas;
+        write_guard.replication_offsets = replication_offsets;
+        drop(write_guard);
+        inner.complete_slot_migrations();
+        inner.send_topology_events(topology_events);
         Ok(())
     }
 
+    /// Returns the slot map used behind a cluster proxy, in which all slots are served by a single initial node:
+    /// the first one that is connected, or the first one if none is.
+    async fn proxy_slot_map(inner: &InnerCore<C>) -> SlotMap {
+        let mut proxy_address = None;
+        for node in &inner.initial_nodes {
+            let address = node.addr.to_string();
+            if Self::is_connected_to(inner, &address).await {
+                proxy_address = Some(address);
+                break;
+            }
+        }
+        let proxy_address = proxy_address
+            .or_else(|| {
+                inner
+                    .initial_nodes
+                    .first()
+                    .map(|node| node.addr.to_string())
+            })
+            .unwrap_or_default();
+        let read_from_replicas = inner
+            .get_cluster_param(|params| params.read_from_replicas.clone())
+            .expect(MUTEX_READ_ERR);
+        SlotMap::new(
+            vec![Slot::new(0, SLOT_SIZE - 1, proxy_address, vec![])],
+            read_from_replicas,
+        )
+    }
+
+    /// Checks whether there's a connection to `address`, which may be stored under one of its resolved IP addresses.
+    async fn is_connected_to(inner: &InnerCore<C>, address: &str) -> bool {
+        if inner
+            .conn_lock
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .node_for_address(address)
+            .is_some()
+        {
+            return true;
+        }
+        let Some((host, port)) = get_host_and_port_from_addr(address) else {
+            return false;
+        };
+        get_socket_addrs(host, port)
+            .await
+            .map_or(false, |mut socket_addresses| {
+                let connections = inner.conn_lock.read().expect(MUTEX_READ_ERR);
+                socket_addresses
+                    .any(|addr| connections.node_for_address(&addr.to_string()).is_some())
+            })
+    }
+
     /// Handles MOVED errors by updating the client's slot and node mappings based on the new primary's role:
     ///
     /// 1. **No Change**: If the new primary is already the current slot owner, no updates are needed.
@@ -1944,47 +2359,70 @@ where
             .shard_addrs_for_slot(slot);
         // let curr_shard_addrs = connections_container.slot_map.shard_addrs_for_slot(slot);
         // Check if the new primary is part of the current shard and update if required
-        if let Some(curr_shard_addrs) = curr_shard_addrs {
+        let previous_primary = curr_shard_addrs.as_ref().map(|addrs| addrs.primary());
+        if let (Some(curr_shard_addrs), Some(previous_primary)) =
+            (curr_shard_addrs, previous_primary.as_ref())
+        {
             match curr_shard_addrs.attempt_shard_role_update(new_primary.clone()) {
-                // Scenario 1: No changes needed as the new primary is already the current slot owner.
+                // Scenario 1: No changes needed as the new primary is already the current slot owner,
+                // e.g. because an earlier MOVED error of the same burst already patched the slot map.
+                ShardUpdateResult::AlreadyPrimary => return Ok(()),
                 // Scenario 2: Failover occurred and the new primary was promoted from a replica.
-                ShardUpdateResult::AlreadyPrimary | ShardUpdateResult::Promoted => return Ok(()),
+                ShardUpdateResult::Promoted => {
+                    inner.send_topology_events([TopologyEvent::Failover {
+                        previous_primary: previous_primary.to_string(),
+                        primary: new_primary.to_string(),
+                    }]);
+                    return Ok(());
+                }
                 // The node was not found in this shard, proceed with further scenarios.
                 ShardUpdateResult::NodeNotFound => {}
             }
         }
+        let slot_moved = TopologyEvent::SlotsMoved {
+            start: slot,
+            end: slot,
+            previous_primary: previous_primary.map(|address| address.to_string()),
+            primary: Some(new_primary.to_string()),
+        };
 
         // Scenario 3 & 4: Check if the new primary exists in other shards
 
         let mut wlock_conn_container = inner.conn_lock.write().expect(MUTEX_READ_ERR);
-        let mut nodes_iter = wlock_conn_container.slot_map_nodes();
-        for (node_addr, shard_addrs_arc) in &mut nodes_iter {
-            if node_addr == new_primary {
-                let is_existing_primary = shard_addrs_arc.primary().eq(&new_primary);
-                if is_existing_primary {
-                    // Scenario 3: Slot Migration - The new primary is an existing primary in another shard
-                    // Update the associated addresses for `slot` to `shard_addrs`.
-                    drop(nodes_iter);
-                    return wlock_conn_container
-                        .slot_map
-                        .update_slot_range(slot, shard_addrs_arc.clone());
-                } else {
-                    // Scenario 4: The MOVED error redirects to `new_primary` which is known as a replica in a shard that doesn’t own `slot`.
-                    // Remove the replica from its existing shard and treat it as a new node in a new shard.
-                    shard_addrs_arc.remove_replica(new_primary.clone())?;
-                    drop(nodes_iter);
-                    return wlock_conn_container
-                        .slot_map
-                        .add_new_primary(slot, new_primary);
-                }
+        let existing_shard_addrs = wlock_conn_container
+            .slot_map_nodes()
+            .find(|(node_addr, _)| *node_addr == new_primary)
+            .map(|(_, shard_addrs_arc)| shard_addrs_arc);
+        let is_new_node = existing_shard_addrs.is_none();
+        let result = match existing_shard_addrs {
+            Some(shard_addrs_arc) if shard_addrs_arc.primary().eq(&new_primary) => {
+                // Scenario 3: Slot Migration - The new primary is an existing primary in another shard
+                // Update the associated addresses for `slot` to `shard_addrs`.
+                wlock_conn_container
+                    .slot_map
+                    .update_slot_range(slot, shard_addrs_arc)
             }
-        }
+            Some(shard_addrs_arc) => {
+                // Scenario 4: The MOVED error redirects to `new_primary` which is known as a replica in a shard that doesn’t own `slot`.
+                // Remove the replica from its existing shard and treat it as a new node in a new shard.
+                shard_addrs_arc.remove_replica(new_primary.clone())?;
+                wlock_conn_container
+                    .slot_map
+                    .add_new_primary(slot, new_primary.clone())
+            }
+            // Scenario 5: New Node - The new primary is not present in the current slots map, add it as a primary of a new shard.
+            None => wlock_conn_container
+                .slot_map
+                .add_new_primary(slot, new_primary.clone()),
+        };
+        drop(wlock_conn_container);
 
-        drop(nodes_iter);
-        // Scenario 5: New Node - The new primary is not present in the current slots map, add it as a primary of a new shard.
-        wlock_conn_container
-            .slot_map
-            .add_new_primary(slot, new_primary)
+        if result.is_ok() {
+            inner.complete_slot_migrations();
+            let node_added = is_new_node.then(|| TopologyEvent::NodeAdded(new_primary.to_string()));
+            inner.send_topology_events(node_added.into_iter().chain([slot_moved]));
+        }
+        result
     }
 
     async fn execute_on_multiple_nodes<'a>(
@@ -2116,11 +2554,48 @@ where
 
         // if we reached this point, we're sending the command only to single node, and we need to find the
         // right connection to the node.
-        let (address, mut conn) = Self::get_connection(routing, core, Some(cmd.clone()))
-            .await
-            .map_err(|err| (OperationTarget::NotFound, err))?;
-        conn.req_packed_command(&cmd)
-            .await
+        let traffic_stats = core.traffic_stats.clone();
+        let (address, result) = match routing {
+            InternalSingleNodeRouting::Redirect {
+                redirect: Redirect::Ask(ask_addr),
+                ..
+            } => {
+                let address = core.map_address(&ask_addr);
+                let result = Self::send_asking(core.clone(), address.clone(), cmd.clone())
+                    .await
+                    .map_err(|err| (OperationTarget::NotFound, err))?;
+                (address, result)
+            }
+            routing => {
+                let (address, mut conn) =
+                    Self::get_connection(routing, core.clone(), Some(cmd.clone()))
+                        .await
+                        .map_err(|err| (OperationTarget::NotFound, err))?;
+                let result = conn.req_packed_command(&cmd).await;
+                (address, result)
+            }
+        };
+        if let Err(err) = &result {
+            if let Some((target, slot)) =
+                err.redirect_node().filter(|_| err.kind() == ErrorKind::Ask)
+            {
+                core.record_slot_migration(slot, &address, core.map_address(target));
+            }
+        }
+        if let Some(traffic_stats) = traffic_stats {
+            let key = RoutingInfo::routing_key(&*cmd);
+            if let Some(key) = key {
+                traffic_stats.sample_key(key);
+            }
+            traffic_stats.record_requests(
+                key.map(get_slot),
+                &address,
+                1,
+                args_bytes(&cmd),
+                result.is_err(),
+            );
+        }
+        result
             .map(Response::Single)
             .map_err(|err| (address.into(), err))
     }
@@ -2130,11 +2605,18 @@ where
         offset: usize,
         count: usize,
         conn: impl Future<Output = RedisResult<(String, C)>>,
+        traffic_stats: Option<Arc<TrafficStats>>,
     ) -> OperationResult {
         trace!("try_pipeline_request");
         let (address, mut conn) = conn.await.map_err(|err| (OperationTarget::NotFound, err))?;
-        conn.req_packed_commands(&pipeline, offset, count)
-            .await
+        let result = conn.req_packed_commands(&pipeline, offset, count).await;
+        if let Some(traffic_stats) = traffic_stats {
+            let (requests, bytes) = pipeline.cmd_iter().fold((0, 0), |(requests, bytes), cmd| {
+                (requests + 1, bytes + args_bytes(cmd))
+            });
+            traffic_stats.record_requests(None, &address, requests, bytes, result.is_err());
+        }
+        result
             .map(Response::Multiple)
             .map_err(|err| (OperationTarget::Node { address }, err))
     }
@@ -2148,11 +2630,13 @@ where
                 count,
                 route,
             } => {
+                let traffic_stats = core.traffic_stats.clone();
                 Self::try_pipeline_request(
                     pipeline,
                     offset,
                     count,
                     Self::get_connection(route, core, None),
+                    traffic_stats,
                 )
                 .await
             }
@@ -2180,6 +2664,34 @@ where
         }
     }
 
+    fn connection_check_for_route(
+        connection: Option<(String, ConnectionFuture<C>)>,
+        route: &Route,
+        cmd: Option<Arc<Cmd>>,
+    ) -> RedisResult<ConnectionCheck<C>> {
+        if let Some((address, conn)) = connection {
+            return Ok(ConnectionCheck::Found((address, conn)));
+        }
+        // No connection is found for the given route:
+        // - For key-based commands, attempt redirection to a random node,
+        //   hopefully to be redirected afterwards by a MOVED error.
+        // - For non-key-based commands, avoid attempting redirection to a random node
+        //   as it wouldn't result in MOVED hints and can lead to unwanted results
+        //   (e.g., sending management command to a different node than the user asked for); instead, raise the error.
+        let routable_cmd = cmd.and_then(|cmd| Routable::command(&*cmd));
+        if routable_cmd.is_some() && !RoutingInfo::is_key_routing_command(&routable_cmd.unwrap()) {
+            Err((
+                ErrorKind::ConnectionNotFoundForRoute,
+                "Requested connection not found for route",
+                format!("{route:?}"),
+            )
+                .into())
+        } else {
+            warn!("No connection found for route `{route:?}`. Attempting redirection to a random node.");
+            Ok(ConnectionCheck::RandomConnection)
+        }
+    }
+
     async fn get_connection(
         routing: InternalSingleNodeRouting<C>,
         core: Core<C>,
@@ -2191,59 +2703,29 @@ where
             InternalSingleNodeRouting::Redirect {
                 redirect: Redirect::Moved(moved_addr),
                 ..
-            } => core
-                .conn_lock
-                .read()
-                .expect(MUTEX_READ_ERR)
-                .connection_for_address(moved_addr.as_str())
-                .map_or(
-                    ConnectionCheck::OnlyAddress(moved_addr),
-                    ConnectionCheck::Found,
-                ),
+            } => Self::connection_check_for_address(&core, core.map_address(&moved_addr)),
             InternalSingleNodeRouting::Redirect {
                 redirect: Redirect::Ask(ask_addr),
                 ..
             } => {
                 asking = true;
-                core.conn_lock
+                Self::connection_check_for_address(&core, core.map_address(&ask_addr))
+            }
+            InternalSingleNodeRouting::SpecificNode(route) => {
+                let connection = core
+                    .conn_lock
                     .read()
                     .expect(MUTEX_READ_ERR)
-                    .connection_for_address(ask_addr.as_str())
-                    .map_or(
-                        ConnectionCheck::OnlyAddress(ask_addr),
-                        ConnectionCheck::Found,
-                    )
+                    .connection_for_route(&route);
+                Self::connection_check_for_route(connection, &route, cmd)?
             }
-            InternalSingleNodeRouting::SpecificNode(route) => {
-                if let Some((conn, address)) = core
+            InternalSingleNodeRouting::SpecificNodeAtOffset { route, min_offset } => {
+                let connection = core
                     .conn_lock
                     .read()
                     .expect(MUTEX_READ_ERR)
-                    .connection_for_route(&route)
-                {
-                    ConnectionCheck::Found((conn, address))
-                } else {
-                    // No connection is found for the given route:
-                    // - For key-based commands, attempt redirection to a random node,
-                    //   hopefully to be redirected afterwards by a MOVED error.
-                    // - For non-key-based commands, avoid attempting redirection to a random node
-                    //   as it wouldn't result in MOVED hints and can lead to unwanted results
-                    //   (e.g., sending management command to a different node than the user asked for); instead, raise the error.
-                    let routable_cmd = cmd.and_then(|cmd| Routable::command(&*cmd));
-                    if routable_cmd.is_some()
-                        && !RoutingInfo::is_key_routing_command(&routable_cmd.unwrap())
-                    {
-                        return Err((
-                            ErrorKind::ConnectionNotFoundForRoute,
-                            "Requested connection not found for route",
-                            format!("{route:?}"),
-                        )
-                            .into());
-                    } else {
-                        warn!("No connection found for route `{route:?}`. Attempting redirection to a random node.");
-                        ConnectionCheck::RandomConnection
-                    }
-                }
+                    .connection_for_route_at_offset(&route, min_offset);
+                Self::connection_check_for_route(connection, &route, cmd)?
             }
             InternalSingleNodeRouting::Random => ConnectionCheck::RandomConnection,
             InternalSingleNodeRouting::Connection { address, conn } => {
@@ -2268,7 +2750,31 @@ where
             }
         };
 
-        let (address, mut conn) = match conn_check {
+        let (address, mut conn) = Self::resolve_connection_check(&core, conn_check).await?;
+
+        if asking {
+            let _ = conn.req_packed_command(&crate::cmd::cmd("ASKING")).await;
+        }
+        Ok((address, conn))
+    }
+
+    /// Returns the connection to `address`, or the address to connect to if there's none.
+    fn connection_check_for_address(core: &InnerCore<C>, address: String) -> ConnectionCheck<C> {
+        core.conn_lock
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .connection_for_address(address.as_str())
+            .map_or(
+                ConnectionCheck::OnlyAddress(address),
+                ConnectionCheck::Found,
+            )
+    }
+
+    async fn resolve_connection_check(
+        core: &InnerCore<C>,
+        conn_check: ConnectionCheck<C>,
+    ) -> RedisResult<(String, C)> {
+        Ok(match conn_check {
             ConnectionCheck::Found((address, connection)) => (address, connection.await),
             ConnectionCheck::OnlyAddress(addr) => {
                 let mut this_conn_params = core.get_cluster_param(|params| params.clone())?;
@@ -2317,12 +2823,98 @@ where
 
                 (random_address, random_conn_future.await)
             }
+        })
+    }
+
+    /// Sends a command redirected with `ASK` to `address`, preceded by `ASKING`.
+    ///
+    /// The commands redirected to the same node while the first of them waits for its turn are sent
+    /// together, in a single write in which each command directly follows its `ASKING`, so that
+    /// other requests on the connection can't be sent in between. Each command gets its own reply,
+    /// so the error of one command doesn't fail the others.
+    ///
+    /// Returns an error if the node couldn't be connected, or the result of the command otherwise.
+    async fn send_asking(
+        core: Core<C>,
+        address: String,
+        cmd: Arc<Cmd>,
+    ) -> RedisResult<RedisResult<Value>> {
+        let (sender, receiver) = oneshot::channel();
+        let is_first = {
+            let mut ask_batches = core.ask_batches.lock().unwrap();
+            let batch = ask_batches.entry(address.clone()).or_default();
+            batch.push((cmd, sender));
+            batch.len() == 1
         };
+        if is_first {
+            // Yield once, so that the other requests redirected in this round join the batch.
+            let mut yielded = false;
+            future::poll_fn(|cx| {
+                if yielded {
+                    return Poll::Ready(());
+                }
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            })
+            .await;
+            let batch = core
+                .ask_batches
+                .lock()
+                .unwrap()
+                .remove(&address)
+                .unwrap_or_default();
+            Self::send_asking_batch(&core, address, batch).await;
+        }
+        receiver.await.unwrap_or_else(|_| {
+            Err(RedisError::from((
+                ErrorKind::ClientError,
+                "ASK-redirected request was dropped",
+            )))
+        })
+    }
 
-        if asking {
-            let _ = conn.req_packed_command(&crate::cmd::cmd("ASKING")).await;
+    async fn send_asking_batch(core: &InnerCore<C>, address: String, batch: Vec<AskBatchEntry>) {
+        let mut conn = match Self::resolve_connection_check(
+            core,
+            Self::connection_check_for_address(core, address),
+        )
+        .await
+        {
+            Ok((_, conn)) => conn,
+            Err(err) => {
+                for (_, sender) in batch {
+                    let _ = sender.send(Err(err.clone_mostly("Failed to connect")));
+                }
+                return;
+            }
+        };
+
+        let mut pipeline = crate::pipe();
+        for (cmd, _) in &batch {
+            pipeline
+                .add_command(crate::cmd::cmd("ASKING"))
+                .add_command(Cmd::clone(cmd));
+        }
+        match conn.req_packed_commands_separately(&pipeline).await {
+            Ok(results) => {
+                // The reply of each command follows the reply of its `ASKING`.
+                let mut results = results.into_iter();
+                for (_, sender) in batch {
+                    let result = match (results.next(), results.next()) {
+                        (Some(Err(err)), _) => Err(err),
+                        (Some(Ok(_)), Some(result)) => result,
+                        _ => Err((ErrorKind::ResponseError, "Missing response").into()),
+                    };
+                    let _ = sender.send(Ok(result));
+                }
+            }
+            Err(err) => {
+                for (_, sender) in batch {
+                    let _ = sender.send(Ok(Err(err.clone_mostly("Failed to send the commands"))));
+                }
+            }
         }
-        Ok((address, conn))
     }
 
     fn poll_recover(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), RedisError>> {
@@ -2456,13 +3048,35 @@ where
                         },
                     }));
                 }
+                Next::RefreshSlots {
+                    request,
+                    moved_redirect: Some(_),
+                    ..
+                } if self.inner.is_proxy_mode() => {
+                    // The proxy serves every slot, so the request is retried on it rather than following the redirect.
+                    if let Some(mut request) = request {
+                        request.info.reset_routing();
+                        let future = Self::try_request(request.info.clone(), self.inner.clone());
+                        self.in_flight_requests.push(Box::pin(Request {
+                            retry_params: retry_params.clone(),
+                            request: Some(request),
+                            future: RequestState::Future {
+                                future: Box::pin(future),
+                            },
+                        }));
+                    }
+                }
                 Next::RefreshSlots {
                     request,
                     sleep_duration,
                     moved_redirect,
                 } => {
-                    poll_flush_action =
-                        poll_flush_action.change_state(PollFlushAction::RebuildSlots);
+                    // A MOVED redirect of a retried request is patched into the slot map on its own;
+                    // the request falls back to a full refresh only if the patch fails.
+                    if moved_redirect.is_none() || request.is_none() {
+                        poll_flush_action =
+                            poll_flush_action.change_state(PollFlushAction::RebuildSlots);
+                    }
                     let future: Option<
                         RequestState<Pin<Box<dyn Future<Output = OperationResult> + Send>>>,
                     > = if let Some(moved_redirect) = moved_redirect {
@@ -2470,7 +3084,7 @@ where
                             future: Box::pin(ClusterConnInner::update_upon_moved_error(
                                 self.inner.clone(),
                                 moved_redirect.slot,
-                                moved_redirect.address.into(),
+                                self.inner.map_address(&moved_redirect.address).into(),
                             )),
                         })
                     } else if let Some(ref request) = request {
@@ -2580,7 +3194,20 @@ where
     }
 
     fn start_send(self: Pin<&mut Self>, msg: Message<C>) -> Result<(), Self::Error> {
-        let Message { cmd, sender } = msg;
+        let Message { mut cmd, sender } = msg;
+
+        // Addresses given by the user are mapped like the addresses reported by the cluster, so that they match the connections' keys.
+        let by_address_route = match &mut cmd {
+            CmdArg::Cmd {
+                routing: InternalRoutingInfo::SingleNode(route),
+                ..
+            }
+            | CmdArg::Pipeline { route, .. } => Some(route),
+            _ => None,
+        };
+        if let Some(InternalSingleNodeRouting::ByAddress(address)) = by_address_route {
+            *address = self.inner.map_address(address);
+        }
 
         let info = RequestInfo { cmd };
 
@@ -2696,10 +3323,30 @@ where
             vec![],
         );
     };
+    let use_cluster_shards = inner
+        .get_cluster_param(|params| params.use_cluster_shards)
+        .expect(MUTEX_READ_ERR)
+        && !inner
+            .cluster_shards_unsupported
+            .load(atomic::Ordering::Relaxed);
+    let cluster_shards_unsupported = &inner.cluster_shards_unsupported;
     let topology_join_results =
         futures::future::join_all(requested_nodes.into_iter().map(|(addr, conn)| async move {
             let mut conn: C = conn.await;
-            let res = conn.req_packed_command(&slot_cmd()).await;
+            if use_cluster_shards {
+                match conn.req_packed_command(&shards_cmd()).await {
+                    Ok(value) => return (addr, Ok(TopologyResponse::ClusterShards(value))),
+                    // Servers older than 7.0 don't support `CLUSTER SHARDS`.
+                    Err(err) if err.kind() == ErrorKind::ResponseError => {
+                        cluster_shards_unsupported.store(true, atomic::Ordering::Relaxed);
+                    }
+                    Err(err) => return (addr, Err(err)),
+                }
+            }
+            let res = conn
+                .req_packed_command(&slot_cmd())
+                .await
+                .map(TopologyResponse::ClusterSlots);
             (addr, res)
         }))
         .await;
@@ -2722,6 +3369,9 @@ where
     let read_from_replicas = inner
         .get_cluster_param(|params| params.read_from_replicas.clone())
         .expect(MUTEX_READ_ERR);
+    let address_mapper = inner
+        .get_cluster_param(|params| params.address_mapper.clone())
+        .expect(MUTEX_READ_ERR);
     (
         calculate_topology(
             topology_values,
@@ -2729,6 +3379,7 @@ where
             tls_mode,
             num_of_nodes_to_query,
             read_from_replicas,
+            address_mapper.as_ref(),
         ),
         failed_addresses,
     )
@@ -8,8 +8,10 @@ use crate::types::{ErrorKind, ProtocolVersion, RedisError, RedisResult};
 use crate::{cluster, cluster::TlsMode};
 use crate::{PubSubSubscriptionInfo, PushInfo};
 use rand::Rng;
+use std::collections::HashMap;
 #[cfg(feature = "cluster-async")]
 use std::ops::Add;
+use std::sync::Arc;
 use std::time::Duration;
 use telemetrylib::GlideOpenTelemetryConfig;
 
@@ -46,11 +48,19 @@ struct BuilderParams {
     connections_validation_interval: Option<Duration>,
     #[cfg(feature = "cluster-async")]
     slots_refresh_rate_limit: SlotsRefreshRateLimit,
+    #[cfg(feature = "cluster-async")]
+    replication_check: Option<ReplicationCheck>,
+    #[cfg(feature = "cluster-async")]
+    traffic_stats: Option<TrafficStatsConfig>,
+    #[cfg(feature = "cluster-async")]
+    proxy_mode: bool,
     client_name: Option<String>,
     response_timeout: Option<Duration>,
     protocol: ProtocolVersion,
     pubsub_subscriptions: Option<PubSubSubscriptionInfo>,
     open_telemetry_config: Option<GlideOpenTelemetryConfig>,
+    use_cluster_shards: bool,
+    address_mapper: Option<AddressMapper>,
 }
 
 #[derive(Clone)]
@@ -116,6 +126,31 @@ impl Default for SlotsRefreshRateLimit {
     }
 }
 
+/// Configuration for the periodic check of the nodes' replication offsets.
+///
+/// # Fields
+///
+/// * `check_interval`: The interval between consecutive checks of the nodes' replication offsets.
+/// * `max_offset_lag`: If set, replicas whose replication offset is more than this number of bytes behind
+///   their primary's are excluded from reads.
+#[cfg(feature = "cluster-async")]
+#[derive(Clone, Copy)]
+pub(crate) struct ReplicationCheck {
+    pub(crate) check_interval: Duration,
+    pub(crate) max_offset_lag: Option<u64>,
+}
+
+/// Configuration for collecting the traffic sent to each slot and node.
+///
+/// # Fields
+///
+/// * `hot_keys`: If set, the capacity and the sample rate of the hot keys tracker.
+#[cfg(feature = "cluster-async")]
+#[derive(Clone, Copy, Default)]
+pub(crate) struct TrafficStatsConfig {
+    pub(crate) hot_keys: Option<(usize, u32)>,
+}
+
 #[cfg(feature = "cluster-async")]
 impl SlotsRefreshRateLimit {
     pub(crate) fn wait_duration(&self) -> Duration {
@@ -126,6 +161,48 @@ impl SlotsRefreshRateLimit {
         self.interval_duration.add(duration_jitter)
     }
 }
+
+/// Translates the node addresses reported by the cluster to the addresses the client connects to.
+///
+/// This is needed when the nodes announce addresses that aren't reachable from the client, for example when the
+/// cluster runs behind NAT or in a container network. The mapper is applied to every address discovered from the
+/// cluster's topology, to `MOVED` and `ASK` redirects, and to requests routed by address.
+#[derive(Clone)]
+pub struct AddressMapper(Arc<AddressMapperFn>);
+
+type AddressMapperFn = dyn Fn(&str, u16) -> Option<(String, u16)> + Send + Sync;
+
+impl AddressMapper {
+    /// Creates a mapper from a callback. Addresses for which the callback returns `None` are used as is.
+    pub fn new<F>(mapper: F) -> Self
+    where
+        F: Fn(&str, u16) -> Option<(String, u16)> + Send + Sync + 'static,
+    {
+        Self(Arc::new(mapper))
+    }
+
+    /// Creates a mapper from a static table of `(host, port)` pairs. Addresses missing from the table are used as is.
+    pub fn from_table(table: HashMap<(String, u16), (String, u16)>) -> Self {
+        Self::new(move |host, port| table.get(&(host.to_string(), port)).cloned())
+    }
+
+    /// Maps a `host:port` address. Addresses that can't be parsed are returned unchanged.
+    pub(crate) fn map_address(&self, address: &str) -> String {
+        address
+            .rsplit_once(':')
+            .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+            .and_then(|(host, port)| (self.0)(host, port))
+            .map(|(host, port)| format!("{host}:{port}"))
+            .unwrap_or_else(|| address.to_string())
+    }
+}
+
+impl std::fmt::Debug for AddressMapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AddressMapper")
+    }
+}
+
 /// Redis cluster specific parameters.
 #[derive(Default, Clone)]
 #[doc(hidden)]
@@ -143,6 +220,13 @@ pub struct ClusterParams {
     #[cfg(feature = "cluster-async")]
     pub(crate) slots_refresh_rate_limit: SlotsRefreshRateLimit,
     #[cfg(feature = "cluster-async")]
+    pub(crate) replication_check: Option<ReplicationCheck>,
+    #[cfg(feature = "cluster-async")]
+    pub(crate) traffic_stats: Option<TrafficStatsConfig>,
+    /// Whether the client connects through a cluster proxy, and pins all requests to the initial nodes.
+    #[cfg(feature = "cluster-async")]
+    pub(crate) proxy_mode: bool,
+    #[cfg(feature = "cluster-async")]
     pub(crate) connections_validation_interval: Option<Duration>,
     pub(crate) tls_params: Option<TlsConnParams>,
     pub(crate) client_name: Option<String>,
@@ -150,6 +234,20 @@ pub struct ClusterParams {
     pub(crate) response_timeout: Duration,
     pub(crate) protocol: ProtocolVersion,
     pub(crate) pubsub_subscriptions: Option<PubSubSubscriptionInfo>,
+    /// Whether the topology is discovered with `CLUSTER SHARDS`, falling back to `CLUSTER SLOTS`
+    /// for servers that don't support it.
+    pub(crate) use_cluster_shards: bool,
+    pub(crate) address_mapper: Option<AddressMapper>,
+}
+
+impl ClusterParams {
+    /// Maps an address reported by the cluster with the configured [`AddressMapper`], if any.
+    pub(crate) fn map_address(&self, address: &str) -> String {
+        match &self.address_mapper {
+            Some(mapper) => mapper.map_address(address),
+            None => address.to_string(),
+        }
+    }
 }
 
 impl ClusterParams {
@@ -176,12 +274,20 @@ impl ClusterParams {
             #[cfg(feature = "cluster-async")]
             slots_refresh_rate_limit: value.slots_refresh_rate_limit,
             #[cfg(feature = "cluster-async")]
+            replication_check: value.replication_check,
+            #[cfg(feature = "cluster-async")]
+            traffic_stats: value.traffic_stats,
+            #[cfg(feature = "cluster-async")]
+            proxy_mode: value.proxy_mode,
+            #[cfg(feature = "cluster-async")]
             connections_validation_interval: value.connections_validation_interval,
             tls_params,
             client_name: value.client_name,
             response_timeout: value.response_timeout.unwrap_or(Duration::MAX),
             protocol: value.protocol,
             pubsub_subscriptions: value.pubsub_subscriptions,
+            use_cluster_shards: value.use_cluster_shards,
+            address_mapper: value.address_mapper,
         })
     }
 }
@@ -394,6 +500,8 @@ impl ClusterClientBuilder {
     /// The parameter `read_strategy` can be one of:
     /// `ReadFromReplicaStrategy::AZAffinity(availability_zone)` - attempt to access replicas in the same availability zone.
     /// If no suitable replica is found (i.e. no replica could be found in the requested availability zone), choose any replica. Falling back to primary if needed.
+    /// `ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary(availability_zone)` - attempt to access replicas in the same availability zone,
+    /// then the primary if it's in that zone. Otherwise, choose any replica, falling back to primary if needed.
     /// `ReadFromReplicaStrategy::RoundRobin` - reads are distributed across replicas for load balancing using round-robin algorithm. Falling back to primary if needed.
     /// `ReadFromReplicaStrategy::AlwaysFromPrimary` ensures all read and write queries are directed to the primary node.
     ///
@@ -473,6 +581,91 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Excludes replicas that lag behind their primary from the reads routed to replicas.
+    ///
+    /// The replication state of the nodes is read with `INFO REPLICATION` every `check_interval`. A replica is excluded
+    /// while its link to the primary is down, or while its replication offset is more than `max_offset_lag` bytes behind
+    /// its primary's. Reads fall back to the primary when none of its replicas qualify.
+    #[cfg(feature = "cluster-async")]
+    pub fn replica_max_staleness(
+        mut self,
+        max_offset_lag: u64,
+        check_interval: Duration,
+    ) -> ClusterClientBuilder {
+        self.builder_params.replication_check = Some(ReplicationCheck {
+            check_interval,
+            max_offset_lag: Some(max_offset_lag),
+        });
+        self
+    }
+
+    /// Tracks the replication offsets of the nodes by reading `INFO REPLICATION` every `check_interval`.
+    ///
+    /// The tracked offsets are used to route reads with [`SingleNodeRoutingInfo::SpecificNodeAtOffset`](crate::cluster_routing::SingleNodeRoutingInfo::SpecificNodeAtOffset)
+    /// to replicas that have caught up. Has no effect if [`Self::replica_max_staleness`] is set, since the offsets are
+    /// then already tracked at its interval.
+    #[cfg(feature = "cluster-async")]
+    pub fn track_replication_offsets(mut self, check_interval: Duration) -> ClusterClientBuilder {
+        self.builder_params
+            .replication_check
+            .get_or_insert(ReplicationCheck {
+                check_interval,
+                max_offset_lag: None,
+            });
+        self
+    }
+
+    /// Counts the requests, request bytes and errors of the single-node commands and pipelines sent to each slot and node.
+    ///
+    /// The collected stats are read with [`ClusterConnection::traffic_stats`](crate::cluster_async::ClusterConnection::traffic_stats).
+    #[cfg(feature = "cluster-async")]
+    pub fn traffic_stats(mut self) -> ClusterClientBuilder {
+        self.builder_params
+            .traffic_stats
+            .get_or_insert_with(Default::default);
+        self
+    }
+
+    /// Collects the traffic stats, and also tracks the `capacity` most requested keys.
+    ///
+    /// Only one in every `sample_rate` requests is counted, and the number of requests to each key is
+    /// extrapolated from the sample. The counts are estimates, which may overcount keys that entered the
+    /// tracker recently.
+    #[cfg(feature = "cluster-async")]
+    pub fn hot_keys(mut self, capacity: usize, sample_rate: u32) -> ClusterClientBuilder {
+        self.builder_params.traffic_stats = Some(TrafficStatsConfig {
+            hot_keys: Some((capacity, sample_rate)),
+        });
+        self
+    }
+
+    /// Connects through a cluster proxy, which serves every slot and routes each request to the node owning it.
+    ///
+    /// All requests are sent to the first reachable initial node, and the cluster's topology is neither
+    /// discovered nor refreshed: `MOVED` replies are retried on the same node instead of updating the slot map.
+    /// Multi-slot commands are still split per slot, and commands sent to all primaries are sent once, to the proxy.
+    #[cfg(feature = "cluster-async")]
+    pub fn proxy_mode(mut self) -> ClusterClientBuilder {
+        self.builder_params.proxy_mode = true;
+        self
+    }
+
+    /// Discovers the cluster's topology with `CLUSTER SHARDS` instead of `CLUSTER SLOTS`.
+    ///
+    /// `CLUSTER SHARDS` also reports the id, role and health of each node. Replicas that are loading their
+    /// dataset aren't used for reads, and failed replicas are left out of the topology.
+    /// Once a node rejects `CLUSTER SHARDS`, the client queries the cluster with `CLUSTER SLOTS` instead.
+    pub fn use_cluster_shards(mut self) -> ClusterClientBuilder {
+        self.builder_params.use_cluster_shards = true;
+        self
+    }
+
+    /// Sets the [`AddressMapper`] used to translate the node addresses reported by the cluster.
+    pub fn address_mapper(mut self, address_mapper: AddressMapper) -> ClusterClientBuilder {
+        self.builder_params.address_mapper = Some(address_mapper);
+        self
+    }
+
     /// Enables timing out on slow connection time.
     ///
     /// If enabled, the cluster will only wait the given time on each connection attempt to each node.
@@ -639,7 +832,10 @@ mod tests {
         DEFAULT_SLOTS_REFRESH_MAX_JITTER_MILLI, DEFAULT_SLOTS_REFRESH_WAIT_DURATION,
     };
 
-    use super::{ClusterClient, ClusterClientBuilder, ConnectionInfo, IntoConnectionInfo};
+    use super::{
+        AddressMapper, ClusterClient, ClusterClientBuilder, ConnectionInfo, HashMap,
+        IntoConnectionInfo,
+    };
 
     fn get_connection_data() -> Vec<ConnectionInfo> {
         vec![
@@ -778,4 +974,29 @@ mod tests {
             DEFAULT_SLOTS_REFRESH_MAX_JITTER_MILLI
         );
     }
+
+    #[test]
+    fn address_mapper_from_table_maps_only_listed_addresses() {
+        let mapper = AddressMapper::from_table(HashMap::from([(
+            ("10.0.0.1".to_string(), 6379),
+            ("127.0.0.1".to_string(), 7000),
+        )]));
+        assert_eq!(mapper.map_address("10.0.0.1:6379"), "127.0.0.1:7000");
+        assert_eq!(mapper.map_address("10.0.0.2:6379"), "10.0.0.2:6379");
+        assert_eq!(mapper.map_address("not an address"), "not an address");
+    }
+
+    #[test]
+    fn address_mapper_is_applied_by_cluster_params() {
+        let client = ClusterClientBuilder::new(get_connection_data())
+            .address_mapper(AddressMapper::new(|host, port| {
+                Some((host.to_string(), port + 1000))
+            }))
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.cluster_params.map_address("[::1]:6379"),
+            "[::1]:7379"
+        );
+    }
 }
@@ -75,6 +75,14 @@ pub enum SingleNodeRoutingInfo {
     RandomPrimary,
     /// Route to the node that matches the [Route]
     SpecificNode(Route),
+    /// Route to the node that matches the [Route], choosing among the replicas only those whose replication offset
+    /// is known to have reached `min_offset`. Routed to the primary if no replica qualifies.
+    SpecificNodeAtOffset {
+        /// The route of the request
+        route: Route,
+        /// The minimal replication offset of a replica serving the request
+        min_offset: i64,
+    },
     /// Route to the node with the given address.
     ByAddress {
         /// DNS hostname of the node
@@ -857,6 +865,27 @@ impl RoutingInfo {
         }
     }
 
+    /// Returns the key that determines the slot of a command routed by a single key, if any.
+    pub(crate) fn routing_key<R>(r: &R) -> Option<&[u8]>
+    where
+        R: Routable + ?Sized,
+    {
+        let has_keys = |key_count_idx| {
+            r.arg_idx(key_count_idx)
+                .and_then(|x| std::str::from_utf8(x).ok())
+                .and_then(|x| x.parse::<u64>().ok())
+                .map_or(false, |key_count| key_count > 0)
+        };
+        match base_routing(&r.command()?) {
+            RouteBy::FirstKey => r.arg_idx(1),
+            RouteBy::SecondArg => r.arg_idx(2),
+            RouteBy::SecondArgAfterKeyCount if has_keys(1) => r.arg_idx(2),
+            RouteBy::ThirdArgAfterKeyCount if has_keys(2) => r.arg_idx(3),
+            RouteBy::StreamsIndex => r.arg_idx(r.position(b"STREAMS")? + 1),
+            _ => None,
+        }
+    }
+
     fn for_key(cmd: &[u8], key: &[u8]) -> RoutingInfo {
         RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(get_route(
             is_readonly_cmd(cmd),
@@ -1680,6 +1709,27 @@ mod tests_routing {
             ]).unwrap()), Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route(slot, SlotAddr::Master)))) if slot == 5210));
     }
 
+    #[test]
+    fn test_routing_key() {
+        assert_eq!(
+            RoutingInfo::routing_key(cmd("GET").arg("foo")),
+            Some(&b"foo"[..])
+        );
+        assert_eq!(
+            RoutingInfo::routing_key(cmd("EVAL").arg("script").arg(1).arg("foo")),
+            Some(&b"foo"[..])
+        );
+        assert_eq!(
+            RoutingInfo::routing_key(cmd("EVAL").arg("script").arg(0)),
+            None
+        );
+        assert_eq!(
+            RoutingInfo::routing_key(cmd("XREAD").arg("STREAMS").arg("foo").arg(0)),
+            Some(&b"foo"[..])
+        );
+        assert_eq!(RoutingInfo::routing_key(&cmd("PING")), None);
+    }
+
     #[test]
     fn test_multi_shard_keys_only() {
         let mut cmd = cmd("DEL");
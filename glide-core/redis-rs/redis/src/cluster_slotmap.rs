@@ -1,6 +1,6 @@
 use std::sync::Arc;
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Display,
     sync::atomic::AtomicUsize,
 };
@@ -8,6 +8,7 @@ use std::{
 use dashmap::DashMap;
 
 use crate::cluster_routing::{Route, ShardAddrs, Slot, SlotAddr};
+use crate::cluster_topology::{NodeHealth, NodeInfo};
 use crate::ErrorKind;
 use crate::RedisError;
 use crate::RedisResult;
@@ -32,6 +33,9 @@ pub enum ReadFromReplicaStrategy {
     /// Spread the read requests between replicas in the same client's Aviliablity zone in a round robin manner,
     /// falling back to other replicas or the primary if needed.
     AZAffinity(String),
+    /// Spread the read requests between replicas in the same client's availability zone in a round robin manner,
+    /// falling back to the primary if it's in the same zone, and then to nodes in other zones.
+    AZAffinityReplicasAndPrimary(String),
 }
 
 #[derive(Debug, Default)]
@@ -39,12 +43,15 @@ pub(crate) struct SlotMap {
     pub(crate) slots: BTreeMap<u16, SlotMapValue>,
     nodes_map: NodesMap,
     read_from_replica: ReadFromReplicaStrategy,
+    /// The metadata of the nodes, if the topology was discovered with `CLUSTER SHARDS`.
+    nodes_info: HashMap<String, NodeInfo>,
 }
 
 fn get_address_from_slot(
     slot: &SlotMapValue,
     read_from_replica: ReadFromReplicaStrategy,
     slot_addr: SlotAddr,
+    nodes_info: &HashMap<String, NodeInfo>,
 ) -> Arc<String> {
     let addrs = &slot.addrs;
     if slot_addr == SlotAddr::Master || addrs.replicas().is_empty() {
@@ -52,26 +59,67 @@ fn get_address_from_slot(
     }
     match read_from_replica {
         ReadFromReplicaStrategy::AlwaysFromPrimary => addrs.primary(),
-        ReadFromReplicaStrategy::RoundRobin => {
-            let index = slot
-                .last_used_replica
-                .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
-                % addrs.replicas().len();
-            addrs.replicas()[index].clone()
+        // The synchronous client doesn't know the nodes' AZs, so the AZ affinity strategies fall back to round robin.
+        ReadFromReplicaStrategy::RoundRobin
+        | ReadFromReplicaStrategy::AZAffinity(_)
+        | ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary(_) => {
+            let replicas = addrs.replicas();
+            for _ in 0..replicas.len() {
+                let index = slot
+                    .last_used_replica
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+                    % replicas.len();
+                if is_available_for_reads(nodes_info, &replicas[index]) {
+                    return replicas[index].clone();
+                }
+            }
+            addrs.primary()
         }
-        ReadFromReplicaStrategy::AZAffinity(_az) => todo!(), // Drop sync client
     }
 }
 
+fn is_available_for_reads(nodes_info: &HashMap<String, NodeInfo>, address: &str) -> bool {
+    nodes_info
+        .get(address)
+        .map_or(true, |node_info| node_info.health == NodeHealth::Online)
+}
+
 impl SlotMap {
     pub(crate) fn new_with_read_strategy(read_from_replica: ReadFromReplicaStrategy) -> Self {
         SlotMap {
             slots: BTreeMap::new(),
             nodes_map: DashMap::new(),
             read_from_replica,
+            nodes_info: HashMap::new(),
         }
     }
 
+    /// Sets the metadata of the nodes, as reported by `CLUSTER SHARDS`.
+    pub(crate) fn with_nodes_info(mut self, nodes_info: HashMap<String, NodeInfo>) -> Self {
+        self.nodes_info = nodes_info;
+        self
+    }
+
+    /// Returns the metadata of the node in `address`, if the topology was discovered with `CLUSTER SHARDS`.
+    pub(crate) fn node_info(&self, address: &str) -> Option<&NodeInfo> {
+        self.nodes_info.get(address)
+    }
+
+    /// Returns the addresses of the nodes by their ids, if the topology was discovered with `CLUSTER SHARDS`.
+    pub(crate) fn addresses_by_node_id(&self) -> HashMap<&str, &str> {
+        self.nodes_info
+            .iter()
+            .filter(|(_, node_info)| !node_info.id.is_empty())
+            .map(|(address, node_info)| (node_info.id.as_str(), address.as_str()))
+            .collect()
+    }
+
+    /// Returns false if the node in `address` is known to be unable to serve reads, such as a replica that is
+    /// still loading its dataset.
+    pub(crate) fn is_available_for_reads(&self, address: &str) -> bool {
+        is_available_for_reads(&self.nodes_info, address)
+    }
+
     pub(crate) fn new(slots: Vec<Slot>, read_from_replica: ReadFromReplicaStrategy) -> Self {
         let mut slot_map = SlotMap::new_with_read_strategy(read_from_replica);
         let mut shard_id = 0;
@@ -141,6 +189,7 @@ impl SlotMap {
                 slot_value,
                 self.read_from_replica.clone(),
                 route.slot_addr(),
+                &self.nodes_info,
             )
         })
     }
@@ -213,6 +262,7 @@ impl SlotMap {
                     slot_value,
                     self.read_from_replica.clone(),
                     slot_addr,
+                    &self.nodes_info,
                 ))
             } else {
                 None
@@ -697,6 +747,43 @@ mod tests_cluster_slotmap {
         );
     }
 
+    #[test]
+    fn test_slot_map_skips_replicas_unavailable_for_reads() {
+        let node_info = |id: &str, health| NodeInfo {
+            id: id.to_string(),
+            role: crate::cluster_topology::NodeRole::Replica,
+            health,
+            replication_offset: 0,
+        };
+        let slot_map =
+            get_slot_map(ReadFromReplicaStrategy::RoundRobin).with_nodes_info(HashMap::from([
+                (
+                    "replica1:6379".to_string(),
+                    node_info("id1", NodeHealth::Loading),
+                ),
+                (
+                    "replica2:6379".to_string(),
+                    node_info("id2", NodeHealth::Loading),
+                ),
+                (
+                    "replica3:6379".to_string(),
+                    node_info("id3", NodeHealth::Online),
+                ),
+            ]));
+
+        // All of the shard's replicas are loading, so the primary is used.
+        let route = Route::new(1, SlotAddr::ReplicaOptional);
+        assert_eq!("node1:6379", *slot_map.slot_addr_for_route(&route).unwrap());
+
+        let route = Route::new(1002, SlotAddr::ReplicaOptional);
+        for _ in 0..3 {
+            assert_eq!(
+                "replica3:6379",
+                *slot_map.slot_addr_for_route(&route).unwrap()
+            );
+        }
+    }
+
     #[test]
     fn test_slot_map_rotate_read_replicas() {
         let slot_map = get_slot_map(ReadFromReplicaStrategy::RoundRobin);
@@ -716,6 +803,29 @@ mod tests_cluster_slotmap {
         );
     }
 
+    #[test]
+    fn test_slot_map_az_affinity_falls_back_to_round_robin() {
+        for read_from_replica in [
+            ReadFromReplicaStrategy::AZAffinity("use-1a".to_string()),
+            ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary("use-1a".to_string()),
+        ] {
+            let slot_map = get_slot_map(read_from_replica);
+            let route = Route::new(1002, SlotAddr::ReplicaOptional);
+            let mut addresses = vec![
+                slot_map.slot_addr_for_route(&route).unwrap(),
+                slot_map.slot_addr_for_route(&route).unwrap(),
+            ];
+            addresses.sort();
+            assert_eq!(
+                addresses,
+                vec![
+                    Arc::new("replica2:6379".to_string()),
+                    Arc::new("replica3:6379".to_string())
+                ]
+            );
+        }
+    }
+
     #[test]
     fn test_get_slots_of_node() {
         let slot_map = get_slot_map(ReadFromReplicaStrategy::AlwaysFromPrimary);
@@ -1,16 +1,16 @@
 //! This module provides the functionality to refresh and calculate the cluster topology for Redis Cluster.
 
 use crate::cluster::get_connection_addr;
+use crate::cluster_client::AddressMapper;
 #[cfg(feature = "cluster-async")]
 use crate::cluster_client::SlotsRefreshRateLimit;
-use crate::cluster_routing::Slot;
+use crate::cluster_routing::{Slot, SlotAddr};
 use crate::cluster_slotmap::{ReadFromReplicaStrategy, SlotMap};
-use crate::{cluster::TlsMode, ErrorKind, RedisError, RedisResult, Value};
+use crate::{cluster::TlsMode, ErrorKind, InfoDict, RedisError, RedisResult, Value};
 #[cfg(all(feature = "cluster-async", not(feature = "tokio-comp")))]
 use async_std::sync::RwLock;
-use std::collections::{hash_map::DefaultHasher, HashMap};
+use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
-use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 #[cfg(all(feature = "cluster-async", feature = "tokio-comp"))]
@@ -35,11 +35,18 @@ pub const DEFAULT_SLOTS_REFRESH_MAX_JITTER_MILLI: u64 = 15 * 1000; // 15 seconds
 pub(crate) const SLOT_SIZE: u16 = 16384;
 pub(crate) type TopologyHash = u64;
 
+/// A slot refresh, whose result is shared by all the callers waiting for it. The reference doesn't keep
+/// the refresh alive, so once all of its callers stop waiting for it, the refresh is dropped, along with
+/// the references it holds to the client's state.
+#[cfg(feature = "cluster-async")]
+pub(crate) type WeakSlotRefresh =
+    futures::future::WeakShared<futures::future::BoxFuture<'static, Result<(), Arc<RedisError>>>>;
+
 /// Represents the state of slot refresh operations.
 #[cfg(feature = "cluster-async")]
 pub(crate) struct SlotRefreshState {
-    /// Indicates if a slot refresh is currently in progress
-    pub(crate) in_progress: AtomicBool,
+    /// The slot refresh currently in progress, joined by the callers that request a refresh meanwhile
+    pub(crate) in_progress: std::sync::Mutex<Option<WeakSlotRefresh>>,
     /// The last slot refresh run timestamp
     pub(crate) last_run: Arc<RwLock<Option<SystemTime>>>,
     pub(crate) rate_limiter: SlotsRefreshRateLimit,
@@ -49,18 +56,169 @@ pub(crate) struct SlotRefreshState {
 impl SlotRefreshState {
     pub(crate) fn new(rate_limiter: SlotsRefreshRateLimit) -> Self {
         Self {
-            in_progress: AtomicBool::new(false),
+            in_progress: std::sync::Mutex::new(None),
             last_run: Arc::new(RwLock::new(None)),
             rate_limiter,
         }
     }
 }
 
+/// The role of a node in its shard, as reported by `CLUSTER SHARDS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeRole {
+    /// The node is the primary of its shard.
+    Primary,
+    /// The node replicates the primary of its shard.
+    Replica,
+}
+
+/// The health of a node, as reported by `CLUSTER SHARDS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeHealth {
+    /// The node is available.
+    Online,
+    /// The node is loading its dataset, and can't serve reads yet.
+    Loading,
+    /// The node is considered failed by the cluster.
+    Failed,
+}
+
+/// The metadata of a node, as reported by `CLUSTER SHARDS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeInfo {
+    /// The node's id, which doesn't change when the node's address changes.
+    pub id: String,
+    /// The node's role in its shard.
+    pub role: NodeRole,
+    /// The node's health.
+    pub health: NodeHealth,
+    /// The node's replication offset.
+    pub replication_offset: i64,
+}
+
+/// The state of the client's connection to a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeConnectionState {
+    /// The connection was re-established after being lost.
+    Connected,
+    /// The connection was lost, or couldn't be established.
+    Disconnected,
+}
+
+/// A change in the cluster's topology, as observed by the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopologyEvent {
+    /// The primary that owns the slots in `start..=end` changed. `None` means that the slots weren't, or are no longer, covered.
+    SlotsMoved {
+        /// The first slot in the range.
+        start: u16,
+        /// The last slot in the range.
+        end: u16,
+        /// The address of the primary that owned the slots.
+        previous_primary: Option<String>,
+        /// The address of the primary that owns the slots.
+        primary: Option<String>,
+    },
+    /// A replica was promoted to be the primary of its shard.
+    Failover {
+        /// The address of the shard's previous primary.
+        previous_primary: String,
+        /// The address of the promoted replica.
+        primary: String,
+    },
+    /// A node joined the topology.
+    NodeAdded(String),
+    /// A node left the topology.
+    NodeRemoved(String),
+    /// The client's connection to a node changed its state.
+    ConnectionStateChanged {
+        /// The address of the node.
+        address: String,
+        /// The new state of the connection.
+        state: NodeConnectionState,
+    },
+}
+
+/// A slot that is migrated between two nodes, as learned from an `ASK` redirect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SlotMigration {
+    /// The address of the node the slot is migrated from.
+    pub(crate) source: String,
+    /// The address of the node the slot is migrated to.
+    pub(crate) target: String,
+}
+
+/// The replication state of a node, as reported by `INFO REPLICATION`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ReplicationState {
+    /// The replication offset the node has processed.
+    pub(crate) offset: i64,
+    /// Whether the node's link to its primary is up. Always true for primaries.
+    pub(crate) link_up: bool,
+}
+
+impl ReplicationState {
+    pub(crate) fn from_info(info: &InfoDict) -> Option<Self> {
+        match info.get::<String>("role")?.as_str() {
+            "master" => Some(Self {
+                offset: info.get("master_repl_offset")?,
+                link_up: true,
+            }),
+            "slave" => Some(Self {
+                // Older servers don't report `slave_repl_offset`.
+                offset: info
+                    .get("slave_repl_offset")
+                    .or_else(|| info.get("master_repl_offset"))?,
+                link_up: info.get::<String>("master_link_status").as_deref() == Some("up"),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this replica shouldn't serve reads, given its primary's state.
+    pub(crate) fn is_stale(&self, primary: &ReplicationState, max_offset_lag: u64) -> bool {
+        !self.link_up || primary.offset.saturating_sub(self.offset) > max_offset_lag as i64
+    }
+}
+
+/// A node's reply to a topology discovery command.
+#[derive(Debug)]
+pub(crate) enum TopologyResponse {
+    ClusterShards(Value),
+    ClusterSlots(Value),
+}
+
+impl TopologyResponse {
+    fn parse(
+        &self,
+        tls: Option<TlsMode>,
+        addr_of_answering_node: &str,
+        address_mapper: Option<&AddressMapper>,
+    ) -> RedisResult<(u16, Vec<Slot>, HashMap<String, NodeInfo>)> {
+        let (count, mut slots, nodes_info) = match self {
+            TopologyResponse::ClusterShards(value) => {
+                parse_and_count_shards(value, tls, addr_of_answering_node)?
+            }
+            TopologyResponse::ClusterSlots(value) => {
+                let (count, slots) = parse_and_count_slots(value, tls, addr_of_answering_node)?;
+                (count, slots, HashMap::new())
+            }
+        };
+        let nodes_info = match address_mapper {
+            Some(mapper) => map_topology_addresses(mapper, &mut slots, nodes_info),
+            None => nodes_info,
+        };
+        Ok((count, slots, nodes_info))
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct TopologyView {
     pub(crate) hash_value: TopologyHash,
     pub(crate) nodes_count: u16,
     slots_and_count: (u16, Vec<Slot>),
+    // Not part of the hash, since the replication offsets reported by different nodes always differ.
+    nodes_info: HashMap<String, NodeInfo>,
 }
 
 impl PartialEq for TopologyView {
@@ -190,6 +348,190 @@ pub(crate) fn parse_and_count_slots(
     Ok((count, slots))
 }
 
+/// Returns the entries of a map reply, which is an array of alternating keys and values in RESP2.
+fn map_entries(value: &Value) -> Option<Vec<(&Value, &Value)>> {
+    match value {
+        Value::Map(entries) => Some(entries.iter().map(|(key, value)| (key, value)).collect()),
+        Value::Array(items) => Some(
+            items
+                .chunks_exact(2)
+                .map(|pair| (&pair[0], &pair[1]))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn field<'a>(entries: &[(&Value, &'a Value)], name: &str) -> Option<&'a Value> {
+    entries.iter().find_map(|(key, value)| {
+        let matches = match key {
+            Value::BulkString(key) => key == name.as_bytes(),
+            Value::SimpleString(key) => key == name,
+            _ => false,
+        };
+        matches.then_some(*value)
+    })
+}
+
+fn string_field(entries: &[(&Value, &Value)], name: &str) -> Option<String> {
+    match field(entries, name)? {
+        Value::BulkString(value) => Some(String::from_utf8_lossy(value).into_owned()),
+        Value::SimpleString(value) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn int_value(value: &Value) -> Option<i64> {
+    match value {
+        Value::Int(value) => Some(*value),
+        Value::BulkString(value) => std::str::from_utf8(value).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn int_field(entries: &[(&Value, &Value)], name: &str) -> Option<i64> {
+    int_value(field(entries, name)?)
+}
+
+// Parse a node of a `CLUSTER SHARDS` reply into its address and metadata.
+fn parse_shard_node(
+    node: &Value,
+    tls: Option<TlsMode>,
+    addr_of_answering_node: &str,
+) -> Option<(String, NodeInfo)> {
+    let entries = map_entries(node)?;
+    // The endpoint is the preferred endpoint of the node, which is the first endpoint returned by `CLUSTER SLOTS`.
+    // Like in `CLUSTER SLOTS`, an empty endpoint is the answering node, and "?" is an unknown endpoint.
+    let hostname = match string_field(&entries, "endpoint").or_else(|| string_field(&entries, "ip"))
+    {
+        Some(endpoint) if endpoint == "?" => return None,
+        Some(endpoint) if !endpoint.is_empty() => endpoint,
+        _ => addr_of_answering_node.to_string(),
+    };
+    if hostname.is_empty() {
+        return None;
+    }
+    let (preferred_port, other_port) = if tls.is_some() {
+        ("tls-port", "port")
+    } else {
+        ("port", "tls-port")
+    };
+    let port = int_field(&entries, preferred_port)
+        .filter(|port| *port > 0)
+        .or_else(|| int_field(&entries, other_port))
+        .filter(|port| *port > 0)?;
+
+    let role = match string_field(&entries, "role").as_deref() {
+        Some("master") | Some("primary") => NodeRole::Primary,
+        _ => NodeRole::Replica,
+    };
+    let health = match string_field(&entries, "health").as_deref() {
+        Some("loading") => NodeHealth::Loading,
+        Some("fail") | Some("failed") => NodeHealth::Failed,
+        // Unknown states are treated as online, so that nodes aren't dropped from the topology by newer servers.
+        _ => NodeHealth::Online,
+    };
+    let node_info = NodeInfo {
+        id: string_field(&entries, "id").unwrap_or_default(),
+        role,
+        health,
+        replication_offset: int_field(&entries, "replication-offset").unwrap_or_default(),
+    };
+    Some((
+        get_connection_addr(hostname, port as u16, tls, None).to_string(),
+        node_info,
+    ))
+}
+
+// Parse slot data and node metadata from a raw `CLUSTER SHARDS` reply.
+// Failed replicas are left out of the topology, like in `CLUSTER SLOTS`, but their metadata is kept.
+pub(crate) fn parse_and_count_shards(
+    raw_shards_resp: &Value,
+    tls: Option<TlsMode>,
+    // The DNS address of the node from which `raw_shards_resp` was received.
+    addr_of_answering_node: &str,
+) -> RedisResult<(u16, Vec<Slot>, HashMap<String, NodeInfo>)> {
+    let mut slots = Vec::new();
+    let mut nodes_info = HashMap::new();
+    let mut count = 0;
+
+    if let Value::Array(shards) = raw_shards_resp {
+        for shard in shards {
+            let Some(entries) = map_entries(shard) else {
+                continue;
+            };
+            let slot_ranges: Vec<(u16, u16)> = match field(&entries, "slots") {
+                Some(Value::Array(bounds)) => bounds
+                    .chunks_exact(2)
+                    .filter_map(|range| {
+                        Some((int_value(&range[0])? as u16, int_value(&range[1])? as u16))
+                    })
+                    .collect(),
+                _ => continue,
+            };
+            let Some(Value::Array(nodes)) = field(&entries, "nodes") else {
+                continue;
+            };
+
+            let mut primary = None;
+            let mut replicas = Vec::new();
+            for node in nodes {
+                let Some((address, node_info)) =
+                    parse_shard_node(node, tls, addr_of_answering_node)
+                else {
+                    continue;
+                };
+                match (node_info.role, node_info.health) {
+                    (NodeRole::Primary, _) => primary = Some(address.clone()),
+                    (NodeRole::Replica, NodeHealth::Failed) => {}
+                    (NodeRole::Replica, _) => replicas.push(address.clone()),
+                }
+                nodes_info.insert(address, node_info);
+            }
+
+            // Shards without slots, such as new empty shards, aren't part of the slot map.
+            let Some(primary) = primary else {
+                continue;
+            };
+            // we sort the replicas, for the same reason as in `parse_and_count_slots`.
+            replicas.sort_unstable();
+            for (start, end) in slot_ranges {
+                count += end - start;
+                slots.push(Slot::new(start, end, primary.clone(), replicas.clone()));
+            }
+        }
+    }
+    if slots.is_empty() {
+        return Err(RedisError::from((
+            ErrorKind::ResponseError,
+            "Error parsing shards: No healthy node found",
+            format!("Raw shards response: {:?}", raw_shards_resp),
+        )));
+    }
+    // Different nodes might return the shards in a different order, which shouldn't make the views unequal.
+    slots.sort_unstable_by_key(|slot| slot.start);
+
+    Ok((count, slots, nodes_info))
+}
+
+// Replaces the addresses announced by the nodes with the addresses the client should connect to.
+pub(crate) fn map_topology_addresses(
+    mapper: &AddressMapper,
+    slots: &mut [Slot],
+    nodes_info: HashMap<String, NodeInfo>,
+) -> HashMap<String, NodeInfo> {
+    for slot in slots.iter_mut() {
+        slot.master = mapper.map_address(&slot.master);
+        for replica in slot.replicas.iter_mut() {
+            *replica = mapper.map_address(replica);
+        }
+    }
+    nodes_info
+        .into_iter()
+        .map(|(address, node_info)| (mapper.map_address(&address), node_info))
+        .collect()
+}
+
 fn calculate_hash<T: Hash>(t: &T) -> u64 {
     let mut s = DefaultHasher::new();
     t.hash(&mut s);
@@ -197,20 +539,23 @@ fn calculate_hash<T: Hash>(t: &T) -> u64 {
 }
 
 pub(crate) fn calculate_topology<'a>(
-    topology_views: impl Iterator<Item = (&'a str, &'a Value)>,
+    topology_views: impl Iterator<Item = (&'a str, &'a TopologyResponse)>,
     curr_retry: usize,
     tls_mode: Option<TlsMode>,
     num_of_queried_nodes: usize,
     read_from_replica: ReadFromReplicaStrategy,
+    address_mapper: Option<&AddressMapper>,
 ) -> RedisResult<(SlotMap, TopologyHash)> {
     let mut hash_view_map = HashMap::new();
     for (host, view) in topology_views {
-        if let Ok(slots_and_count) = parse_and_count_slots(view, tls_mode, host) {
+        if let Ok((count, slots, nodes_info)) = view.parse(tls_mode, host, address_mapper) {
+            let slots_and_count = (count, slots);
             let hash_value = calculate_hash(&slots_and_count);
             let topology_entry = hash_view_map.entry(hash_value).or_insert(TopologyView {
                 hash_value,
                 nodes_count: 0,
                 slots_and_count,
+                nodes_info,
             });
             topology_entry.nodes_count += 1;
         }
@@ -257,7 +602,8 @@ pub(crate) fn calculate_topology<'a>(
         );
         let slots_data = most_frequent_topology.slots_and_count.1;
         Ok((
-            SlotMap::new(slots_data, read_from_replica),
+            SlotMap::new(slots_data, read_from_replica)
+                .with_nodes_info(most_frequent_topology.nodes_info),
             most_frequent_topology.hash_value,
         ))
     };
@@ -287,6 +633,89 @@ pub(crate) fn calculate_topology<'a>(
     }
 }
 
+// The first and last slot of a range that moved, and its previous and current primaries.
+type MovedRange = (u16, u16, Option<Arc<String>>, Option<Arc<String>>);
+
+// Lists the changes between two slot maps. Slots that moved to a promoted replica are reported by the failover alone.
+pub(crate) fn diff_slot_maps(previous: &SlotMap, current: &SlotMap) -> Vec<TopologyEvent> {
+    let mut events = Vec::new();
+
+    let mut failovers = HashSet::new();
+    for primary in current.addresses_for_all_primaries() {
+        let Some(previous_primary) = previous
+            .nodes_map()
+            .get(&primary)
+            .map(|shard_addrs| shard_addrs.primary())
+        else {
+            continue;
+        };
+        if previous_primary != primary && !current.is_primary(&previous_primary) {
+            events.push(TopologyEvent::Failover {
+                previous_primary: previous_primary.to_string(),
+                primary: primary.to_string(),
+            });
+            failovers.insert((previous_primary, primary));
+        }
+    }
+
+    let mut moved_range: Option<MovedRange> = None;
+    for slot in 0..SLOT_SIZE {
+        let previous_primary = previous.node_address_for_slot(slot, SlotAddr::Master);
+        let primary = current.node_address_for_slot(slot, SlotAddr::Master);
+        let moved = previous_primary != primary
+            && !matches!((&previous_primary, &primary), (Some(previous_primary), Some(primary))
+                if failovers.contains(&(previous_primary.clone(), primary.clone())));
+        match &mut moved_range {
+            Some((_, end, range_previous, range_current))
+                if moved
+                    && *end + 1 == slot
+                    && *range_previous == previous_primary
+                    && *range_current == primary =>
+            {
+                *end = slot;
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(range) = moved_range.take() {
+            events.push(slots_moved_event(range));
+        }
+        if moved {
+            moved_range = Some((slot, slot, previous_primary, primary));
+        }
+    }
+    if let Some(range) = moved_range {
+        events.push(slots_moved_event(range));
+    }
+
+    let previous_nodes = previous.all_node_addresses();
+    let current_nodes = current.all_node_addresses();
+    let mut added: Vec<_> = current_nodes.difference(&previous_nodes).collect();
+    added.sort_unstable();
+    events.extend(
+        added
+            .into_iter()
+            .map(|address| TopologyEvent::NodeAdded(address.to_string())),
+    );
+    let mut removed: Vec<_> = previous_nodes.difference(&current_nodes).collect();
+    removed.sort_unstable();
+    events.extend(
+        removed
+            .into_iter()
+            .map(|address| TopologyEvent::NodeRemoved(address.to_string())),
+    );
+    events
+}
+
+fn slots_moved_event((start, end, previous_primary, primary): MovedRange) -> TopologyEvent {
+    TopologyEvent::SlotsMoved {
+        start,
+        end,
+        previous_primary: previous_primary.map(|address| address.to_string()),
+        primary: primary.map(|address| address.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -466,8 +895,8 @@ mod tests {
         TwoNodesViewFullCoverage,
         TwoNodesViewMissingSlots,
     }
-    fn get_view(view_type: &ViewType) -> (&str, Value) {
-        match view_type {
+    fn get_view(view_type: &ViewType) -> (&str, TopologyResponse) {
+        let (addr, value) = match view_type {
             ViewType::SingleNodeViewFullCoverage => (
                 "first",
                 Value::Array(vec![slot_value(0, 16383, "node1", 6379)]),
@@ -490,7 +919,8 @@ mod tests {
                     slot_value(4001, 16383, "node4", 6382),
                 ]),
             ),
-        }
+        };
+        (addr, TopologyResponse::ClusterSlots(value))
     }
 
     fn get_node_addr(name: &str, port: u16) -> Arc<ShardAddrs> {
@@ -526,6 +956,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            None,
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -549,6 +980,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            None,
         );
         assert!(topology_view.is_err());
     }
@@ -568,6 +1000,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            None,
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -591,6 +1024,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            None,
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -615,6 +1049,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            None,
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -639,6 +1074,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            None,
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -646,4 +1082,332 @@ mod tests {
         let expected = vec![node_1];
         assert_eq!(res, expected);
     }
+
+    fn shard_node(endpoint: &str, port: i64, id: &str, role: &str, health: &str) -> Value {
+        Value::Array(vec![
+            Value::BulkString(b"id".to_vec()),
+            Value::BulkString(id.as_bytes().to_vec()),
+            Value::BulkString(b"port".to_vec()),
+            Value::Int(port),
+            Value::BulkString(b"ip".to_vec()),
+            Value::BulkString(b"10.0.0.1".to_vec()),
+            Value::BulkString(b"endpoint".to_vec()),
+            Value::BulkString(endpoint.as_bytes().to_vec()),
+            Value::BulkString(b"role".to_vec()),
+            Value::BulkString(role.as_bytes().to_vec()),
+            Value::BulkString(b"replication-offset".to_vec()),
+            Value::Int(100),
+            Value::BulkString(b"health".to_vec()),
+            Value::BulkString(health.as_bytes().to_vec()),
+        ])
+    }
+
+    fn shard_value(slot_ranges: Vec<(i64, i64)>, nodes: Vec<Value>) -> Value {
+        Value::Array(vec![
+            Value::BulkString(b"slots".to_vec()),
+            Value::Array(
+                slot_ranges
+                    .into_iter()
+                    .flat_map(|(start, end)| [Value::Int(start), Value::Int(end)])
+                    .collect(),
+            ),
+            Value::BulkString(b"nodes".to_vec()),
+            Value::Array(nodes),
+        ])
+    }
+
+    #[test]
+    fn parse_shards_keeps_node_metadata_and_skips_failed_replicas() {
+        let view = Value::Array(vec![
+            shard_value(
+                vec![(0, 8000)],
+                vec![
+                    shard_node("primary1", 6379, "id1", "master", "online"),
+                    shard_node("replica1", 6379, "id2", "replica", "loading"),
+                    shard_node("replica2", 6379, "id3", "replica", "failed"),
+                ],
+            ),
+            // A shard without slots isn't part of the topology.
+            shard_value(
+                vec![],
+                vec![shard_node("primary3", 6379, "id5", "master", "online")],
+            ),
+            shard_value(
+                vec![(8001, 10000), (10001, 16383)],
+                vec![shard_node("", 6380, "id4", "master", "online")],
+            ),
+        ]);
+
+        let (count, slots, nodes_info) = parse_and_count_shards(&view, None, "node").unwrap();
+        assert_eq!(count, 16381);
+        let slots: Vec<_> = slots
+            .iter()
+            .map(|slot| (slot.start, slot.end, slot.master(), slot.replicas()))
+            .collect();
+        assert_eq!(
+            slots,
+            vec![
+                (0, 8000, "primary1:6379", vec!["replica1:6379".to_string()]),
+                (8001, 10000, "node:6380", vec![]),
+                (10001, 16383, "node:6380", vec![]),
+            ]
+        );
+        assert_eq!(
+            nodes_info.get("replica1:6379"),
+            Some(&NodeInfo {
+                id: "id2".to_string(),
+                role: NodeRole::Replica,
+                health: NodeHealth::Loading,
+                replication_offset: 100,
+            })
+        );
+        assert_eq!(
+            nodes_info.get("replica2:6379").map(|info| info.health),
+            Some(NodeHealth::Failed)
+        );
+        assert_eq!(
+            nodes_info.get("node:6380").map(|info| info.role),
+            Some(NodeRole::Primary)
+        );
+    }
+
+    #[test]
+    fn parse_shards_in_resp3_and_different_order_returns_the_same_view() {
+        let resp2_view = Value::Array(vec![
+            shard_value(
+                vec![(0, 8000)],
+                vec![shard_node("primary1", 6379, "id1", "master", "online")],
+            ),
+            shard_value(
+                vec![(8001, 16383)],
+                vec![shard_node("primary2", 6379, "id2", "master", "online")],
+            ),
+        ]);
+        let to_map = |value: &Value| match value {
+            Value::Array(items) => Value::Map(
+                items
+                    .chunks_exact(2)
+                    .map(|pair| (pair[0].clone(), pair[1].clone()))
+                    .collect(),
+            ),
+            _ => unreachable!(),
+        };
+        let Value::Array(shards) = &resp2_view else {
+            unreachable!()
+        };
+        let resp3_view = Value::Array(
+            shards
+                .iter()
+                .rev()
+                .map(|shard| {
+                    let Value::Array(shard) = shard else {
+                        unreachable!()
+                    };
+                    let Value::Array(nodes) = &shard[3] else {
+                        unreachable!()
+                    };
+                    Value::Map(vec![
+                        (shard[0].clone(), shard[1].clone()),
+                        (
+                            shard[2].clone(),
+                            Value::Array(nodes.iter().map(to_map).collect()),
+                        ),
+                    ])
+                })
+                .collect(),
+        );
+
+        let (count1, slots1, _) = parse_and_count_shards(&resp2_view, None, "node").unwrap();
+        let (count2, slots2, _) = parse_and_count_shards(&resp3_view, None, "node").unwrap();
+        assert_eq!(
+            calculate_hash(&(count1, slots1)),
+            calculate_hash(&(count2, slots2))
+        );
+    }
+
+    #[test]
+    fn parse_shards_without_primaries_is_an_error() {
+        let view = Value::Array(vec![shard_value(
+            vec![(0, 16383)],
+            vec![shard_node("replica1", 6379, "id2", "replica", "online")],
+        )]);
+        assert!(parse_and_count_shards(&view, None, "node").is_err());
+    }
+
+    #[test]
+    fn test_topology_calculator_keeps_node_metadata_from_cluster_shards() {
+        let view = Value::Array(vec![shard_value(
+            vec![(0, 16383)],
+            vec![
+                shard_node("primary1", 6379, "id1", "master", "online"),
+                shard_node("replica1", 6379, "id2", "replica", "loading"),
+            ],
+        )]);
+        let topology_results = [
+            ("first", TopologyResponse::ClusterShards(view.clone())),
+            ("second", TopologyResponse::ClusterShards(view)),
+        ];
+
+        let (topology_view, _) = calculate_topology(
+            topology_results.iter().map(|(addr, value)| (*addr, value)),
+            1,
+            None,
+            2,
+            ReadFromReplicaStrategy::RoundRobin,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            topology_view
+                .node_info("primary1:6379")
+                .map(|info| info.id.as_str()),
+            Some("id1")
+        );
+        assert_eq!(
+            topology_view.addresses_by_node_id().get("id2").copied(),
+            Some("replica1:6379")
+        );
+        assert!(!topology_view.is_available_for_reads("replica1:6379"));
+        assert!(topology_view.is_available_for_reads("primary1:6379"));
+    }
+
+    #[test]
+    fn test_topology_calculator_maps_node_addresses() {
+        let view = Value::Array(vec![shard_value(
+            vec![(0, 16383)],
+            vec![
+                shard_node("primary1", 6379, "id1", "master", "online"),
+                shard_node("replica1", 6379, "id2", "replica", "online"),
+            ],
+        )]);
+        let topology_results = [("first", TopologyResponse::ClusterShards(view))];
+        let mapper = AddressMapper::from_table(HashMap::from([(
+            ("primary1".to_string(), 6379),
+            ("127.0.0.1".to_string(), 7000),
+        )]));
+
+        let (topology_view, _) = calculate_topology(
+            topology_results.iter().map(|(addr, value)| (*addr, value)),
+            1,
+            None,
+            1,
+            ReadFromReplicaStrategy::AlwaysFromPrimary,
+            Some(&mapper),
+        )
+        .unwrap();
+        let shard = Arc::new(ShardAddrs::new(
+            "127.0.0.1:7000".to_string().into(),
+            vec!["replica1:6379".to_string().into()],
+        ));
+        // Both the primary and the replica are keyed to the same shard.
+        assert_eq!(
+            collect_shard_addrs(&topology_view),
+            vec![shard.clone(), shard]
+        );
+        assert_eq!(
+            topology_view.addresses_by_node_id().get("id1").copied(),
+            Some("127.0.0.1:7000")
+        );
+    }
+
+    fn slot_map(slots: &[(u16, u16, &str, &[&str])]) -> SlotMap {
+        SlotMap::new(
+            slots
+                .iter()
+                .map(|(start, end, primary, replicas)| {
+                    Slot::new(
+                        *start,
+                        *end,
+                        primary.to_string(),
+                        replicas.iter().map(|replica| replica.to_string()).collect(),
+                    )
+                })
+                .collect(),
+            ReadFromReplicaStrategy::AlwaysFromPrimary,
+        )
+    }
+
+    #[test]
+    fn test_diff_slot_maps_reports_failover_without_moved_slots() {
+        let previous = slot_map(&[
+            (0, 8191, "node1:6379", &["node2:6379"]),
+            (8192, 16383, "node3:6379", &[]),
+        ]);
+        let current = slot_map(&[
+            (0, 8191, "node2:6379", &["node1:6379"]),
+            (8192, 16383, "node3:6379", &[]),
+        ]);
+        assert_eq!(
+            diff_slot_maps(&previous, &current),
+            vec![TopologyEvent::Failover {
+                previous_primary: "node1:6379".to_string(),
+                primary: "node2:6379".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_slot_maps_reports_moved_slots_and_nodes() {
+        let previous = slot_map(&[
+            (0, 8191, "node1:6379", &[]),
+            (8192, 16383, "node2:6379", &["node3:6379"]),
+        ]);
+        let current = slot_map(&[
+            (0, 99, "node2:6379", &["node4:6379"]),
+            (100, 8191, "node1:6379", &[]),
+            (8192, 16383, "node2:6379", &["node4:6379"]),
+        ]);
+        assert_eq!(
+            diff_slot_maps(&previous, &current),
+            vec![
+                TopologyEvent::SlotsMoved {
+                    start: 0,
+                    end: 99,
+                    previous_primary: Some("node1:6379".to_string()),
+                    primary: Some("node2:6379".to_string()),
+                },
+                TopologyEvent::NodeAdded("node4:6379".to_string()),
+                TopologyEvent::NodeRemoved("node3:6379".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replication_state_from_info() {
+        let primary = ReplicationState::from_info(&InfoDict::new(
+            "# Replication\r\nrole:master\r\nconnected_slaves:1\r\nmaster_repl_offset:1000\r\n",
+        ))
+        .unwrap();
+        assert_eq!(
+            primary,
+            ReplicationState {
+                offset: 1000,
+                link_up: true
+            }
+        );
+
+        let replica = ReplicationState::from_info(&InfoDict::new(
+            "# Replication\r\nrole:slave\r\nmaster_link_status:down\r\nslave_repl_offset:900\r\nmaster_repl_offset:900\r\n",
+        ))
+        .unwrap();
+        assert_eq!(
+            replica,
+            ReplicationState {
+                offset: 900,
+                link_up: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_replica_is_stale_when_lagging_or_disconnected() {
+        let primary = ReplicationState {
+            offset: 1000,
+            link_up: true,
+        };
+        let replica = |offset, link_up| ReplicationState { offset, link_up };
+        assert!(!replica(900, true).is_stale(&primary, 100));
+        assert!(replica(899, true).is_stale(&primary, 100));
+        assert!(replica(1000, false).is_stale(&primary, 100));
+    }
 }
@@ -242,13 +242,26 @@ where
 }
 
 fn write_command<'a, I>(cmd: &mut (impl ?Sized + io::Write), args: I, cursor: u64) -> io::Result<()>
+where
+    I: IntoIterator<Item = Arg<&'a [u8]>> + Clone + ExactSizeIterator,
+{
+    let args_count = args.len();
+    write_command_with_args_count(cmd, args, args_count, cursor)
+}
+
+fn write_command_with_args_count<'a, I>(
+    cmd: &mut (impl ?Sized + io::Write),
+    args: I,
+    args_count: usize,
+    cursor: u64,
+) -> io::Result<()>
 where
     I: IntoIterator<Item = Arg<&'a [u8]>> + Clone + ExactSizeIterator,
 {
     let mut buf = ::itoa::Buffer::new();
 
     cmd.write_all(b"*")?;
-    let s = buf.format(args.len());
+    let s = buf.format(args_count);
     cmd.write_all(s.as_bytes())?;
     cmd.write_all(b"\r\n")?;
 
@@ -414,6 +427,23 @@ impl Cmd {
         write_command(cmd, self.args_iter(), self.cursor.unwrap_or(0)).unwrap()
     }
 
+    /// Writes the packed command followed by the header of an additional trailing argument of `streamed_arg_len` bytes.
+    /// The caller is expected to write the argument's payload and a terminating CRLF right after it.
+    #[cfg(feature = "aio")]
+    pub(crate) fn write_packed_command_with_streamed_arg(
+        &self,
+        cmd: &mut Vec<u8>,
+        streamed_arg_len: usize,
+    ) {
+        let args = self.args_iter();
+        let args_count = args.len() + 1;
+        write_command_with_args_count(cmd, args, args_count, self.cursor.unwrap_or(0)).unwrap();
+        let mut buf = ::itoa::Buffer::new();
+        cmd.push(b'$');
+        cmd.extend_from_slice(buf.format(streamed_arg_len).as_bytes());
+        cmd.extend_from_slice(b"\r\n");
+    }
+
     /// Like `get_packed_command` but replaces the cursor with the
     /// provided value.  If the command is not in scan mode, `None`
     /// is returned.
@@ -681,4 +711,15 @@ mod tests {
         assert_eq!(c.arg_idx(3), None);
         assert_eq!(c.arg_idx(4), None);
     }
+
+    #[cfg(feature = "aio")]
+    #[test]
+    fn test_write_packed_command_with_streamed_arg() {
+        let mut c = Cmd::new();
+        c.arg("SET").arg("foo");
+
+        let mut packed = Vec::new();
+        c.write_packed_command_with_streamed_arg(&mut packed, 42);
+        assert_eq!(packed, b"*3\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$42\r\n");
+    }
 }
@@ -418,7 +418,13 @@ pub use crate::types::{
 #[cfg(feature = "aio")]
 #[cfg_attr(docsrs, doc(cfg(feature = "aio")))]
 pub use crate::{
-    cmd::AsyncIter, commands::AsyncCommands, parser::parse_redis_value_async, types::RedisFuture,
+    cmd::AsyncIter,
+    commands::AsyncCommands,
+    parser::{
+        parse_redis_value_async, parse_redis_value_streamed_async, StreamedValue,
+        DEFAULT_STREAM_CHUNK_SIZE,
+    },
+    types::RedisFuture,
 };
 
 mod macros;
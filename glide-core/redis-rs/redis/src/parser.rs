@@ -310,6 +310,18 @@ mod aio_support {
     use tokio::io::AsyncRead;
     use tokio_util::codec::{Decoder, Encoder};
 
+    /// The default size of the chunks handed to the callback of [`parse_redis_value_streamed_async`].
+    pub const DEFAULT_STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+    /// The result of reading a reply with [`parse_redis_value_streamed_async`].
+    #[derive(Debug, PartialEq)]
+    pub enum StreamedValue {
+        /// The reply was a bulk string, and its payload of the given length was passed to the chunk callback.
+        Streamed(usize),
+        /// The reply wasn't a bulk string, and was parsed into a regular value.
+        Value(Value),
+    }
+
     #[derive(Default)]
     pub struct ValueCodec {
         state: AnySendSyncPartialState,
@@ -399,6 +411,105 @@ mod aio_support {
             Ok(result) => result.try_into(),
         }
     }
+
+    type ValueDecoder = combine::stream::Decoder<AnySendSyncPartialState, PointerOffset<[u8]>>;
+
+    /// Reads more bytes from `read` into the decoder's buffer.
+    async fn fill_decoder_buffer<R>(decoder: &mut ValueDecoder, read: &mut R) -> RedisResult<()>
+    where
+        R: AsyncRead + std::marker::Unpin,
+    {
+        let buffered = decoder.buffer().len();
+        decoder
+            .__before_parse_tokio(std::pin::Pin::new(&mut *read))
+            .await?;
+        if decoder.buffer().len() == buffered {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(())
+    }
+
+    /// Parses a redis value asynchronously, streaming the payload of a bulk string reply.
+    ///
+    /// If the reply is a bulk string, its payload is passed to `on_chunk` in chunks of up to `chunk_size` bytes
+    /// while it is read, so the whole value is never buffered in memory. Any other reply is parsed into a regular [`Value`].
+    /// If `on_chunk` returns an error, the rest of the payload is still drained from `read`, so that the reader is left
+    /// at the start of the next reply, and the error is returned.
+    ///
+    /// The reply is read through the decoder's buffer, so bytes that were already read into it are streamed too,
+    /// and bytes of the following replies are left in it for the next parse.
+    pub async fn parse_redis_value_streamed_async<R, F>(
+        decoder: &mut ValueDecoder,
+        read: &mut R,
+        chunk_size: usize,
+        mut on_chunk: F,
+    ) -> RedisResult<StreamedValue>
+    where
+        R: AsyncRead + std::marker::Unpin,
+        F: FnMut(&[u8]) -> RedisResult<()>,
+    {
+        // The length line of a bulk string is at most a sign and 19 digits.
+        const MAX_LENGTH_LINE: usize = 20;
+
+        if decoder.buffer().is_empty() {
+            fill_decoder_buffer(decoder, read).await?;
+        }
+        if decoder.buffer()[0] != b'$' {
+            return parse_redis_value_async(decoder, read)
+                .await
+                .map(StreamedValue::Value);
+        }
+
+        let parse_error = |detail: &str| {
+            RedisError::from((ErrorKind::ParseError, "parse error", detail.to_string()))
+        };
+        let line_end = loop {
+            if let Some(line_end) = decoder
+                .buffer()
+                .windows(2)
+                .position(|bytes| bytes == b"\r\n")
+            {
+                break line_end;
+            }
+            if decoder.buffer().len() > MAX_LENGTH_LINE + 1 {
+                return Err(parse_error("Bulk string length line is too long"));
+            }
+            fill_decoder_buffer(decoder, read).await?;
+        };
+        let size = str::from_utf8(&decoder.buffer()[1..line_end])
+            .ok()
+            .and_then(|line| line.trim().parse::<i64>().ok())
+            .ok_or_else(|| parse_error("Expected integer, got garbage"))?;
+        decoder.advance(read, line_end + 2);
+        if size < 0 {
+            return Ok(StreamedValue::Value(Value::Nil));
+        }
+
+        let size = size as usize;
+        let chunk_size = chunk_size.max(1);
+        let mut remaining = size;
+        let mut callback_result = Ok(());
+        while remaining > 0 {
+            if decoder.buffer().is_empty() {
+                fill_decoder_buffer(decoder, read).await?;
+            }
+            let chunk_len = remaining.min(chunk_size).min(decoder.buffer().len());
+            if callback_result.is_ok() {
+                callback_result = on_chunk(&decoder.buffer()[..chunk_len]);
+            }
+            decoder.advance(read, chunk_len);
+            remaining -= chunk_len;
+        }
+        while decoder.buffer().len() < 2 {
+            fill_decoder_buffer(decoder, read).await?;
+        }
+        if &decoder.buffer()[..2] != b"\r\n" {
+            return Err(parse_error("Bulk string isn't terminated by CRLF"));
+        }
+        decoder.advance(read, 2);
+
+        callback_result.map(|_| StreamedValue::Streamed(size))
+    }
 }
 
 #[cfg(feature = "aio")]
@@ -514,6 +625,96 @@ mod tests {
         assert_eq!(result, Ok(Value::Okay));
     }
 
+    #[cfg(feature = "aio")]
+    #[tokio::test]
+    async fn parse_streamed_bulk_string_in_chunks() {
+        let mut decoder = combine::stream::Decoder::new();
+        let mut bytes = &b"$10\r\n0123456789\r\n+OK\r\n"[..];
+        let mut chunks = Vec::new();
+        let result = parse_redis_value_streamed_async(&mut decoder, &mut bytes, 4, |chunk| {
+            chunks.push(chunk.to_vec());
+            Ok(())
+        })
+        .await;
+
+        assert_eq!(result, Ok(StreamedValue::Streamed(10)));
+        assert_eq!(
+            chunks,
+            vec![b"0123".to_vec(), b"4567".to_vec(), b"89".to_vec()]
+        );
+        let result = parse_redis_value_async(&mut decoder, &mut bytes).await;
+        assert_eq!(result, Ok(Value::Okay));
+    }
+
+    #[cfg(feature = "aio")]
+    #[tokio::test]
+    async fn parse_streamed_bulk_string_already_in_the_decoder_buffer() {
+        let mut decoder = combine::stream::Decoder::new();
+        let mut bytes = &b"+OK\r\n$10\r\n0123456789\r\n:5\r\n"[..];
+        // The whole input is read into the decoder's buffer while parsing the first reply.
+        let result = parse_redis_value_async(&mut decoder, &mut bytes).await;
+        assert_eq!(result, Ok(Value::Okay));
+        assert!(bytes.is_empty());
+
+        let mut chunks = Vec::new();
+        let result = parse_redis_value_streamed_async(&mut decoder, &mut bytes, 4, |chunk| {
+            chunks.push(chunk.to_vec());
+            Ok(())
+        })
+        .await;
+        assert_eq!(result, Ok(StreamedValue::Streamed(10)));
+        assert_eq!(
+            chunks,
+            vec![b"0123".to_vec(), b"4567".to_vec(), b"89".to_vec()]
+        );
+        let result = parse_redis_value_async(&mut decoder, &mut bytes).await;
+        assert_eq!(result, Ok(Value::Int(5)));
+    }
+
+    #[cfg(feature = "aio")]
+    #[tokio::test]
+    async fn parse_streamed_returns_non_bulk_string_values() {
+        let mut decoder = combine::stream::Decoder::new();
+        let mut bytes = &b"$-1\r\n*2\r\n:1\r\n+two\r\n-ERR failed\r\n"[..];
+        let mut on_chunk = |_: &[u8]| -> RedisResult<()> { panic!("unexpected chunk") };
+
+        let result =
+            parse_redis_value_streamed_async(&mut decoder, &mut bytes, 4, &mut on_chunk).await;
+        assert_eq!(result, Ok(StreamedValue::Value(Value::Nil)));
+
+        let result =
+            parse_redis_value_streamed_async(&mut decoder, &mut bytes, 4, &mut on_chunk).await;
+        assert_eq!(
+            result,
+            Ok(StreamedValue::Value(Value::Array(vec![
+                Value::Int(1),
+                Value::SimpleString("two".to_string())
+            ])))
+        );
+
+        let result =
+            parse_redis_value_streamed_async(&mut decoder, &mut bytes, 4, &mut on_chunk).await;
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ResponseError);
+    }
+
+    #[cfg(feature = "aio")]
+    #[tokio::test]
+    async fn parse_streamed_drains_value_after_callback_error() {
+        let mut decoder = combine::stream::Decoder::new();
+        let mut bytes = &b"$6\r\nabcdef\r\n:5\r\n"[..];
+        let mut calls = 0;
+        let result = parse_redis_value_streamed_async(&mut decoder, &mut bytes, 2, |_| {
+            calls += 1;
+            Err(RedisError::from((ErrorKind::ClientError, "stop")))
+        })
+        .await;
+
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ClientError);
+        assert_eq!(calls, 1);
+        let result = parse_redis_value_async(&mut decoder, &mut bytes).await;
+        assert_eq!(result, Ok(Value::Int(5)));
+    }
+
     #[test]
     fn parse_nested_error_and_handle_more_inputs() {
         // from https://redis.io/docs/interact/transactions/ -
@@ -157,6 +157,10 @@ pub enum ErrorKind {
     /// Used when an error occurs on when user perform wrong usage of management operation.
     /// E.g. not allowed configuration change.
     UserOperationError,
+
+    /// The request was cancelled by the client before a response was received,
+    /// e.g. because the client was closed before the request completed.
+    RequestCancelled,
 }
 
 #[derive(PartialEq, Debug)]
@@ -412,6 +416,8 @@ pub enum PushKind {
     PSubscribe,
     /// `ssubscribe` is received when client subscribed to a shard channel.
     SSubscribe,
+    /// `topology_change` is sent from the **library** when a change in a cluster's topology is observed.
+    TopologyChange,
 }
 
 impl PushKind {
@@ -454,6 +460,7 @@ impl fmt::Display for PushKind {
             PushKind::PSubscribe => write!(f, "psubscribe"),
             PushKind::SSubscribe => write!(f, "ssubscribe"),
             PushKind::Disconnection => write!(f, "disconnection"),
+            PushKind::TopologyChange => write!(f, "topology_change"),
         }
     }
 }
@@ -905,6 +912,7 @@ impl RedisError {
             ErrorKind::ParseError => "parse error",
             ErrorKind::NotAllSlotsCovered => "not all slots are covered",
             ErrorKind::UserOperationError => "Wrong usage of management operation",
+            ErrorKind::RequestCancelled => "request cancelled",
         }
     }
 
@@ -1028,7 +1036,7 @@ impl RedisError {
     ///
     /// The `ioerror_description` parameter will be prepended to the message in
     /// case an `IoError` is found.
-    #[cfg(feature = "connection-manager")] // Used to avoid "unused method" warning
+    #[cfg(any(feature = "connection-manager", feature = "cluster-async"))] // Used to avoid "unused method" warning
     pub(crate) fn clone_mostly(&self, ioerror_description: &'static str) -> Self {
         let repr = match self.repr {
             ErrorRepr::WithDescription(kind, desc) => ErrorRepr::WithDescription(kind, desc),
@@ -1101,6 +1109,7 @@ impl RedisError {
             ErrorKind::FatalReceiveError => RetryMethod::Reconnect,
             ErrorKind::FatalSendError => RetryMethod::ReconnectAndRetry,
             ErrorKind::UserOperationError => RetryMethod::NoRetry,
+            ErrorKind::RequestCancelled => RetryMethod::NoRetry,
         }
     }
 }
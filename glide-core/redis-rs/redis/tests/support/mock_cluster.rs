@@ -29,12 +29,18 @@ use tokio::runtime::Runtime;
 
 type Handler = Arc<dyn Fn(&[u8], u16) -> Result<(), RedisResult<Value>> + Send + Sync>;
 
+/// Responds to all the commands sent in a single write, with one result per command.
+pub type PipelineHandler = Arc<dyn Fn(&[Vec<u8>], u16) -> Vec<RedisResult<Value>> + Send + Sync>;
+
 pub struct MockConnectionBehavior {
     pub id: String,
     pub handler: Handler,
     pub connection_id_provider: AtomicUsize,
     pub returned_ip_type: ConnectionIPReturnType,
     pub return_connection_err: ShouldReturnConnectionError,
+    /// If set, the commands that are sent in a single write with their own results are passed to it together,
+    /// instead of one by one to `handler`.
+    pub pipeline_handler: Option<PipelineHandler>,
 }
 
 impl MockConnectionBehavior {
@@ -45,6 +51,7 @@ impl MockConnectionBehavior {
             connection_id_provider: AtomicUsize::new(0),
             returned_ip_type: ConnectionIPReturnType::default(),
             return_connection_err: ShouldReturnConnectionError::default(),
+            pipeline_handler: None,
         }
     }
 
@@ -85,6 +92,7 @@ pub fn get_mock_connection_with_port(name: &str, id: usize, port: u16) -> MockCo
         id,
         handler: get_mock_connection_handler(name),
         port,
+        pipeline_handler: None,
     }
 }
 
@@ -123,6 +131,7 @@ pub struct MockConnection {
     pub id: usize,
     pub handler: Handler,
     pub port: u16,
+    pub pipeline_handler: Option<PipelineHandler>,
 }
 
 #[cfg(feature = "cluster-async")]
@@ -178,6 +187,7 @@ impl cluster_async::Connect for MockConnection {
                     .fetch_add(1, Ordering::SeqCst),
                 handler: conn_utils.get_handler(),
                 port,
+                pipeline_handler: conn_utils.pipeline_handler.clone(),
             },
             ip,
         )))
@@ -205,6 +215,7 @@ impl cluster::Connect for MockConnection {
                 .fetch_add(1, Ordering::SeqCst),
             handler: conn_utils.get_handler(),
             port,
+            pipeline_handler: None,
         })
     }
 
@@ -364,6 +375,26 @@ impl aio::ConnectionLike for MockConnection {
         Box::pin(future::ok(vec![]))
     }
 
+    fn req_packed_commands_separately<'a>(
+        &'a mut self,
+        pipeline: &'a redis::Pipeline,
+    ) -> RedisFuture<'a, Vec<RedisResult<Value>>> {
+        let commands: Vec<Vec<u8>> = pipeline
+            .cmd_iter()
+            .map(|cmd| cmd.get_packed_command())
+            .collect();
+        let results = match &self.pipeline_handler {
+            Some(pipeline_handler) => pipeline_handler(&commands, self.port),
+            None => commands
+                .iter()
+                .map(|cmd| {
+                    (self.handler)(cmd, self.port).expect_err("Handler did not specify a response")
+                })
+                .collect(),
+        };
+        Box::pin(future::ok(results))
+    }
+
     fn get_db(&self) -> i64 {
         0
     }
@@ -455,6 +486,17 @@ impl MockEnv {
         client_builder: ClusterClientBuilder,
         id: &str,
         handler: impl Fn(&[u8], u16) -> Result<(), RedisResult<Value>> + Send + Sync + 'static,
+    ) -> Self {
+        Self::with_pipeline_handler(client_builder, id, handler, None)
+    }
+
+    /// Like [`MockEnv::with_client_builder`], but the commands sent in a single write with their own results
+    /// are passed together to `pipeline_handler`, if it's set.
+    pub fn with_pipeline_handler(
+        client_builder: ClusterClientBuilder,
+        id: &str,
+        handler: impl Fn(&[u8], u16) -> Result<(), RedisResult<Value>> + Send + Sync + 'static,
+        pipeline_handler: Option<PipelineHandler>,
     ) -> Self {
         #[cfg(feature = "cluster-async")]
         let runtime = tokio::runtime::Builder::new_current_thread()
@@ -468,6 +510,9 @@ impl MockEnv {
             &id,
             Arc::new(move |cmd, port| handler(cmd, port)),
         );
+        modify_mock_connection_behavior(&id, |behavior| {
+            behavior.pipeline_handler = pipeline_handler
+        });
         let client = client_builder.build().unwrap();
         let connection = client.get_generic_connection(None).unwrap();
         #[cfg(feature = "cluster-async")]
@@ -115,7 +115,7 @@ mod cluster_async {
         cluster_routing::{
             MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
         },
-        cluster_topology::{get_slot, DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES},
+        cluster_topology::{get_slot, TopologyEvent, DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES},
         cmd, from_owned_redis_value, parse_redis_value, AsyncCommands, Cmd, ErrorKind,
         FromRedisValue, GlideConnectionOptions, InfoDict, IntoConnectionInfo, ProtocolVersion,
         PubSubChannelOrPattern, PubSubSubscriptionInfo, PubSubSubscriptionKind, PushInfo, PushKind,
@@ -1142,6 +1142,48 @@ mod cluster_async {
         assert_eq!(value, Ok(Some(123)));
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_traffic_stats() {
+        let name = "traffic_stats";
+
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]).hot_keys(10, 1),
+            name,
+            move |cmd: &[u8], _| {
+                respond_startup(name, cmd)?;
+                Err(Ok(Value::Okay))
+            },
+        );
+
+        runtime.block_on(async {
+            for key in ["foo", "foo", "bar"] {
+                cmd("SET")
+                    .arg(key)
+                    .arg("value")
+                    .query_async::<_, ()>(&mut connection)
+                    .await
+                    .unwrap();
+            }
+        });
+
+        let stats = connection.traffic_stats().unwrap();
+        let foo_slot = get_slot(b"foo");
+        assert_eq!(stats.slots[&foo_slot].requests, 2);
+        assert_eq!(stats.slots[&foo_slot].errors, 0);
+        assert_eq!(stats.nodes[&format!("{name}:6379")].requests, 3);
+        assert_eq!(stats.hot_keys[0].key, "foo");
+        assert_eq!(stats.hot_keys[0].estimated_requests, 2);
+
+        connection.reset_traffic_stats();
+        assert!(connection.traffic_stats().unwrap().nodes.is_empty());
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_async_cluster_tryagain_exhaust_retries() {
@@ -1293,6 +1335,7 @@ mod cluster_async {
         let started = atomic::AtomicBool::new(false);
         let refresh_calls = Arc::new(atomic::AtomicUsize::new(0));
         let refresh_calls_cloned = refresh_calls.clone();
+        let moved_node = atomic::AtomicU16::new(0);
         let MockEnv {
             runtime,
             async_connection: mut connection,
@@ -1319,12 +1362,15 @@ mod cluster_async {
                 let i = requests.fetch_add(1, atomic::Ordering::SeqCst);
                 let is_get_cmd = contains_slice(cmd, b"GET");
                 let get_response = Err(Ok(Value::BulkString(b"123".to_vec())));
-                let moved_node = ports[0];
                 match i {
-                    // Respond that the key exists on a node that does not yet have a connection:
-                    0 => Err(parse_redis_value(
-                        format!("-MOVED 123 {name}:{moved_node}\r\n").as_bytes(),
-                    )),
+                    // Respond with a MOVED error that redirects to the node itself,
+                    // so the slot map can't be patched and a full refresh is required:
+                    0 => {
+                        moved_node.store(port, atomic::Ordering::SeqCst);
+                        Err(parse_redis_value(
+                            format!("-MOVED 0 {name}:{port}\r\n").as_bytes(),
+                        ))
+                    }
                     _ => {
                         if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
                             refresh_calls_cloned.fetch_add(1, atomic::Ordering::SeqCst);
@@ -1335,7 +1381,7 @@ mod cluster_async {
                                 slots_config_vec[view_index].clone(),
                             )))
                         } else {
-                            assert_eq!(port, moved_node);
+                            assert_eq!(port, moved_node.load(atomic::Ordering::SeqCst));
                             assert!(is_get_cmd, "{:?}", std::str::from_utf8(cmd));
                             get_response
                         }
@@ -1398,6 +1444,7 @@ mod cluster_async {
         let refresh_calls_cloned = Arc::clone(&refresh_calls);
         let wait_duration = Duration::from_millis(10);
         let num_of_nodes = ports.len();
+        let moved_node = atomic::AtomicU16::new(0);
 
         let MockEnv {
             runtime,
@@ -1424,7 +1471,6 @@ mod cluster_async {
                 let i = requests.fetch_add(1, atomic::Ordering::SeqCst);
                 let is_get_cmd = contains_slice(cmd, b"GET");
                 let get_response = Err(Ok(Value::BulkString(b"123".to_vec())));
-                let moved_node = ports[0];
                 match i {
                     // The first request calls are the starting calls for each GET command where we want to respond with MOVED error
                     0 => {
@@ -1432,8 +1478,10 @@ mod cluster_async {
                             // Wait for the wait duration to pass
                             std::thread::sleep(wait_duration.add(Duration::from_millis(10)));
                         }
+                        // The node redirects to itself, so the MOVED error requires a full refresh
+                        moved_node.store(port, atomic::Ordering::SeqCst);
                         Err(parse_redis_value(
-                            format!("-MOVED 123 {test_name}:{moved_node}\r\n").as_bytes(),
+                            format!("-MOVED 0 {test_name}:{port}\r\n").as_bytes(),
                         ))
                     }
                     _ => {
@@ -1448,7 +1496,7 @@ mod cluster_async {
                         } else {
                             // Even if the slots weren't refreshed we still expect the command to be
                             // routed by the redirect host and port it received in the moved error
-                            assert_eq!(port, moved_node);
+                            assert_eq!(port, moved_node.load(atomic::Ordering::SeqCst));
                             assert!(is_get_cmd, "{:?}", std::str::from_utf8(cmd));
                             get_response
                         }
@@ -1907,6 +1955,8 @@ mod cluster_async {
             },
         );
 
+        let mut topology_events = connection.subscribe_topology_events();
+
         // First request: Trigger MOVED error and reroute
         let value = runtime.block_on(
             cmd("SET")
@@ -1937,6 +1987,15 @@ mod cluster_async {
 
         // Assert there was only a single MOVED error
         assert_eq!(cloned_moved_requests.load(Ordering::Relaxed), 1);
+
+        // The promotion is reported to the subscribers of topology events
+        assert_eq!(
+            topology_events.try_recv(),
+            Ok(TopologyEvent::Failover {
+                previous_primary: format!("{name}:{moved_from_port}"),
+                primary: format!("{name}:{moved_to_port}"),
+            })
+        );
     }
 
     #[test]
@@ -2371,6 +2430,320 @@ mod cluster_async {
         );
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_is_released_when_dropped_during_slot_refresh() {
+        // The handler is owned by the client's connections, so it's released only once the client's state is.
+        let name = "test_async_cluster_is_released_when_dropped_during_slot_refresh";
+        let client_state = Arc::new(());
+        let released = Arc::downgrade(&client_state);
+        let started = atomic::AtomicBool::new(false);
+        let refresh_calls = Arc::new(atomic::AtomicUsize::new(0));
+        let refresh_calls_cloned = refresh_calls.clone();
+
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            connection: sync_connection,
+            handler,
+            client,
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .slots_refresh_rate_limit(Duration::from_secs(0), 0),
+            name,
+            move |cmd: &[u8], _port| {
+                let _ = &client_state;
+                if !started.load(atomic::Ordering::SeqCst) {
+                    respond_startup(name, cmd)?;
+                }
+                started.store(true, atomic::Ordering::SeqCst);
+                if contains_slice(cmd, b"PING") || contains_slice(cmd, b"SETNAME") {
+                    return Err(Ok(Value::SimpleString("OK".into())));
+                }
+                if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
+                    // Keep the slot refresh retrying.
+                    refresh_calls_cloned.fetch_add(1, atomic::Ordering::SeqCst);
+                    return Err(Err((ErrorKind::ResponseError, "refresh failed").into()));
+                }
+                // The node already owns the slot, so the MOVED error requires a full refresh.
+                Err(parse_redis_value(
+                    format!("-MOVED 0 {name}:6379\r\n").as_bytes(),
+                ))
+            },
+        );
+
+        runtime.block_on(async {
+            let mut get = cmd("GET");
+            get.arg("test");
+            let _ = tokio::time::timeout(
+                Duration::from_millis(200),
+                get.query_async::<_, Option<i32>>(&mut connection),
+            )
+            .await;
+        });
+        assert!(refresh_calls.load(atomic::Ordering::SeqCst) > 0);
+
+        // Drop the client while the slot refresh is waiting to be retried.
+        drop(connection);
+        drop(sync_connection);
+        drop(client);
+        drop(runtime);
+        drop(handler);
+        assert!(released.upgrade().is_none());
+    }
+
+    fn cluster_shards_response(name: &str, port: u16) -> Value {
+        Value::Array(vec![Value::Array(vec![
+            Value::BulkString(b"slots".to_vec()),
+            Value::Array(vec![Value::Int(0), Value::Int(16383)]),
+            Value::BulkString(b"nodes".to_vec()),
+            Value::Array(vec![Value::Array(vec![
+                Value::BulkString(b"id".to_vec()),
+                Value::BulkString(b"node1".to_vec()),
+                Value::BulkString(b"port".to_vec()),
+                Value::Int(port.into()),
+                Value::BulkString(b"endpoint".to_vec()),
+                Value::BulkString(name.as_bytes().to_vec()),
+                Value::BulkString(b"role".to_vec()),
+                Value::BulkString(b"master".to_vec()),
+                Value::BulkString(b"health".to_vec()),
+                Value::BulkString(b"online".to_vec()),
+            ])]),
+        ])])
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_connects_to_the_new_address_of_a_node() {
+        let name = "test_async_cluster_connects_to_the_new_address_of_a_node";
+        let address_changed = Arc::new(atomic::AtomicBool::new(false));
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .use_cluster_shards()
+                .periodic_topology_checks(Duration::from_millis(10))
+                .slots_refresh_rate_limit(Duration::from_secs(0), 0),
+            name,
+            {
+                let address_changed = address_changed.clone();
+                move |cmd: &[u8], port| {
+                    if contains_slice(cmd, b"PING") || contains_slice(cmd, b"SETNAME") {
+                        return Err(Ok(Value::SimpleString("OK".into())));
+                    }
+                    if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SHARDS") {
+                        let node_port = if address_changed.load(atomic::Ordering::SeqCst) {
+                            6380
+                        } else {
+                            6379
+                        };
+                        return Err(Ok(cluster_shards_response(name, node_port)));
+                    }
+                    // The node is only reachable in its new address.
+                    assert_eq!(port, 6380, "{:?}", std::str::from_utf8(cmd));
+                    Err(Ok(Value::BulkString(b"123".to_vec())))
+                }
+            },
+        );
+
+        address_changed.store(true, atomic::Ordering::SeqCst);
+        runtime.block_on(async move {
+            let _ = sleep(Duration::from_millis(200).into()).await;
+            let res = cmd("GET")
+                .arg("test")
+                .query_async::<_, Option<i32>>(&mut connection)
+                .await;
+            assert_eq!(res, Ok(Some(123)));
+        });
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_stops_querying_cluster_shards_once_unsupported() {
+        let name = "test_async_cluster_stops_querying_cluster_shards_once_unsupported";
+        let shards_calls = Arc::new(atomic::AtomicUsize::new(0));
+        let slots_calls = Arc::new(atomic::AtomicUsize::new(0));
+        let MockEnv {
+            runtime,
+            async_connection: _connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .use_cluster_shards()
+                .periodic_topology_checks(Duration::from_millis(10)),
+            name,
+            {
+                let shards_calls = shards_calls.clone();
+                let slots_calls = slots_calls.clone();
+                move |cmd: &[u8], _port| {
+                    if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SHARDS") {
+                        shards_calls.fetch_add(1, atomic::Ordering::SeqCst);
+                        return Err(parse_redis_value(b"-ERR unknown subcommand 'SHARDS'\r\n"));
+                    }
+                    if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
+                        slots_calls.fetch_add(1, atomic::Ordering::SeqCst);
+                    }
+                    respond_startup(name, cmd)?;
+                    Err(Ok(Value::Nil))
+                }
+            },
+        );
+
+        runtime.block_on(async {
+            let _ = sleep(Duration::from_millis(200).into()).await;
+        });
+        // The synchronous client queries `CLUSTER SHARDS` once too.
+        assert!(shards_calls.load(atomic::Ordering::SeqCst) <= 2);
+        assert!(slots_calls.load(atomic::Ordering::SeqCst) > 2);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_moved_error_patches_slot_map_without_refresh() {
+        // Slot 123 is owned by the last node, and the MOVED error reports that it migrated to the first node.
+        // The slot map is patched for that slot, so the request is retried without calling CLUSTER SLOTS.
+        let name = "test_async_cluster_moved_error_patches_slot_map_without_refresh";
+        let ports = get_ports(3);
+        let slots_config = get_topology_with_majority(&ports)[0].clone();
+        let moved_node = ports[0];
+        let requests = atomic::AtomicUsize::new(0);
+        let started = atomic::AtomicBool::new(false);
+        let refresh_calls = Arc::new(atomic::AtomicUsize::new(0));
+        let refresh_calls_cloned = refresh_calls.clone();
+
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .slots_refresh_rate_limit(Duration::from_secs(0), 0),
+            name,
+            move |cmd: &[u8], port| {
+                if !started.load(atomic::Ordering::SeqCst) {
+                    respond_startup_with_replica_using_config(
+                        name,
+                        cmd,
+                        Some(slots_config.clone()),
+                    )?;
+                    started.store(true, atomic::Ordering::SeqCst);
+                }
+
+                if contains_slice(cmd, b"PING") || contains_slice(cmd, b"SETNAME") {
+                    return Err(Ok(Value::SimpleString("OK".into())));
+                }
+                if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
+                    refresh_calls_cloned.fetch_add(1, atomic::Ordering::SeqCst);
+                    return Err(Ok(create_topology_from_config(name, slots_config.clone())));
+                }
+
+                match requests.fetch_add(1, atomic::Ordering::SeqCst) {
+                    0 => Err(parse_redis_value(
+                        format!("-MOVED 123 {name}:{moved_node}\r\n").as_bytes(),
+                    )),
+                    _ => {
+                        assert_eq!(port, moved_node);
+                        Err(Ok(Value::BulkString(b"123".to_vec())))
+                    }
+                }
+            },
+        );
+
+        runtime.block_on(async move {
+            let res = cmd("GET")
+                .arg("test")
+                .query_async::<_, Option<i32>>(&mut connection)
+                .await;
+            assert_eq!(res, Ok(Some(123)));
+
+            let _ = sleep(Duration::from_millis(200).into()).await;
+            assert_eq!(refresh_calls.load(atomic::Ordering::Relaxed), 0);
+        });
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_concurrent_moved_errors_of_a_slot_do_not_refresh_slots() {
+        // Concurrent requests to a migrated slot all receive MOVED errors from its previous owner.
+        // The first MOVED error patches the slot map, and the rest find the redirect target already
+        // owning the slot, so every request is retried without calling CLUSTER SLOTS.
+        let name = "test_async_cluster_concurrent_moved_errors_of_a_slot_do_not_refresh_slots";
+        let ports = get_ports(3);
+        let slots_config = get_topology_with_majority(&ports)[0].clone();
+        let moved_node = ports[0];
+        let num_of_requests = 5;
+        let started = atomic::AtomicBool::new(false);
+        let moved_errors = Arc::new(atomic::AtomicUsize::new(0));
+        let moved_errors_cloned = moved_errors.clone();
+        let refresh_calls = Arc::new(atomic::AtomicUsize::new(0));
+        let refresh_calls_cloned = refresh_calls.clone();
+
+        let MockEnv {
+            runtime,
+            async_connection: connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .slots_refresh_rate_limit(Duration::from_secs(0), 0),
+            name,
+            move |cmd: &[u8], port| {
+                if !started.load(atomic::Ordering::SeqCst) {
+                    respond_startup_with_replica_using_config(
+                        name,
+                        cmd,
+                        Some(slots_config.clone()),
+                    )?;
+                    started.store(true, atomic::Ordering::SeqCst);
+                }
+
+                if contains_slice(cmd, b"PING") || contains_slice(cmd, b"SETNAME") {
+                    return Err(Ok(Value::SimpleString("OK".into())));
+                }
+                if contains_slice(cmd, b"CLUSTER")
+                    && (contains_slice(cmd, b"SLOTS") || contains_slice(cmd, b"SHARDS"))
+                {
+                    refresh_calls_cloned.fetch_add(1, atomic::Ordering::SeqCst);
+                    return Err(Ok(create_topology_from_config(name, slots_config.clone())));
+                }
+
+                if port == moved_node {
+                    Err(Ok(Value::BulkString(b"123".to_vec())))
+                } else {
+                    moved_errors_cloned.fetch_add(1, atomic::Ordering::SeqCst);
+                    let slot = get_slot(b"{test}");
+                    Err(parse_redis_value(
+                        format!("-MOVED {slot} {name}:{moved_node}\r\n").as_bytes(),
+                    ))
+                }
+            },
+        );
+
+        runtime.block_on(async move {
+            let results = future::join_all((0..num_of_requests).map(|i| {
+                let mut connection = connection.clone();
+                async move {
+                    cmd("GET")
+                        .arg(format!("{{test}}{i}"))
+                        .query_async::<_, Option<i32>>(&mut connection)
+                        .await
+                }
+            }))
+            .await;
+            for res in results {
+                assert_eq!(res, Ok(Some(123)));
+            }
+            assert_eq!(moved_errors.load(atomic::Ordering::SeqCst), num_of_requests);
+
+            let _ = sleep(Duration::from_millis(200).into()).await;
+            assert_eq!(refresh_calls.load(atomic::Ordering::Relaxed), 0);
+        });
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_async_cluster_ask_redirect() {
@@ -2421,6 +2794,73 @@ mod cluster_async {
         assert_eq!(value, Ok(Some(123)));
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_ask_redirected_requests_are_batched() {
+        let name = "node";
+        let writes = Arc::new(AtomicU16::new(0));
+        let pipeline_handler: PipelineHandler = {
+            let writes = writes.clone();
+            Arc::new(move |commands: &[Vec<u8>], port| {
+                assert_eq!(port, 6380);
+                writes.fetch_add(1, Ordering::SeqCst);
+                // Both requests are sent in a single write, each preceded by `ASKING`.
+                assert_eq!(commands.len(), 4);
+                commands
+                    .iter()
+                    .map(|cmd| {
+                        if contains_slice(cmd, b"ASKING") {
+                            Ok(Value::Okay)
+                        } else if contains_slice(cmd, b"INCR") {
+                            Ok(Value::Int(1))
+                        } else if contains_slice(cmd, b"LPUSH") {
+                            parse_redis_value(
+                                b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+                            )
+                        } else {
+                            panic!("Unexpected command")
+                        }
+                    })
+                    .collect()
+            })
+        };
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::with_pipeline_handler(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]),
+            name,
+            move |cmd: &[u8], port| {
+                respond_startup_two_nodes(name, cmd)?;
+                match port {
+                    6379 => Err(parse_redis_value(b"-ASK 6918 node:6380\r\n")),
+                    _ => panic!("Wrong node"),
+                }
+            },
+            Some(pipeline_handler),
+        );
+
+        let ask_redirections = Telemetry::total_ask_redirections();
+        let mut other_connection = connection.clone();
+        let (mut incr, mut lpush) = (cmd("INCR"), cmd("LPUSH"));
+        incr.arg("{test}a");
+        lpush.arg("{test}b").arg("value");
+        let (incr_result, lpush_result) = runtime.block_on(async {
+            futures::join!(
+                incr.query_async::<_, i64>(&mut connection),
+                lpush.query_async::<_, i64>(&mut other_connection),
+            )
+        });
+
+        // Each request gets its own reply, so the error of one doesn't fail the other.
+        assert_eq!(incr_result.unwrap(), 1);
+        assert_eq!(lpush_result.unwrap_err().code(), Some("WRONGTYPE"));
+        assert_eq!(writes.load(Ordering::SeqCst), 1);
+        assert_eq!(Telemetry::total_ask_redirections(), ask_redirections + 2);
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_async_cluster_ask_save_new_connection() {
@@ -3231,6 +3671,85 @@ mod cluster_async {
         );
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_proxy_mode_pins_requests_to_the_proxy() {
+        let name = "test_async_cluster_proxy_mode_pins_requests_to_the_proxy";
+        let topology_queried = Arc::new(AtomicBool::new(false));
+        let other_port_used = Arc::new(AtomicBool::new(false));
+        let mget_requests = Arc::new(AtomicU16::new(0));
+        let get_requests = Arc::new(AtomicU16::new(0));
+
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .retries(3)
+                .proxy_mode(),
+            name,
+            {
+                let topology_queried = topology_queried.clone();
+                let other_port_used = other_port_used.clone();
+                let mget_requests = mget_requests.clone();
+                let get_requests = get_requests.clone();
+                move |received_cmd: &[u8], port| {
+                    if port != 6379 {
+                        other_port_used.store(true, Ordering::SeqCst);
+                    }
+                    if contains_slice(received_cmd, b"CLUSTER") {
+                        topology_queried.store(true, Ordering::SeqCst);
+                    }
+                    respond_startup(name, received_cmd)?;
+                    let cmd_str = std::str::from_utf8(received_cmd).unwrap();
+                    if cmd_str.contains("MGET") {
+                        mget_requests.fetch_add(1, Ordering::SeqCst);
+                        let values = ["foo", "bar", "baz"]
+                            .iter()
+                            .filter(|key| cmd_str.contains(*key))
+                            .map(|key| Value::BulkString(key.as_bytes().to_vec()))
+                            .collect();
+                        return Err(Ok(Value::Array(values)));
+                    }
+                    // The proxy redirects the first GET, which must be retried on the proxy.
+                    if get_requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                        return Err(parse_redis_value(
+                            format!("-MOVED 123 {name}:6380\r\n").as_bytes(),
+                        ));
+                    }
+                    Err(Ok(Value::BulkString(b"123".to_vec())))
+                }
+            },
+        );
+        // Only the async connection supports proxy mode, the sync one discovered the topology.
+        topology_queried.store(false, Ordering::SeqCst);
+
+        let value = runtime.block_on(
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, Option<i32>>(&mut connection),
+        );
+        assert_eq!(value, Ok(Some(123)));
+
+        let values = runtime
+            .block_on(
+                cmd("MGET")
+                    .arg("foo")
+                    .arg("bar")
+                    .arg("baz")
+                    .query_async::<_, Vec<String>>(&mut connection),
+            )
+            .unwrap();
+        assert_eq!(values, vec!["foo", "bar", "baz"]);
+        // The keys are in 3 different slots, so the command is split per slot.
+        assert_eq!(mget_requests.load(Ordering::SeqCst), 3);
+        assert_eq!(get_requests.load(Ordering::SeqCst), 2);
+        assert!(!topology_queried.load(Ordering::SeqCst));
+        assert!(!other_port_used.load(Ordering::SeqCst));
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_async_cluster_split_multi_shard_command_and_combine_arrays_of_values() {
@@ -0,0 +1,313 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use telemetrylib::Telemetry;
+use tokio::sync::oneshot;
+
+use super::types::AdmissionMode;
+
+pub const DEFAULT_ADMISSION_QUEUE_SIZE: u32 = 1000;
+pub const DEFAULT_ADMISSION_QUEUE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// The reason a request wasn't admitted.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AdmissionError {
+    /// The inflight requests limit was reached, and the client is configured to fail fast.
+    LimitReached,
+    /// The inflight requests limit was reached, and the admission queue is full.
+    QueueFull,
+    /// The request waited in the admission queue for longer than the queue timeout.
+    QueueTimeout,
+}
+
+/// The kind of the request waiting for admission. Each kind is queued separately, and freed
+/// slots alternate between the kinds, so that a burst of one kind won't starve the other.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RequestKind {
+    Command,
+    Transaction,
+}
+
+#[derive(Default)]
+struct WaitQueues {
+    commands: VecDeque<oneshot::Sender<()>>,
+    transactions: VecDeque<oneshot::Sender<()>>,
+    prefer_transactions: bool,
+}
+
+impl WaitQueues {
+    fn len(&self) -> usize {
+        self.commands.len() + self.transactions.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.commands.is_empty() && self.transactions.is_empty()
+    }
+
+    fn push(&mut self, kind: RequestKind, sender: oneshot::Sender<()>) {
+        match kind {
+            RequestKind::Command => self.commands.push_back(sender),
+            RequestKind::Transaction => self.transactions.push_back(sender),
+        }
+    }
+
+    fn pop(&mut self) -> Option<oneshot::Sender<()>> {
+        let (first, second) = if self.prefer_transactions {
+            (&mut self.transactions, &mut self.commands)
+        } else {
+            (&mut self.commands, &mut self.transactions)
+        };
+        let sender = first.pop_front().or_else(|| second.pop_front())?;
+        self.prefer_transactions = !self.prefer_transactions;
+        Some(sender)
+    }
+}
+
+/// A request waiting in the admission queue. If it's dropped after a slot was handed over to it,
+/// for example because the request was cancelled, the slot is released instead of being lost.
+struct QueuedRequest<'a> {
+    admission: &'a AdmissionControl,
+    receiver: oneshot::Receiver<()>,
+}
+
+impl<'a> QueuedRequest<'a> {
+    fn new(admission: &'a AdmissionControl, receiver: oneshot::Receiver<()>) -> Self {
+        Telemetry::incr_admission_queue_depth(1);
+        Self {
+            admission,
+            receiver,
+        }
+    }
+}
+
+impl Drop for QueuedRequest<'_> {
+    fn drop(&mut self) {
+        Telemetry::decr_admission_queue_depth(1);
+        // A slot that was received was already consumed, so only a slot that wasn't received yet is released.
+        self.receiver.close();
+        if self.receiver.try_recv().is_ok() {
+            self.admission.release();
+        }
+    }
+}
+
+/// Limits the number of concurrent requests of a client.
+pub(crate) struct AdmissionControl {
+    // Setting this counter to limit the inflight requests, in case of any queue is blocked, so we return error to the customer.
+    inflight_requests_allowed: AtomicIsize,
+    mode: AdmissionMode,
+    wait_queues: Mutex<WaitQueues>,
+}
+
+impl AdmissionControl {
+    pub(crate) fn new(inflight_requests_limit: u32, mode: AdmissionMode) -> Self {
+        Self {
+            inflight_requests_allowed: AtomicIsize::new(inflight_requests_limit as isize),
+            mode,
+            wait_queues: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn try_reserve(&self) -> bool {
+        // We use this approach of checking the `inflight_requests_allowed` value
+        // twice, before and after decrementing, to prevent it from reaching negative
+        // values. Allowing the `inflight_requests_allowed` value to go below zero
+        // could lead to a race condition where tasks might not be able to run even
+        // when there are available slots.
+        if self.inflight_requests_allowed.load(Ordering::SeqCst) <= 0 {
+            false
+        } else {
+            // The value is being checked again because it might have changed
+            // during the intervening period since the load by other tasks.
+            if self
+                .inflight_requests_allowed
+                .fetch_sub(1, Ordering::SeqCst)
+                <= 0
+            {
+                self.inflight_requests_allowed
+                    .fetch_add(1, Ordering::SeqCst);
+                return false;
+            }
+            true
+        }
+    }
+
+    pub(crate) async fn reserve(&self, kind: RequestKind) -> Result<(), AdmissionError> {
+        let AdmissionMode::Queue {
+            max_queue_size,
+            queue_timeout,
+        } = self.mode
+        else {
+            return self
+                .try_reserve()
+                .then_some(())
+                .ok_or(AdmissionError::LimitReached);
+        };
+
+        let mut queued_request = {
+            let mut wait_queues = self.wait_queues.lock().unwrap();
+            // Requests that are already waiting are served first, so a new request only skips the queue if it's empty.
+            if wait_queues.is_empty() && self.try_reserve() {
+                return Ok(());
+            }
+            if wait_queues.len() >= max_queue_size as usize {
+                return Err(AdmissionError::QueueFull);
+            }
+            let (sender, receiver) = oneshot::channel();
+            wait_queues.push(kind, sender);
+            QueuedRequest::new(self, receiver)
+        };
+
+        let start = Instant::now();
+        let receiver = &mut queued_request.receiver;
+        let result = match tokio::time::timeout(queue_timeout, &mut *receiver).await {
+            Ok(Ok(())) => Ok(()),
+            _ => {
+                // A slot might have been handed over just as the timeout expired. Closing the receiver
+                // prevents further handovers, and a slot that was already handed over is used instead of being lost.
+                receiver.close();
+                receiver
+                    .try_recv()
+                    .map_err(|_| AdmissionError::QueueTimeout)
+            }
+        };
+        drop(queued_request);
+        if result.is_ok() {
+            Telemetry::record_queue_wait_time(start.elapsed());
+        }
+        result
+    }
+
+    pub(crate) fn release(&self) -> isize {
+        if matches!(self.mode, AdmissionMode::Queue { .. }) {
+            let mut wait_queues = self.wait_queues.lock().unwrap();
+            // Hand the slot directly to the next waiting request. Senders whose receivers timed out are skipped.
+            while let Some(sender) = wait_queues.pop() {
+                if sender.send(()).is_ok() {
+                    return self.inflight_requests_allowed.load(Ordering::SeqCst);
+                }
+            }
+            // The slot is freed while the queues are still locked, so that a request can't queue itself
+            // after finding no free slot, and then wait although the slot was freed.
+            return self
+                .inflight_requests_allowed
+                .fetch_add(1, Ordering::SeqCst);
+        }
+        self.inflight_requests_allowed
+            .fetch_add(1, Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue_mode(max_queue_size: u32, queue_timeout: Duration) -> AdmissionMode {
+        AdmissionMode::Queue {
+            max_queue_size,
+            queue_timeout,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast_rejects_at_limit() {
+        let admission = AdmissionControl::new(1, AdmissionMode::FailFast);
+        assert_eq!(admission.reserve(RequestKind::Command).await, Ok(()));
+        assert_eq!(
+            admission.reserve(RequestKind::Command).await,
+            Err(AdmissionError::LimitReached)
+        );
+        admission.release();
+        assert_eq!(admission.reserve(RequestKind::Command).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_queued_request_is_admitted_on_release() {
+        let admission = std::sync::Arc::new(AdmissionControl::new(
+            1,
+            queue_mode(10, Duration::from_secs(5)),
+        ));
+        admission.reserve(RequestKind::Command).await.unwrap();
+
+        let waiter = {
+            let admission = admission.clone();
+            tokio::spawn(async move { admission.reserve(RequestKind::Command).await })
+        };
+        while admission.wait_queues.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        admission.release();
+        assert_eq!(waiter.await.unwrap(), Ok(()));
+        // The slot was handed over to the waiting request, so the limit is still reached.
+        assert!(!admission.try_reserve());
+    }
+
+    #[tokio::test]
+    async fn test_slot_handed_over_to_dropped_request_is_released() {
+        let admission = AdmissionControl::new(1, queue_mode(10, Duration::from_secs(5)));
+        admission.reserve(RequestKind::Command).await.unwrap();
+
+        let mut waiter = Box::pin(admission.reserve(RequestKind::Command));
+        assert!(futures::poll!(&mut waiter).is_pending());
+        // The slot is handed over to the waiting request, which is dropped before it's admitted.
+        admission.release();
+        drop(waiter);
+
+        assert!(admission.wait_queues.lock().unwrap().is_empty());
+        assert!(admission.try_reserve());
+        assert!(!admission.try_reserve());
+    }
+
+    #[tokio::test]
+    async fn test_queue_timeout_and_queue_size() {
+        let admission = AdmissionControl::new(1, queue_mode(1, Duration::from_millis(10)));
+        admission.reserve(RequestKind::Command).await.unwrap();
+        let (first, second) = tokio::join!(
+            admission.reserve(RequestKind::Command),
+            admission.reserve(RequestKind::Transaction)
+        );
+        assert_eq!(first, Err(AdmissionError::QueueTimeout));
+        assert_eq!(second, Err(AdmissionError::QueueFull));
+
+        // Timed out requests don't consume the released slot.
+        admission.release();
+        assert!(admission.try_reserve());
+    }
+
+    #[test]
+    fn test_wait_queues_alternate_between_request_kinds() {
+        let mut wait_queues = WaitQueues::default();
+        let mut receivers = Vec::new();
+        for kind in [
+            RequestKind::Command,
+            RequestKind::Command,
+            RequestKind::Transaction,
+            RequestKind::Transaction,
+        ] {
+            let (sender, receiver) = oneshot::channel();
+            wait_queues.push(kind, sender);
+            receivers.push((kind, receiver));
+        }
+        let mut order = Vec::new();
+        while let Some(sender) = wait_queues.pop() {
+            sender.send(()).unwrap();
+            let index = receivers
+                .iter_mut()
+                .position(|(_, receiver)| receiver.try_recv().is_ok())
+                .unwrap();
+            order.push(receivers.remove(index).0);
+        }
+        assert_eq!(
+            order,
+            vec![
+                RequestKind::Command,
+                RequestKind::Transaction,
+                RequestKind::Command,
+                RequestKind::Transaction
+            ]
+        );
+    }
+}
@@ -1,9 +1,16 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
+mod admission_control;
+mod session;
+mod shutdown;
 mod types;
 
 use crate::cluster_scan_container::insert_cluster_scan_cursor;
+use crate::compression::CompressionConfig;
 use crate::scripts_container::get_script;
+pub use admission_control::{
+    AdmissionError, RequestKind, DEFAULT_ADMISSION_QUEUE_SIZE, DEFAULT_ADMISSION_QUEUE_TIMEOUT,
+};
 use futures::FutureExt;
 use logger_core::{log_info, log_warn};
 use redis::aio::ConnectionLike;
@@ -12,6 +19,7 @@ use redis::cluster_routing::{
     MultipleNodeRoutingInfo, ResponsePolicy, Routable, RoutingInfo, SingleNodeRoutingInfo,
 };
 use redis::cluster_slotmap::ReadFromReplicaStrategy;
+use redis::cluster_topology::{NodeConnectionState, TopologyEvent};
 use redis::{
     ClusterScanArgs, Cmd, ErrorKind, FromRedisValue, PushInfo, RedisError, RedisResult,
     ScanStateRC, Value,
@@ -19,12 +27,17 @@ use redis::{
 pub use standalone_client::StandaloneClient;
 use std::io;
 use std::str::FromStr;
-use std::sync::atomic::{AtomicIsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 pub use types::*;
 
-use self::value_conversion::{convert_to_expected_type, expected_type_for_cmd, get_value_type};
+use self::admission_control::AdmissionControl;
+use self::session::SessionToken;
+use self::shutdown::Shutdown;
+use self::value_conversion::{
+    convert_to_expected_type, expected_type_for_cmd, expected_type_with_decompression,
+    get_value_type,
+};
 mod reconnecting_connection;
 mod standalone_client;
 mod value_conversion;
@@ -59,6 +72,13 @@ pub const DEFAULT_MAX_INFLIGHT_REQUESTS: u32 = 1000;
 /// and performance overhead.
 pub const CONNECTION_CHECKS_INTERVAL: Duration = Duration::from_secs(3);
 
+/// The default interval between replication offset checks, when replica staleness is bounded
+/// or read-your-writes consistency is enabled.
+pub const DEFAULT_REPLICATION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// By default, one in every 100 requests is sampled for hot keys, to keep the tracking cheap on the request path.
+pub const DEFAULT_HOT_KEYS_SAMPLE_RATE: u32 = 100;
+
 pub(super) fn get_port(address: &NodeAddress) -> u16 {
     const DEFAULT_PORT: u16 = 6379;
     if address.port == 0 {
@@ -125,8 +145,12 @@ pub enum ClientWrapper {
 pub struct Client {
     internal_client: ClientWrapper,
     request_timeout: Duration,
-    // Setting this counter to limit the inflight requests, in case of any queue is blocked, so we return error to the customer.
-    inflight_requests_allowed: Arc<AtomicIsize>,
+    admission_control: Arc<AdmissionControl>,
+    compression_config: Option<CompressionConfig>,
+    shutdown: Arc<Shutdown>,
+    flush_telemetry_on_close: bool,
+    /// Set when read-your-writes consistency is enabled. Only used in cluster mode.
+    session: Option<Arc<SessionToken>>,
 }
 
 async fn run_with_timeout<T>(
@@ -249,6 +273,9 @@ fn get_request_timeout(cmd: &Cmd, default_timeout: Duration) -> RedisResult<Opti
 }
 
 impl Client {
+    /// Sends the command and returns its whole reply. Replies are always buffered in memory, even very large bulk strings:
+    /// the client's connections are shared between concurrent requests, so streaming a value in chunks, as
+    /// `redis::aio::Connection::req_streamed` does, isn't supported here.
     pub fn send_command<'a>(
         &'a mut self,
         cmd: &'a Cmd,
@@ -261,7 +288,20 @@ impl Client {
                 return async { Err(err) }.boxed();
             }
         };
-        run_with_timeout(request_timeout, async move {
+        let compressed_cmd = self
+            .compression_config
+            .as_ref()
+            .and_then(|config| config.compress_command(cmd));
+        let decompress = self.compression_config.is_some();
+        let shutdown = self.shutdown.clone();
+        let session = self.session.clone();
+        let request = run_with_timeout(request_timeout, async move {
+            let expected_type = if decompress {
+                expected_type_with_decompression(cmd, &expected_type)
+            } else {
+                expected_type
+            };
+            let cmd = compressed_cmd.as_ref().unwrap_or(cmd);
             match self.internal_client {
                 ClientWrapper::Standalone(ref mut client) => client.send_command(cmd).await,
                 ClientWrapper::Cluster { ref mut client } => {
@@ -290,12 +330,15 @@ impl Client {
                                 .or_else(|| RoutingInfo::for_routable(cmd))
                                 .unwrap_or(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
                         };
-                    client.route_command(cmd, routing).await
+                    match session {
+                        Some(session) => session.route_command(client, cmd, routing).await,
+                        None => client.route_command(cmd, routing).await,
+                    }
                 }
             }
             .and_then(|value| convert_to_expected_type(value, expected_type))
-        })
-        .boxed()
+        });
+        async move { shutdown.run(request).await }.boxed()
     }
 
     // Cluster scan is not passed to redis-rs as a regular command, so we need to handle it separately.
@@ -316,21 +359,51 @@ impl Client {
         scan_state_cursor: &'a ScanStateRC,
         cluster_scan_args: ClusterScanArgs,
     ) -> RedisResult<Value> {
+        let shutdown = self.shutdown.clone();
+        shutdown
+            .run(async {
+                match self.internal_client {
+                    ClientWrapper::Standalone(_) => {
+                        unreachable!("Cluster scan is not supported in standalone mode")
+                    }
+                    ClientWrapper::Cluster { ref mut client } => {
+                        let (cursor, keys) = client
+                            .cluster_scan(scan_state_cursor.clone(), cluster_scan_args)
+                            .await?;
+                        let cluster_cursor_id = if cursor.is_finished() {
+                            Value::BulkString(FINISHED_SCAN_CURSOR.into())
+                        } else {
+                            Value::BulkString(insert_cluster_scan_cursor(cursor).into())
+                        };
+                        Ok(Value::Array(vec![cluster_cursor_id, Value::Array(keys)]))
+                    }
+                }
+            })
+            .await
+    }
+
+    /// Closes the client gracefully. New requests are rejected immediately, and inflight requests
+    /// are given up to `drain_timeout` to complete, after which they fail with a
+    /// [`RequestErrorType::Cancelled`](crate::errors::RequestErrorType::Cancelled) error.
+    /// Once no request is running, pending OpenTelemetry spans are flushed and the connections are closed.
+    ///
+    /// Closing affects all clones of the client. If `drain_timeout` is `None`, the client waits for all inflight requests to complete.
+    pub async fn close(&self, drain_timeout: Option<Duration>) {
+        let cancelled_requests = self.shutdown.close(drain_timeout).await;
+        if cancelled_requests > 0 {
+            log_warn(
+                "close client",
+                format!("Cancelled {cancelled_requests} inflight requests that didn't complete before the drain timeout"),
+            );
+        }
+        if self.flush_telemetry_on_close {
+            // The tracer provider is shared by all the clients of the process, so it's only flushed.
+            // Flushing blocks until the spans are exported, which requires the runtime to keep running.
+            let _ = tokio::task::spawn_blocking(GlideOpenTelemetry::flush).await;
+        }
         match self.internal_client {
-            ClientWrapper::Standalone(_) => {
-                unreachable!("Cluster scan is not supported in standalone mode")
-            }
-            ClientWrapper::Cluster { ref mut client } => {
-                let (cursor, keys) = client
-                    .cluster_scan(scan_state_cursor.clone(), cluster_scan_args)
-                    .await?;
-                let cluster_cursor_id = if cursor.is_finished() {
-                    Value::BulkString(FINISHED_SCAN_CURSOR.into())
-                } else {
-                    Value::BulkString(insert_cluster_scan_cursor(cursor).into())
-                };
-                Ok(Value::Array(vec![cluster_cursor_id, Value::Array(keys)]))
-            }
+            ClientWrapper::Standalone(ref client) => client.close(),
+            ClientWrapper::Cluster { ref client } => client.close(),
         }
     }
 
@@ -339,6 +412,7 @@ impl Client {
         mut values: Vec<Value>,
         command_count: usize,
         offset: usize,
+        decompress: bool,
     ) -> RedisResult<Value> {
         assert_eq!(values.len(), 1);
         let value = values.pop();
@@ -367,18 +441,32 @@ impl Client {
                     .into());
             }
         };
-        Self::convert_transaction_values_to_expected_types(pipeline, values, command_count)
+        Self::convert_transaction_values_to_expected_types(
+            pipeline,
+            values,
+            command_count,
+            decompress,
+        )
     }
 
     fn convert_transaction_values_to_expected_types(
         pipeline: &redis::Pipeline,
         values: Vec<Value>,
         command_count: usize,
+        decompress: bool,
     ) -> RedisResult<Value> {
         let values = values
             .into_iter()
-            .zip(pipeline.cmd_iter().map(expected_type_for_cmd))
-            .map(|(value, expected_type)| convert_to_expected_type(value, expected_type))
+            .zip(pipeline.cmd_iter())
+            .map(|(value, cmd)| -> RedisResult<Value> {
+                let expected_type = expected_type_for_cmd(cmd);
+                let expected_type = if decompress {
+                    expected_type_with_decompression(cmd, &expected_type)
+                } else {
+                    expected_type
+                };
+                convert_to_expected_type(value, expected_type)
+            })
             .try_fold(
                 Vec::with_capacity(command_count),
                 |mut acc, result| -> RedisResult<_> {
@@ -396,7 +484,15 @@ impl Client {
     ) -> redis::RedisFuture<'a, Value> {
         let command_count = pipeline.cmd_iter().count();
         let offset = command_count + 1;
-        run_with_timeout(Some(self.request_timeout), async move {
+        let compressed_pipeline = self
+            .compression_config
+            .as_ref()
+            .and_then(|config| config.compress_transaction(pipeline));
+        let decompress = self.compression_config.is_some();
+        let shutdown = self.shutdown.clone();
+        let request = run_with_timeout(Some(self.request_timeout), async move {
+            let original_pipeline = pipeline;
+            let pipeline = compressed_pipeline.as_ref().unwrap_or(pipeline);
             let values = match self.internal_client {
                 ClientWrapper::Standalone(ref mut client) => {
                     client.send_pipeline(pipeline, offset, 1).await
@@ -410,9 +506,15 @@ impl Client {
                 },
             }?;
 
-            Self::get_transaction_values(pipeline, values, command_count, offset)
-        })
-        .boxed()
+            Self::get_transaction_values(
+                original_pipeline,
+                values,
+                command_count,
+                offset,
+                decompress,
+            )
+        });
+        async move { shutdown.run(request).await }.boxed()
     }
 
     pub async fn invoke_script<'a>(
@@ -439,33 +541,35 @@ impl Client {
         }
     }
 
+    /// Reserves an inflight request slot without waiting, regardless of the configured admission mode.
     pub fn reserve_inflight_request(&self) -> bool {
-        // We use this approach of checking the `inflight_requests_allowed` value
-        // twice, before and after decrementing, to prevent it from reaching negative
-        // values. Allowing the `inflight_requests_allowed` value to go below zero
-        // could lead to a race condition where tasks might not be able to run even
-        // when there are available slots.
-        if self.inflight_requests_allowed.load(Ordering::SeqCst) <= 0 {
-            false
-        } else {
-            // The value is being checked again because it might have changed
-            // during the intervening period since the load by other tasks.
-            if self
-                .inflight_requests_allowed
-                .fetch_sub(1, Ordering::SeqCst)
-                <= 0
-            {
-                self.inflight_requests_allowed
-                    .fetch_add(1, Ordering::SeqCst);
-                return false;
-            }
-            true
-        }
+        self.admission_control.try_reserve()
+    }
+
+    /// Reserves an inflight request slot according to the configured admission mode.
+    /// In queue mode, this waits for a slot to be released, up to the configured queue timeout.
+    pub async fn admit_inflight_request(&self, kind: RequestKind) -> Result<(), AdmissionError> {
+        self.admission_control.reserve(kind).await
     }
 
     pub fn release_inflight_request(&self) -> isize {
-        self.inflight_requests_allowed
-            .fetch_add(1, Ordering::SeqCst)
+        self.admission_control.release()
+    }
+
+    /// Returns the traffic statistics collected since the client was created or the stats were last reset.
+    /// Returns `None` unless the client is a cluster client configured to collect them.
+    pub fn traffic_stats(&self) -> Option<TrafficStatsSnapshot> {
+        match &self.internal_client {
+            ClientWrapper::Cluster { client } => client.traffic_stats(),
+            ClientWrapper::Standalone(_) => None,
+        }
+    }
+
+    /// Clears the traffic statistics collected thus far.
+    pub fn reset_traffic_stats(&self) {
+        if let ClientWrapper::Cluster { client } = &self.internal_client {
+            client.reset_traffic_stats();
+        }
     }
 
     /// Update the password used to authenticate with the servers.
@@ -581,6 +685,9 @@ async fn create_cluster_client(
     let read_from_strategy = request.read_from.unwrap_or_default();
     builder = builder.read_from(match read_from_strategy {
         ReadFrom::AZAffinity(az) => ReadFromReplicaStrategy::AZAffinity(az),
+        ReadFrom::AZAffinityReplicasAndPrimary(az) => {
+            ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary(az)
+        }
         ReadFrom::PreferReplica => ReadFromReplicaStrategy::RoundRobin,
         ReadFrom::Primary => ReadFromReplicaStrategy::AlwaysFromPrimary,
     });
@@ -602,12 +709,47 @@ async fn create_cluster_client(
     if let Some(pubsub_subscriptions) = redis_connection_info.pubsub_subscriptions.clone() {
         builder = builder.pubsub_subscriptions(pubsub_subscriptions);
     }
+    if let Some(address_mapper) = request.address_mapper {
+        builder = builder.address_mapper(address_mapper);
+    }
+    if request.proxy_mode {
+        builder = builder.proxy_mode();
+    }
+    if let Some(rate_limit) = request.slots_refresh_rate_limit {
+        builder = builder.slots_refresh_rate_limit(rate_limit.interval, rate_limit.max_jitter_ms);
+    }
+    if request.read_your_writes {
+        builder = builder.track_replication_offsets(DEFAULT_REPLICATION_CHECK_INTERVAL);
+    }
+    match request.traffic_stats {
+        Some(TrafficStatsConfig {
+            hot_keys: Some((capacity, sample_rate)),
+        }) => builder = builder.hot_keys(capacity, sample_rate),
+        Some(TrafficStatsConfig { hot_keys: None }) => builder = builder.traffic_stats(),
+        None => {}
+    }
+    if let Some(staleness) = request.replica_staleness {
+        builder = builder.replica_max_staleness(
+            staleness.max_offset_lag,
+            staleness
+                .check_interval
+                .unwrap_or(DEFAULT_REPLICATION_CHECK_INTERVAL),
+        );
+    }
 
     // Always use with Glide
     builder = builder.periodic_connections_checks(CONNECTION_CHECKS_INTERVAL);
+    builder = builder.use_cluster_shards();
 
     let client = builder.build()?;
+    let topology_events_sender = push_sender.clone().filter(|_| request.topology_events);
     let mut con = client.get_async_connection(push_sender).await?;
+    if let Some(sender) = topology_events_sender {
+        tokio::spawn(forward_topology_events(
+            con.subscribe_topology_events(),
+            sender,
+        ));
+    }
 
     // This validation ensures that sharded subscriptions are not applied to Redis engines older than version 7.0,
     // preventing scenarios where the client becomes inoperable or, worse, unaware that sharded pubsub messages are not being received.
@@ -658,6 +800,71 @@ async fn create_cluster_client(
     Ok(con)
 }
 
+/// Sends the cluster's topology changes to the client's push notifications, until either side is closed.
+async fn forward_topology_events(
+    mut events: tokio::sync::broadcast::Receiver<TopologyEvent>,
+    push_sender: mpsc::UnboundedSender<PushInfo>,
+) {
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                log_warn(
+                    "topology events",
+                    format!("{missed} topology change notifications were dropped"),
+                );
+                continue;
+            }
+            Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+        };
+        if push_sender.send(topology_event_push(event)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Encodes a topology change as a push notification. The first element of the data is the event's type.
+fn topology_event_push(event: TopologyEvent) -> PushInfo {
+    let string = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+    let optional_string = |value: Option<String>| value.as_deref().map_or(Value::Nil, string);
+    let data = match event {
+        TopologyEvent::SlotsMoved {
+            start,
+            end,
+            previous_primary,
+            primary,
+        } => vec![
+            string("slots_moved"),
+            Value::Int(start.into()),
+            Value::Int(end.into()),
+            optional_string(previous_primary),
+            optional_string(primary),
+        ],
+        TopologyEvent::Failover {
+            previous_primary,
+            primary,
+        } => vec![
+            string("failover"),
+            string(&previous_primary),
+            string(&primary),
+        ],
+        TopologyEvent::NodeAdded(address) => vec![string("node_added"), string(&address)],
+        TopologyEvent::NodeRemoved(address) => vec![string("node_removed"), string(&address)],
+        TopologyEvent::ConnectionStateChanged { address, state } => vec![
+            string("connection_state_changed"),
+            string(&address),
+            string(match state {
+                NodeConnectionState::Connected => "connected",
+                NodeConnectionState::Disconnected => "disconnected",
+            }),
+        ],
+    };
+    PushInfo {
+        kind: redis::PushKind::TopologyChange,
+        data,
+    }
+}
+
 #[derive(thiserror::Error)]
 pub enum ConnectionError {
     Standalone(standalone_client::StandaloneClientConnectionError),
@@ -738,6 +945,8 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
                     ReadFrom::Primary => "Only primary",
                     ReadFrom::PreferReplica => "Prefer replica",
                     ReadFrom::AZAffinity(_) => "Prefer replica in user's availability zone",
+                    ReadFrom::AZAffinityReplicasAndPrimary(_) =>
+                        "Prefer replica or primary in user's availability zone",
                 }
             )
         })
@@ -782,8 +991,72 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
         request.inflight_requests_limit,
     );
 
+    let admission_mode = request
+        .admission_mode
+        .map(|admission_mode| format!("\nInflight requests admission mode: {admission_mode:?}"))
+        .unwrap_or_default();
+
+    let compression_config = request
+        .compression_config
+        .as_ref()
+        .map(|config| format!("\nCompression: {config:?}"))
+        .unwrap_or_default();
+
+    let topology_events = if request.cluster_mode_enabled && request.topology_events {
+        "\nTopology change notifications: Enabled"
+    } else {
+        ""
+    };
+
+    let replica_staleness = match request.replica_staleness {
+        Some(staleness) if request.cluster_mode_enabled => format!(
+            "\nReplica max staleness: {} bytes, checked every {:?}",
+            staleness.max_offset_lag,
+            staleness
+                .check_interval
+                .unwrap_or(DEFAULT_REPLICATION_CHECK_INTERVAL)
+        ),
+        _ => String::new(),
+    };
+
+    let read_your_writes = if request.cluster_mode_enabled && request.read_your_writes {
+        "\nRead your writes: Enabled"
+    } else {
+        ""
+    };
+
+    let traffic_stats = match request.traffic_stats {
+        Some(TrafficStatsConfig {
+            hot_keys: Some((capacity, sample_rate)),
+        }) if request.cluster_mode_enabled => format!(
+            "\nTraffic stats: Enabled, tracking {capacity} hot keys sampled from 1 in {sample_rate} requests"
+        ),
+        Some(_) if request.cluster_mode_enabled => "\nTraffic stats: Enabled".to_string(),
+        _ => String::new(),
+    };
+
+    let proxy_mode = if request.cluster_mode_enabled && request.proxy_mode {
+        "\nCluster proxy mode: Enabled"
+    } else {
+        ""
+    };
+
+    let slots_refresh_rate_limit = match request.slots_refresh_rate_limit {
+        Some(rate_limit) if request.cluster_mode_enabled => format!(
+            "\nSlots refresh rate limit: {:?} with up to {}ms jitter",
+            rate_limit.interval, rate_limit.max_jitter_ms
+        ),
+        _ => String::new(),
+    };
+
+    let address_mapper = if request.address_mapper.is_some() {
+        "\nAddress mapping: Enabled"
+    } else {
+        ""
+    };
+
     format!(
-        "\nAddresses: {addresses}{tls_mode}{cluster_mode}{request_timeout}{connection_timeout}{rfr_strategy}{connection_retry_strategy}{database_id}{protocol}{client_name}{periodic_checks}{pubsub_subscriptions}{inflight_requests_limit}",
+        "\nAddresses: {addresses}{tls_mode}{cluster_mode}{request_timeout}{connection_timeout}{rfr_strategy}{connection_retry_strategy}{database_id}{protocol}{client_name}{periodic_checks}{pubsub_subscriptions}{inflight_requests_limit}{admission_mode}{compression_config}{address_mapper}{topology_events}{replica_staleness}{read_your_writes}{traffic_stats}{proxy_mode}{slots_refresh_rate_limit}",
     )
 }
 
@@ -802,9 +1075,11 @@ impl Client {
         let inflight_requests_limit = request
             .inflight_requests_limit
             .unwrap_or(DEFAULT_MAX_INFLIGHT_REQUESTS);
-        let inflight_requests_allowed = Arc::new(AtomicIsize::new(
-            inflight_requests_limit.try_into().unwrap(),
+        let admission_control = Arc::new(AdmissionControl::new(
+            inflight_requests_limit,
+            request.admission_mode.unwrap_or_default(),
         ));
+        let compression_config = request.compression_config.clone();
 
         if let Some(endpoint_str) = &request.otel_endpoint {
             let trace_exporter = GlideOpenTelemetryTraceExporter::from_str(endpoint_str.as_str())
@@ -820,6 +1095,9 @@ impl Client {
 
             GlideOpenTelemetry::initialise(config);
         };
+        let flush_telemetry_on_close = request.otel_endpoint.is_some();
+        let session = (request.cluster_mode_enabled && request.read_your_writes)
+            .then(|| Arc::new(SessionToken::default()));
 
         tokio::time::timeout(DEFAULT_CLIENT_CREATION_TIMEOUT, async move {
             let internal_client = if request.cluster_mode_enabled {
@@ -838,7 +1116,11 @@ impl Client {
             Ok(Self {
                 internal_client,
                 request_timeout,
-                inflight_requests_allowed,
+                admission_control,
+                compression_config,
+                shutdown: Arc::new(Shutdown::new()),
+                flush_telemetry_on_close,
+                session,
             })
         })
         .await
@@ -1033,4 +1315,25 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), Some(Duration::from_millis(100)));
     }
+
+    #[test]
+    fn test_topology_event_push_encodes_event_type_first() {
+        let push = super::topology_event_push(redis::cluster_topology::TopologyEvent::SlotsMoved {
+            start: 0,
+            end: 100,
+            previous_primary: None,
+            primary: Some("node1:6379".to_string()),
+        });
+        assert_eq!(push.kind, redis::PushKind::TopologyChange);
+        assert_eq!(
+            push.data,
+            vec![
+                redis::Value::BulkString(b"slots_moved".to_vec()),
+                redis::Value::Int(0),
+                redis::Value::Int(100),
+                redis::Value::Nil,
+                redis::Value::BulkString(b"node1:6379".to_vec()),
+            ]
+        );
+    }
 }
@@ -252,13 +252,25 @@ impl ReconnectingConnection {
     }
 
     pub(super) fn mark_as_dropped(&self) {
+        // The connection might be marked twice, if the client was closed before being dropped.
+        if self
+            .inner
+            .backend
+            .client_dropped_flagged
+            .swap(true, Ordering::Relaxed)
+        {
+            return;
+        }
         // Update the telemetry for each connection that is dropped. A dropped connection
         // will not be re-connected, so update the telemetry here
         Telemetry::decr_total_connections(1);
-        self.inner
-            .backend
-            .client_dropped_flagged
-            .store(true, Ordering::Relaxed)
+    }
+
+    /// Marks the connection as dropped and releases the underlying connection, so it will be
+    /// closed once the requests currently using it complete.
+    pub(super) fn close(&self) {
+        self.mark_as_dropped();
+        *self.inner.state.lock().unwrap() = ConnectionState::InitializedDisconnected;
     }
 
     pub(super) async fn try_get_connection(&self) -> Option<MultiplexedConnection> {
@@ -0,0 +1,179 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use logger_core::log_warn;
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_routing::{
+    is_readonly_cmd, Routable, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
+};
+use redis::{cmd, Cmd, FromRedisValue, InfoDict, RedisResult, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Provides read-your-writes consistency for the reads routed to replicas.
+///
+/// After a write to a slot, the replication offset of the slot's primary is recorded. Subsequent reads of the
+/// slot are only routed to replicas known to have reached that offset, or to the primary otherwise.
+/// Only writes routed to a single slot are tracked.
+///
+/// The offset is read with `INFO REPLICATION` after each tracked write, so every tracked write costs an additional round-trip.
+#[derive(Default)]
+pub(crate) struct SessionToken {
+    offsets: Mutex<HashMap<u16, WrittenOffset>>,
+}
+
+/// The replication offset that must be reached to serve reads of a written slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WrittenOffset {
+    Known(i64),
+    /// The offset of the last write couldn't be read, so reads are served by the primary until the next write's offset is known.
+    Unknown,
+}
+
+impl SessionToken {
+    /// Returns the offset a node must have reached to serve a read of `slot`, if the slot was written to.
+    fn min_offset(&self, slot: u16) -> Option<WrittenOffset> {
+        self.offsets.lock().unwrap().get(&slot).copied()
+    }
+
+    fn record_write(&self, slot: u16, offset: WrittenOffset) {
+        let mut offsets = self.offsets.lock().unwrap();
+        let min_offset = offsets.entry(slot).or_insert(offset);
+        *min_offset = match (*min_offset, offset) {
+            (WrittenOffset::Known(previous), WrittenOffset::Known(offset)) => {
+                WrittenOffset::Known(previous.max(offset))
+            }
+            // The offset read after a write covers all the previous writes to the slot.
+            (_, offset) => offset,
+        };
+    }
+
+    /// Restricts a read of a slot written to in this session to the nodes that have applied the write.
+    fn read_routing(&self, routing: RoutingInfo) -> RoutingInfo {
+        match routing {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))
+                if route.slot_addr() != SlotAddr::Master =>
+            {
+                match self.min_offset(route.slot()) {
+                    Some(WrittenOffset::Known(min_offset)) => {
+                        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNodeAtOffset {
+                            route,
+                            min_offset,
+                        })
+                    }
+                    Some(WrittenOffset::Unknown) => {
+                        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                            route.slot(),
+                            SlotAddr::Master,
+                        )))
+                    }
+                    None => RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)),
+                }
+            }
+            routing => routing,
+        }
+    }
+
+    /// Sends `cmd` to the cluster, routing reads according to the writes of this session.
+    pub(crate) async fn route_command(
+        &self,
+        client: &mut ClusterConnection,
+        cmd: &Cmd,
+        routing: RoutingInfo,
+    ) -> RedisResult<Value> {
+        if is_readonly_cmd(&cmd.command().unwrap_or_default()) {
+            return client.route_command(cmd, self.read_routing(routing)).await;
+        }
+
+        let written_slot = match &routing {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)) => {
+                Some(route.slot())
+            }
+            _ => None,
+        };
+        let value = client.route_command(cmd, routing).await?;
+        if let Some(slot) = written_slot {
+            let offset = match Self::primary_offset(client, slot).await {
+                Ok(offset) => WrittenOffset::Known(offset),
+                Err(err) => {
+                    log_warn(
+                        "read your writes",
+                        format!("Failed to get the replication offset of slot {slot}: {err}"),
+                    );
+                    WrittenOffset::Unknown
+                }
+            };
+            self.record_write(slot, offset);
+        }
+        Ok(value)
+    }
+
+    async fn primary_offset(client: &mut ClusterConnection, slot: u16) -> RedisResult<i64> {
+        let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+            slot,
+            SlotAddr::Master,
+        )));
+        let value = client
+            .route_command(cmd("INFO").arg("REPLICATION"), routing)
+            .await?;
+        InfoDict::from_redis_value(&value)?
+            .get("master_repl_offset")
+            .ok_or_else(|| {
+                (
+                    redis::ErrorKind::ResponseError,
+                    "Missing `master_repl_offset` in INFO REPLICATION",
+                )
+                    .into()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_route(slot: u16) -> RoutingInfo {
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+            slot,
+            SlotAddr::ReplicaOptional,
+        )))
+    }
+
+    #[test]
+    fn test_reads_of_written_slots_require_the_written_offset() {
+        let session = SessionToken::default();
+        session.record_write(12, WrittenOffset::Known(100));
+        session.record_write(12, WrittenOffset::Known(50));
+
+        assert_eq!(
+            session.read_routing(read_route(12)),
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNodeAtOffset {
+                route: Route::new(12, SlotAddr::ReplicaOptional),
+                min_offset: 100,
+            })
+        );
+        assert_eq!(session.read_routing(read_route(13)), read_route(13));
+    }
+
+    #[test]
+    fn test_reads_are_served_by_the_primary_until_the_offset_is_known() {
+        let session = SessionToken::default();
+        session.record_write(12, WrittenOffset::Known(100));
+        session.record_write(12, WrittenOffset::Unknown);
+        assert_eq!(
+            session.read_routing(read_route(12)),
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                12,
+                SlotAddr::Master
+            )))
+        );
+
+        session.record_write(12, WrittenOffset::Known(150));
+        assert_eq!(
+            session.read_routing(read_route(12)),
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNodeAtOffset {
+                route: Route::new(12, SlotAddr::ReplicaOptional),
+                min_offset: 150,
+            })
+        );
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{ErrorKind, RedisError, RedisResult};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::{watch, Notify};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Open,
+    /// New requests are rejected, and inflight requests are allowed to complete.
+    Draining,
+    /// Inflight requests are cancelled.
+    Closed,
+}
+
+/// Tracks the requests running on a client, so that closing the client can wait for them to complete.
+pub(crate) struct Shutdown {
+    state: watch::Sender<State>,
+    inflight_requests: AtomicUsize,
+    drained: Notify,
+}
+
+struct InflightRequestGuard<'a>(&'a Shutdown);
+
+impl Drop for InflightRequestGuard<'_> {
+    fn drop(&mut self) {
+        if self.0.inflight_requests.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.drained.notify_waiters();
+        }
+    }
+}
+
+fn closed_error() -> RedisError {
+    (ErrorKind::ClientError, "The client is closed").into()
+}
+
+fn cancelled_error() -> RedisError {
+    (
+        ErrorKind::RequestCancelled,
+        "Request cancelled",
+        "the client was closed before the request completed".to_string(),
+    )
+        .into()
+}
+
+impl Shutdown {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: watch::channel(State::Open).0,
+            inflight_requests: AtomicUsize::new(0),
+            drained: Notify::new(),
+        }
+    }
+
+    /// Runs `request` unless the client is closing. The request is cancelled if the client
+    /// is closed before it completes.
+    pub(crate) async fn run<T>(
+        &self,
+        request: impl Future<Output = RedisResult<T>>,
+    ) -> RedisResult<T> {
+        let mut state = self.state.subscribe();
+        // The request is counted before the state is checked, so a concurrent `close`
+        // either sees this request as inflight, or this request sees the client as closing.
+        self.inflight_requests.fetch_add(1, Ordering::SeqCst);
+        let _guard = InflightRequestGuard(self);
+        if *state.borrow_and_update() != State::Open {
+            return Err(closed_error());
+        }
+        tokio::select! {
+            result = request => result,
+            _ = state.wait_for(|state| *state == State::Closed) => Err(cancelled_error()),
+        }
+    }
+
+    /// Stops accepting new requests, and waits up to `deadline` for the inflight requests to complete.
+    /// Requests that are still running once the deadline passes are cancelled.
+    /// Returns the number of cancelled requests.
+    pub(crate) async fn close(&self, deadline: Option<Duration>) -> usize {
+        self.state.send_if_modified(|state| {
+            let open = *state == State::Open;
+            if open {
+                *state = State::Draining;
+            }
+            open
+        });
+
+        let drain = async {
+            loop {
+                let drained = self.drained.notified();
+                tokio::pin!(drained);
+                // Register for the notification before checking the counter, so a notification sent in between isn't missed.
+                drained.as_mut().enable();
+                if self.inflight_requests.load(Ordering::SeqCst) == 0 {
+                    return;
+                }
+                drained.await;
+            }
+        };
+        let cancelled = match deadline {
+            Some(deadline) => match tokio::time::timeout(deadline, drain).await {
+                Ok(()) => 0,
+                Err(_) => self.inflight_requests.load(Ordering::SeqCst),
+            },
+            None => {
+                drain.await;
+                0
+            }
+        };
+        self.state.send_replace(State::Closed);
+        cancelled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_close_waits_for_inflight_requests() {
+        let shutdown = Arc::new(Shutdown::new());
+        let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+        let request = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                shutdown
+                    .run(async {
+                        receiver.await.unwrap();
+                        Ok(1)
+                    })
+                    .await
+            })
+        };
+        while shutdown.inflight_requests.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let close = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move { shutdown.close(Some(Duration::from_secs(5))).await })
+        };
+        while *shutdown.state.borrow() == State::Open {
+            tokio::task::yield_now().await;
+        }
+        let rejected = shutdown.run(async { Ok(2) }).await.unwrap_err();
+        assert_eq!(rejected.kind(), ErrorKind::ClientError);
+
+        sender.send(()).unwrap();
+        assert_eq!(request.await.unwrap().unwrap(), 1);
+        assert_eq!(close.await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_close_cancels_requests_after_deadline() {
+        let shutdown = Arc::new(Shutdown::new());
+        let request = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                shutdown
+                    .run(futures::future::pending::<RedisResult<()>>())
+                    .await
+            })
+        };
+        while shutdown.inflight_requests.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        assert_eq!(shutdown.close(Some(Duration::from_millis(10))).await, 1);
+        let err = request.await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::RequestCancelled);
+        assert!(matches!(
+            crate::errors::error_type(&err),
+            crate::errors::RequestErrorType::Cancelled
+        ));
+    }
+}
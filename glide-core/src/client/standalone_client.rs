@@ -31,6 +31,10 @@ enum ReadFrom {
         client_az: String,
         last_read_replica_index: Arc<AtomicUsize>,
     },
+    AZAffinityReplicasAndPrimary {
+        client_az: String,
+        last_read_replica_index: Arc<AtomicUsize>,
+    },
 }
 
 #[derive(Debug)]
@@ -129,7 +133,7 @@ impl StandaloneClient {
         let pubsub_addr = &connection_request.addresses[pubsub_node_index];
         let discover_az = matches!(
             connection_request.read_from,
-            Some(ClientReadFrom::AZAffinity(_))
+            Some(ClientReadFrom::AZAffinity(_) | ClientReadFrom::AZAffinityReplicasAndPrimary(_))
         );
 
         let connection_timeout = to_duration(
@@ -273,6 +277,44 @@ impl StandaloneClient {
         latest_read_replica_index: &Arc<AtomicUsize>,
         client_az: String,
     ) -> &ReconnectingConnection {
+        match self
+            .round_robin_read_from_same_az_node(latest_read_replica_index, &client_az, false)
+            .await
+        {
+            Some(replica) => replica,
+            // Attempt a fallback to any available replica in other AZs or primary.
+            None => self.round_robin_read_from_replica(latest_read_replica_index),
+        }
+    }
+
+    async fn round_robin_read_from_az_replica_or_primary(
+        &self,
+        latest_read_replica_index: &Arc<AtomicUsize>,
+        client_az: String,
+    ) -> &ReconnectingConnection {
+        if let Some(replica) = self
+            .round_robin_read_from_same_az_node(latest_read_replica_index, &client_az, true)
+            .await
+        {
+            return replica;
+        }
+        let primary = self.get_primary_connection();
+        if let Ok(connection) = primary.get_connection().await {
+            if connection.get_az().as_deref() == Some(client_az.as_str()) {
+                return primary;
+            }
+        }
+        self.round_robin_read_from_replica(latest_read_replica_index)
+    }
+
+    /// Returns the next node in the client's AZ, in a round robin manner. If `skip_primary` is set, only replicas are
+    /// considered, so that the primary is used only after all the same-AZ replicas.
+    async fn round_robin_read_from_same_az_node(
+        &self,
+        latest_read_replica_index: &Arc<AtomicUsize>,
+        client_az: &str,
+        skip_primary: bool,
+    ) -> Option<&ReconnectingConnection> {
         let initial_index = latest_read_replica_index.load(Ordering::Relaxed);
         let mut retries = 0usize;
 
@@ -280,12 +322,14 @@ impl StandaloneClient {
             retries = retries.saturating_add(1);
             // Looped through all replicas; no connected replica found in the same AZ.
             if retries > self.inner.nodes.len() {
-                // Attempt a fallback to any available replica in other AZs or primary.
-                return self.round_robin_read_from_replica(latest_read_replica_index);
+                return None;
             }
 
             // Calculate index based on initial index and check count.
             let index = (initial_index + retries) % self.inner.nodes.len();
+            if skip_primary && index == self.inner.primary_index {
+                continue;
+            }
             let replica = &self.inner.nodes[index];
 
             // Attempt to get a connection and retrieve the replica's AZ.
@@ -299,7 +343,7 @@ impl StandaloneClient {
                             Ordering::Relaxed,
                             Ordering::Relaxed,
                         );
-                        return replica;
+                        return Some(replica);
                     }
                 }
             }
@@ -326,6 +370,16 @@ impl StandaloneClient {
                 )
                 .await
             }
+            ReadFrom::AZAffinityReplicasAndPrimary {
+                client_az,
+                last_read_replica_index,
+            } => {
+                self.round_robin_read_from_az_replica_or_primary(
+                    last_read_replica_index,
+                    client_az.to_string(),
+                )
+                .await
+            }
         }
     }
 
@@ -551,6 +605,13 @@ impl StandaloneClient {
             .update_connection_password(password.clone())
             .await
     }
+
+    /// Closes the connections to all nodes. The connections won't be re-established.
+    pub fn close(&self) {
+        for node in self.inner.nodes.iter() {
+            node.close();
+        }
+    }
 }
 
 async fn get_connection_and_replication_info(
@@ -608,6 +669,12 @@ fn get_read_from(read_from: Option<super::ReadFrom>) -> ReadFrom {
             client_az: az,
             last_read_replica_index: Default::default(),
         },
+        Some(super::ReadFrom::AZAffinityReplicasAndPrimary(az)) => {
+            ReadFrom::AZAffinityReplicasAndPrimary {
+                client_az: az,
+                last_read_replica_index: Default::default(),
+            }
+        }
         None => ReadFrom::Primary,
     }
 }
@@ -6,6 +6,13 @@ use logger_core::log_warn;
 use std::collections::HashSet;
 use std::time::Duration;
 
+#[cfg(feature = "socket-layer")]
+use super::admission_control::{DEFAULT_ADMISSION_QUEUE_SIZE, DEFAULT_ADMISSION_QUEUE_TIMEOUT};
+#[cfg(feature = "socket-layer")]
+use super::DEFAULT_HOT_KEYS_SAMPLE_RATE;
+#[cfg(feature = "socket-layer")]
+use crate::compression::CompressionBackend;
+use crate::compression::CompressionConfig;
 #[cfg(feature = "socket-layer")]
 use crate::connection_request as protobuf;
 
@@ -27,6 +34,42 @@ pub struct ConnectionRequest {
     pub inflight_requests_limit: Option<u32>,
     pub otel_endpoint: Option<String>,
     pub otel_span_flush_interval_ms: Option<u64>,
+    pub compression_config: Option<CompressionConfig>,
+    pub admission_mode: Option<AdmissionMode>,
+    /// Translates the node addresses reported by the cluster. Only used in cluster mode.
+    pub address_mapper: Option<redis::cluster::AddressMapper>,
+    /// Whether the cluster's topology changes are sent as push notifications. Only used in cluster mode.
+    pub topology_events: bool,
+    /// Excludes lagging replicas from reads. Only used in cluster mode.
+    pub replica_staleness: Option<ReplicaStaleness>,
+    /// Whether reads routed to replicas observe the client's previous writes. Only used in cluster mode.
+    /// Each write routed to a single slot is followed by a query of the primary's replication offset.
+    pub read_your_writes: bool,
+    /// Collects traffic statistics. Only used in cluster mode.
+    pub traffic_stats: Option<TrafficStatsConfig>,
+    /// Whether the addresses are of a cluster proxy, to which all requests are sent. Only used in cluster mode.
+    pub proxy_mode: bool,
+    /// Limits how often the topology is refreshed. When `None`, the default rate limit is used. Only used in cluster mode.
+    pub slots_refresh_rate_limit: Option<SlotsRefreshRateLimit>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotsRefreshRateLimit {
+    pub interval: Duration,
+    pub max_jitter_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplicaStaleness {
+    pub max_offset_lag: u64,
+    /// When `None`, the default interval is used.
+    pub check_interval: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrafficStatsConfig {
+    /// The number of hot keys tracked, and one in how many requests is sampled for them.
+    pub hot_keys: Option<(usize, u32)>,
 }
 
 pub struct AuthenticationInfo {
@@ -42,6 +85,20 @@ pub enum PeriodicCheck {
     ManualInterval(Duration),
 }
 
+/// Defines what happens to a request once the inflight requests limit is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AdmissionMode {
+    /// Reject the request immediately.
+    #[default]
+    FailFast,
+    /// Wait for an inflight request to complete, up to `queue_timeout`.
+    /// Requests are rejected immediately if `max_queue_size` requests are already waiting.
+    Queue {
+        max_queue_size: u32,
+        queue_timeout: Duration,
+    },
+}
+
 #[derive(Debug)]
 pub struct NodeAddress {
     pub host: String,
@@ -60,6 +117,7 @@ pub enum ReadFrom {
     Primary,
     PreferReplica,
     AZAffinity(String),
+    AZAffinityReplicasAndPrimary(String),
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Default)]
@@ -97,24 +155,31 @@ fn none_if_zero(value: u32) -> Option<u32> {
 #[cfg(feature = "socket-layer")]
 impl From<protobuf::ConnectionRequest> for ConnectionRequest {
     fn from(value: protobuf::ConnectionRequest) -> Self {
+        // The AZ affinity strategies fall back to `ReadFrom::PreferReplica` if the client's AZ isn't set.
+        let client_az = || {
+            let client_az = chars_to_string_option(&value.client_az);
+            if client_az.is_none() {
+                log_warn(
+                    "types",
+                    format!(
+                        "Failed to convert availability zone string: '{:?}'. Falling back to `ReadFrom::PreferReplica`",
+                        value.client_az
+                    ),
+                );
+            }
+            client_az
+        };
         let read_from = value.read_from.enum_value().ok().map(|val| match val {
             protobuf::ReadFrom::Primary => ReadFrom::Primary,
             protobuf::ReadFrom::PreferReplica => ReadFrom::PreferReplica,
             protobuf::ReadFrom::LowestLatency => todo!(),
             protobuf::ReadFrom::AZAffinity => {
-                if let Some(client_az) = chars_to_string_option(&value.client_az) {
-                    ReadFrom::AZAffinity(client_az)
-                } else {
-                    log_warn(
-                        "types",
-                        format!(
-                            "Failed to convert availability zone string: '{:?}'. Falling back to `ReadFrom::PreferReplica`",
-                            value.client_az
-                        ),
-                    );
-                    ReadFrom::PreferReplica
-                }
+                client_az().map_or(ReadFrom::PreferReplica, ReadFrom::AZAffinity)
             }
+            protobuf::ReadFrom::AZAffinityReplicasAndPrimary => client_az().map_or(
+                ReadFrom::PreferReplica,
+                ReadFrom::AZAffinityReplicasAndPrimary,
+            ),
         });
 
         let client_name = chars_to_string_option(&value.client_name);
@@ -211,6 +276,76 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
         let otel_endpoint = chars_to_string_option(&value.opentelemetry_config.collector_end_point);
         let otel_span_flush_interval_ms = value.opentelemetry_config.span_flush_interval;
 
+        let compression_config = value.compression_config.0.map(|config| CompressionConfig {
+            enabled: config.enabled,
+            backend: match config.backend.enum_value_or_default() {
+                protobuf::CompressionBackend::Zstd => CompressionBackend::Zstd,
+                protobuf::CompressionBackend::Lz4 => CompressionBackend::Lz4,
+            },
+            compression_level: config.compression_level,
+            min_compression_size: none_if_zero(config.min_compression_size)
+                .map(|size| size as usize),
+        });
+
+        let admission_mode = value.inflight_admission_config.0.map(|config| {
+            match config.mode.enum_value_or_default() {
+                protobuf::InflightAdmissionMode::FailFast => AdmissionMode::FailFast,
+                protobuf::InflightAdmissionMode::Queue => AdmissionMode::Queue {
+                    max_queue_size: none_if_zero(config.max_queue_size)
+                        .unwrap_or(DEFAULT_ADMISSION_QUEUE_SIZE),
+                    queue_timeout: none_if_zero(config.queue_timeout)
+                        .map(|timeout| Duration::from_millis(timeout.into()))
+                        .unwrap_or(DEFAULT_ADMISSION_QUEUE_TIMEOUT),
+                },
+            }
+        });
+
+        let address_mapper = (!value.address_mappings.is_empty()).then(|| {
+            redis::cluster::AddressMapper::from_table(
+                value
+                    .address_mappings
+                    .iter()
+                    .map(|mapping| {
+                        (
+                            (mapping.from.host.to_string(), mapping.from.port as u16),
+                            (mapping.to.host.to_string(), mapping.to.port as u16),
+                        )
+                    })
+                    .collect(),
+            )
+        });
+
+        let replica_staleness = value
+            .replica_staleness_config
+            .0
+            .map(|config| ReplicaStaleness {
+                max_offset_lag: config.max_offset_lag,
+                check_interval: none_if_zero(config.check_interval)
+                    .map(|interval| Duration::from_millis(interval.into())),
+            });
+
+        let traffic_stats = value
+            .traffic_stats_config
+            .0
+            .map(|config| TrafficStatsConfig {
+                hot_keys: none_if_zero(config.hot_keys_capacity).map(|capacity| {
+                    (
+                        capacity as usize,
+                        none_if_zero(config.hot_keys_sample_rate)
+                            .unwrap_or(DEFAULT_HOT_KEYS_SAMPLE_RATE),
+                    )
+                }),
+            });
+
+        let slots_refresh_rate_limit =
+            value
+                .slots_refresh_rate_limit
+                .0
+                .map(|config| SlotsRefreshRateLimit {
+                    interval: Duration::from_millis(config.interval.into()),
+                    max_jitter_ms: config.max_jitter.into(),
+                });
+
         ConnectionRequest {
             read_from,
             client_name,
@@ -228,6 +363,59 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
             inflight_requests_limit,
             otel_endpoint,
             otel_span_flush_interval_ms,
+            compression_config,
+            admission_mode,
+            address_mapper,
+            topology_events: value.topology_change_notifications,
+            replica_staleness,
+            read_your_writes: value.read_your_writes,
+            traffic_stats,
+            proxy_mode: value.cluster_proxy_mode,
+            slots_refresh_rate_limit,
         }
     }
 }
+
+#[cfg(all(test, feature = "socket-layer"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_config_from_protobuf() {
+        let mut request = protobuf::ConnectionRequest::new();
+        assert_eq!(
+            ConnectionRequest::from(request.clone()).compression_config,
+            None
+        );
+
+        let mut config = protobuf::CompressionConfig::new();
+        config.enabled = true;
+        config.backend = protobuf::CompressionBackend::Lz4.into();
+        config.compression_level = Some(3);
+        config.min_compression_size = 128;
+        request.compression_config = Some(config).into();
+        assert_eq!(
+            ConnectionRequest::from(request.clone()).compression_config,
+            Some(CompressionConfig {
+                enabled: true,
+                backend: CompressionBackend::Lz4,
+                compression_level: Some(3),
+                min_compression_size: Some(128),
+            })
+        );
+
+        // Zeros and unset fields fall back to the defaults.
+        let mut config = protobuf::CompressionConfig::new();
+        config.enabled = true;
+        request.compression_config = Some(config).into();
+        assert_eq!(
+            ConnectionRequest::from(request).compression_config,
+            Some(CompressionConfig {
+                enabled: true,
+                backend: CompressionBackend::Zstd,
+                compression_level: None,
+                min_compression_size: None,
+            })
+        );
+    }
+}
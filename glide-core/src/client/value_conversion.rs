@@ -1,5 +1,6 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
+use crate::compression::{decompress_stored_values, returns_stored_values};
 use redis::{
     cluster_routing::Routable, from_owned_redis_value, Cmd, ErrorKind, RedisResult, Value,
 };
@@ -44,6 +45,8 @@ pub(crate) enum ExpectedReturnType<'a> {
     SimpleString,
     XAutoClaimReturnType,
     XInfoStreamFullReturnType,
+    // Converts the response to the inner type, and then decompresses the stored values in it.
+    Decompressed(&'a Option<ExpectedReturnType<'a>>),
 }
 
 pub(crate) fn convert_to_expected_type(
@@ -55,6 +58,9 @@ pub(crate) fn convert_to_expected_type(
     };
 
     match expected {
+        ExpectedReturnType::Decompressed(inner_type) => {
+            convert_to_expected_type(value, *inner_type).map(decompress_stored_values)
+        }
         ExpectedReturnType::Map {
             key_type,
             value_type,
@@ -1391,6 +1397,20 @@ fn is_array(val: Value) -> bool {
     matches!(val, Value::Array(_))
 }
 
+/// Returns the expected type of the response of `cmd` when compression is configured, so that the stored values in it are decompressed.
+/// `expected_type` is the type returned by [`expected_type_for_cmd`].
+pub(crate) fn expected_type_with_decompression<'a>(
+    cmd: &Cmd,
+    expected_type: &'a Option<ExpectedReturnType<'a>>,
+) -> Option<ExpectedReturnType<'a>> {
+    match cmd.command() {
+        Some(command) if returns_stored_values(&command) => {
+            Some(ExpectedReturnType::Decompressed(expected_type))
+        }
+        _ => *expected_type,
+    }
+}
+
 pub(crate) fn expected_type_for_cmd(cmd: &Cmd) -> Option<ExpectedReturnType> {
     let command = cmd.command()?;
 
@@ -1566,6 +1586,48 @@ pub(crate) fn get_value_type<'a>(value: &Value) -> &'a str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compression::CompressionConfig;
+
+    #[test]
+    fn convert_decompressed_hgetall() {
+        let value = "glide".repeat(100).into_bytes();
+        let config = CompressionConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let compressed = config.compress(&value).unwrap();
+        let cmd = redis::cmd("HGETALL").arg("key").clone();
+        let expected_type = expected_type_for_cmd(&cmd);
+        // The RESP2 response is converted to a map before its values are decompressed.
+        let resp2_response = Value::Array(vec![
+            Value::BulkString(b"field".to_vec()),
+            Value::BulkString(compressed),
+        ]);
+        assert_eq!(
+            convert_to_expected_type(
+                resp2_response,
+                expected_type_with_decompression(&cmd, &expected_type)
+            )
+            .unwrap(),
+            Value::Map(vec![(
+                Value::BulkString(b"field".to_vec()),
+                Value::BulkString(value),
+            )])
+        );
+    }
+
+    #[test]
+    fn expected_type_with_decompression_only_decompresses_stored_values() {
+        let get = redis::cmd("GET").arg("key").clone();
+        assert!(matches!(
+            expected_type_with_decompression(&get, &expected_type_for_cmd(&get)),
+            Some(ExpectedReturnType::Decompressed(&None))
+        ));
+        let strlen = redis::cmd("STRLEN").arg("key").clone();
+        assert!(
+            expected_type_with_decompression(&strlen, &expected_type_for_cmd(&strlen)).is_none()
+        );
+    }
 
     #[test]
     fn xinfo_stream_expected_return_type() {
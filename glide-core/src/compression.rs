@@ -0,0 +1,355 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Opt-in transparent compression of stored values.
+//!
+//! Compressed values are framed with a short header - a 4 byte magic followed by a backend id - so that
+//! readers can tell compressed values apart from plain ones. Values without the header, or values that
+//! fail to decompress, are returned as-is, which lets clients read keys written before compression was
+//! enabled, and lets readers be upgraded before writers start compressing.
+//!
+//! Only the values of plain string and hash writes are compressed. `JSON.SET` documents are never compressed:
+//! the server parses them and would reject the framed bytes, and paths couldn't be queried in a compressed
+//! document. Values that should be compressed must be stored with `SET` instead.
+//!
+//! Responses are decompressed while they're converted to their expected type, see
+//! `ExpectedReturnType::Decompressed`.
+
+use redis::cluster_routing::Routable;
+use redis::{Cmd, Pipeline, Value};
+use std::io::Read;
+
+/// The magic bytes that open every compressed value. The leading NUL byte makes collisions with textual values unlikely.
+pub const COMPRESSION_MAGIC: [u8; 4] = [0x00, b'G', b'L', b'Z'];
+const HEADER_LEN: usize = COMPRESSION_MAGIC.len() + 1;
+
+/// Values smaller than this are written uncompressed, unless configured otherwise.
+pub const DEFAULT_MIN_COMPRESSION_SIZE: usize = 64;
+
+/// Values that decompress to more than this many bytes are returned as-is. This matches the server's
+/// largest bulk string, and protects readers from values that claim to decompress to huge sizes.
+pub const MAX_DECOMPRESSED_SIZE: usize = 512 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompressionBackend {
+    #[default]
+    Zstd,
+    Lz4,
+}
+
+impl CompressionBackend {
+    fn id(self) -> u8 {
+        match self {
+            CompressionBackend::Zstd => 1,
+            CompressionBackend::Lz4 => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(CompressionBackend::Zstd),
+            2 => Some(CompressionBackend::Lz4),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompressionConfig {
+    /// Compress values on writes. When disabled, values are still decompressed on reads.
+    pub enabled: bool,
+    pub backend: CompressionBackend,
+    /// Backend specific compression level. Ignored by LZ4.
+    pub compression_level: Option<i32>,
+    pub min_compression_size: Option<usize>,
+}
+
+impl CompressionConfig {
+    /// Returns the framed, compressed form of `value`, or `None` if the value should be stored as-is.
+    pub fn compress(&self, value: &[u8]) -> Option<Vec<u8>> {
+        if !self.enabled
+            || value.len()
+                < self
+                    .min_compression_size
+                    .unwrap_or(DEFAULT_MIN_COMPRESSION_SIZE)
+            || is_compressed(value)
+        {
+            return None;
+        }
+        let mut framed = Vec::with_capacity(HEADER_LEN + value.len() / 2);
+        framed.extend_from_slice(&COMPRESSION_MAGIC);
+        framed.push(self.backend.id());
+        match self.backend {
+            CompressionBackend::Zstd => {
+                let level = self
+                    .compression_level
+                    .unwrap_or(zstd::DEFAULT_COMPRESSION_LEVEL);
+                zstd::stream::copy_encode(value, &mut framed, level).ok()?;
+            }
+            CompressionBackend::Lz4 => {
+                framed.extend_from_slice(&lz4_flex::compress_prepend_size(value));
+            }
+        }
+        // Compression isn't worth it if it doesn't save space.
+        (framed.len() < value.len()).then_some(framed)
+    }
+
+    /// Returns a copy of `cmd` with its value arguments compressed, or `None` if no argument was compressed.
+    pub(crate) fn compress_command(&self, cmd: &Cmd) -> Option<Cmd> {
+        if !self.enabled {
+            return None;
+        }
+        let (first_value_index, step) = value_arguments(&cmd.command()?)?;
+        let mut compressed_any = false;
+        let mut compressed_cmd = Cmd::new();
+        for (index, arg) in cmd.args_iter().enumerate() {
+            let redis::Arg::Simple(arg) = arg else {
+                return None;
+            };
+            let is_value = index >= first_value_index && (index - first_value_index) % step == 0;
+            match is_value.then(|| self.compress(arg)).flatten() {
+                Some(compressed) => {
+                    compressed_any = true;
+                    compressed_cmd.arg(compressed);
+                }
+                None => {
+                    compressed_cmd.arg(arg);
+                }
+            }
+        }
+        compressed_any.then_some(compressed_cmd)
+    }
+
+    /// Returns a copy of the transaction with its commands' value arguments compressed, or `None` if nothing was compressed.
+    pub(crate) fn compress_transaction(&self, pipeline: &Pipeline) -> Option<Pipeline> {
+        if !self.enabled {
+            return None;
+        }
+        let mut compressed_any = false;
+        let mut compressed_pipeline = Pipeline::with_capacity(pipeline.cmd_iter().count());
+        compressed_pipeline.atomic();
+        for cmd in pipeline.cmd_iter() {
+            match self.compress_command(cmd) {
+                Some(compressed_cmd) => {
+                    compressed_any = true;
+                    compressed_pipeline.add_command(compressed_cmd);
+                }
+                None => {
+                    compressed_pipeline.add_command(cmd.clone());
+                }
+            }
+        }
+        compressed_any.then_some(compressed_pipeline)
+    }
+}
+
+/// Returns the index of the first value argument of a write command, and the distance between consecutive value arguments.
+fn value_arguments(command: &[u8]) -> Option<(usize, usize)> {
+    match command {
+        b"SET" | b"SETNX" | b"GETSET" => Some((2, usize::MAX)),
+        b"SETEX" | b"PSETEX" => Some((3, usize::MAX)),
+        b"MSET" | b"MSETNX" => Some((2, 2)),
+        b"HSET" | b"HSETNX" | b"HMSET" => Some((3, 2)),
+        _ => None,
+    }
+}
+
+/// Returns whether the response of `command` contains stored values, which may be compressed.
+pub(crate) fn returns_stored_values(command: &[u8]) -> bool {
+    matches!(
+        command,
+        b"GET"
+            | b"GETDEL"
+            | b"GETEX"
+            | b"GETSET"
+            | b"SET"
+            | b"MGET"
+            | b"HGET"
+            | b"HMGET"
+            | b"HVALS"
+            | b"HGETALL"
+    )
+}
+
+fn is_compressed(value: &[u8]) -> bool {
+    value.len() > HEADER_LEN && value.starts_with(&COMPRESSION_MAGIC)
+}
+
+/// Returns the original bytes of a compressed value, or `None` if `value` isn't a valid compressed value,
+/// or if it decompresses to more than [`MAX_DECOMPRESSED_SIZE`] bytes.
+pub fn decompress(value: &[u8]) -> Option<Vec<u8>> {
+    decompress_with_limit(value, MAX_DECOMPRESSED_SIZE)
+}
+
+fn decompress_with_limit(value: &[u8], limit: usize) -> Option<Vec<u8>> {
+    if !is_compressed(value) {
+        return None;
+    }
+    let payload = &value[HEADER_LEN..];
+    match CompressionBackend::from_id(value[COMPRESSION_MAGIC.len()])? {
+        CompressionBackend::Zstd => {
+            // The output is read incrementally, so nothing beyond the limit is ever allocated.
+            let mut decompressed = Vec::new();
+            zstd::stream::Decoder::new(payload)
+                .ok()?
+                .take(limit as u64 + 1)
+                .read_to_end(&mut decompressed)
+                .ok()?;
+            (decompressed.len() <= limit).then_some(decompressed)
+        }
+        CompressionBackend::Lz4 => {
+            // The size is checked before it's allocated.
+            let size = u32::from_le_bytes(payload.get(..4)?.try_into().ok()?) as usize;
+            if size > limit {
+                return None;
+            }
+            lz4_flex::decompress_size_prepended(payload).ok()
+        }
+    }
+}
+
+/// Decompresses the compressed bulk strings in `value`, and in the values of its arrays and maps.
+pub(crate) fn decompress_stored_values(value: Value) -> Value {
+    match value {
+        Value::BulkString(bytes) => match decompress(&bytes) {
+            Some(decompressed) => Value::BulkString(decompressed),
+            None => Value::BulkString(bytes),
+        },
+        Value::Array(values) => {
+            Value::Array(values.into_iter().map(decompress_stored_values).collect())
+        }
+        Value::Map(pairs) => Value::Map(
+            pairs
+                .into_iter()
+                .map(|(field, value)| (field, decompress_stored_values(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(backend: CompressionBackend) -> CompressionConfig {
+        CompressionConfig {
+            enabled: true,
+            backend,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compression_round_trip() {
+        let value = "glide".repeat(100).into_bytes();
+        for backend in [CompressionBackend::Zstd, CompressionBackend::Lz4] {
+            let compressed = config(backend).compress(&value).unwrap();
+            assert!(compressed.starts_with(&COMPRESSION_MAGIC));
+            assert!(compressed.len() < value.len());
+            assert_eq!(decompress(&compressed), Some(value.clone()));
+        }
+    }
+
+    #[test]
+    fn test_small_or_disabled_values_are_not_compressed() {
+        let config = config(CompressionBackend::Zstd);
+        assert_eq!(config.compress(b"short"), None);
+
+        let disabled = CompressionConfig {
+            enabled: false,
+            ..config
+        };
+        assert_eq!(disabled.compress(&[b'a'; 1024]), None);
+    }
+
+    #[test]
+    fn test_uncompressed_values_are_returned_unchanged() {
+        let mut corrupted = COMPRESSION_MAGIC.to_vec();
+        corrupted.extend_from_slice(&[1, 2, 3, 4]);
+        let value = Value::Array(vec![
+            Value::BulkString(b"legacy".to_vec()),
+            Value::BulkString(corrupted.clone()),
+            Value::Nil,
+        ]);
+        assert_eq!(decompress_stored_values(value.clone()), value);
+    }
+
+    #[test]
+    fn test_compress_command_only_compresses_values() {
+        let config = config(CompressionBackend::Lz4);
+        let value = vec![b'v'; 256];
+        let mut cmd = Cmd::new();
+        cmd.arg("HSET")
+            .arg("key")
+            .arg(&value)
+            .arg(&value)
+            .arg("field")
+            .arg("small");
+        let compressed_cmd = config.compress_command(&cmd).unwrap();
+        let args: Vec<_> = compressed_cmd
+            .args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(arg) => arg.to_vec(),
+                redis::Arg::Cursor => unreachable!(),
+            })
+            .collect();
+        assert_eq!(args[2], value);
+        assert_eq!(decompress(&args[3]), Some(value));
+        assert_eq!(args[4], b"field");
+        assert_eq!(args[5], b"small");
+
+        let mut cmd = Cmd::new();
+        cmd.arg("APPEND").arg("key").arg(vec![b'v'; 256]);
+        assert!(config.compress_command(&cmd).is_none());
+    }
+
+    #[test]
+    fn test_values_decompressing_beyond_the_limit_are_not_decompressed() {
+        let value = vec![b'v'; 1024];
+        for backend in [CompressionBackend::Zstd, CompressionBackend::Lz4] {
+            let compressed = config(backend).compress(&value).unwrap();
+            assert_eq!(
+                decompress_with_limit(&compressed, 1024),
+                Some(value.clone())
+            );
+            assert_eq!(decompress_with_limit(&compressed, 1023), None);
+        }
+
+        // An LZ4 value that claims a huge size is rejected before the size is allocated.
+        let mut claimed_huge = COMPRESSION_MAGIC.to_vec();
+        claimed_huge.push(CompressionBackend::Lz4.id());
+        claimed_huge.extend_from_slice(&u32::MAX.to_le_bytes());
+        claimed_huge.extend_from_slice(&[0; 8]);
+        assert_eq!(decompress(&claimed_huge), None);
+    }
+
+    #[test]
+    fn test_compress_transaction() {
+        let config = config(CompressionBackend::Zstd);
+        let value = "glide".repeat(100).into_bytes();
+        let mut pipeline = Pipeline::new();
+        pipeline.atomic();
+        pipeline.cmd("SET").arg("key").arg(&value);
+        pipeline.cmd("GET").arg("key");
+
+        let compressed = config.compress_transaction(&pipeline).unwrap();
+        let packed = compressed.get_packed_pipeline();
+        assert!(packed.windows(5).any(|window| window == b"MULTI"));
+        let cmds: Vec<_> = compressed.cmd_iter().collect();
+        assert_eq!(cmds.len(), 2);
+        let set_value = match cmds[0].args_iter().nth(2) {
+            Some(redis::Arg::Simple(arg)) => arg.to_vec(),
+            _ => unreachable!(),
+        };
+        assert_eq!(decompress(&set_value), Some(value));
+        assert_eq!(
+            cmds[1].get_packed_command(),
+            redis::cmd("GET").arg("key").get_packed_command()
+        );
+
+        let mut pipeline = Pipeline::new();
+        pipeline.atomic();
+        pipeline.cmd("GET").arg("key");
+        assert!(config.compress_transaction(&pipeline).is_none());
+    }
+}
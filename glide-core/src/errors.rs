@@ -1,6 +1,6 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
-use redis::RedisError;
+use redis::{ErrorKind, RedisError};
 
 #[repr(C)]
 pub enum RequestErrorType {
@@ -8,20 +8,126 @@ pub enum RequestErrorType {
     ExecAbort = 1,
     Timeout = 2,
     Disconnect = 3,
+    Cancelled = 4,
+}
+
+/// A machine-readable kind of a request error, mirroring `redis::ErrorKind`.
+///
+/// The numeric values are shared with the wrappers through `response.proto` and the FFI headers,
+/// so existing values must never be changed or reused. New kinds are appended at the end.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestErrorKind {
+    Unknown = 0,
+    ResponseError = 1,
+    ParseError = 2,
+    AuthenticationFailed = 3,
+    TypeError = 4,
+    ExecAbortError = 5,
+    BusyLoadingError = 6,
+    NoScriptError = 7,
+    InvalidClientConfig = 8,
+    Moved = 9,
+    Ask = 10,
+    TryAgain = 11,
+    ClusterDown = 12,
+    CrossSlot = 13,
+    MasterDown = 14,
+    IoError = 15,
+    FatalSendError = 16,
+    FatalReceiveError = 17,
+    ClientError = 18,
+    ExtensionError = 19,
+    ReadOnly = 20,
+    MasterNameNotFoundBySentinel = 21,
+    NoValidReplicasFoundBySentinel = 22,
+    EmptySentinelList = 23,
+    NotBusy = 24,
+    AllConnectionsUnavailable = 25,
+    ConnectionNotFoundForRoute = 26,
+    Resp3NotSupported = 27,
+    NotAllSlotsCovered = 28,
+    UserOperationError = 29,
+    RequestCancelled = 30,
+    /// The request was rejected by glide-core because the inflight requests limit was reached.
+    InflightRequestsLimit = 31,
+}
+
+impl From<ErrorKind> for RequestErrorKind {
+    fn from(kind: ErrorKind) -> Self {
+        #[allow(unreachable_patterns)]
+        match kind {
+            ErrorKind::ResponseError => RequestErrorKind::ResponseError,
+            ErrorKind::ParseError => RequestErrorKind::ParseError,
+            ErrorKind::AuthenticationFailed => RequestErrorKind::AuthenticationFailed,
+            ErrorKind::TypeError => RequestErrorKind::TypeError,
+            ErrorKind::ExecAbortError => RequestErrorKind::ExecAbortError,
+            ErrorKind::BusyLoadingError => RequestErrorKind::BusyLoadingError,
+            ErrorKind::NoScriptError => RequestErrorKind::NoScriptError,
+            ErrorKind::InvalidClientConfig => RequestErrorKind::InvalidClientConfig,
+            ErrorKind::Moved => RequestErrorKind::Moved,
+            ErrorKind::Ask => RequestErrorKind::Ask,
+            ErrorKind::TryAgain => RequestErrorKind::TryAgain,
+            ErrorKind::ClusterDown => RequestErrorKind::ClusterDown,
+            ErrorKind::CrossSlot => RequestErrorKind::CrossSlot,
+            ErrorKind::MasterDown => RequestErrorKind::MasterDown,
+            ErrorKind::IoError => RequestErrorKind::IoError,
+            ErrorKind::FatalSendError => RequestErrorKind::FatalSendError,
+            ErrorKind::FatalReceiveError => RequestErrorKind::FatalReceiveError,
+            ErrorKind::ClientError => RequestErrorKind::ClientError,
+            ErrorKind::ExtensionError => RequestErrorKind::ExtensionError,
+            ErrorKind::ReadOnly => RequestErrorKind::ReadOnly,
+            ErrorKind::MasterNameNotFoundBySentinel => {
+                RequestErrorKind::MasterNameNotFoundBySentinel
+            }
+            ErrorKind::NoValidReplicasFoundBySentinel => {
+                RequestErrorKind::NoValidReplicasFoundBySentinel
+            }
+            ErrorKind::EmptySentinelList => RequestErrorKind::EmptySentinelList,
+            ErrorKind::NotBusy => RequestErrorKind::NotBusy,
+            ErrorKind::AllConnectionsUnavailable => RequestErrorKind::AllConnectionsUnavailable,
+            ErrorKind::ConnectionNotFoundForRoute => RequestErrorKind::ConnectionNotFoundForRoute,
+            ErrorKind::RESP3NotSupported => RequestErrorKind::Resp3NotSupported,
+            ErrorKind::NotAllSlotsCovered => RequestErrorKind::NotAllSlotsCovered,
+            ErrorKind::UserOperationError => RequestErrorKind::UserOperationError,
+            ErrorKind::RequestCancelled => RequestErrorKind::RequestCancelled,
+            // Kinds that only exist with optional redis-rs features.
+            _ => RequestErrorKind::Unknown,
+        }
+    }
 }
 
 pub fn error_type(error: &RedisError) -> RequestErrorType {
-    if error.is_timeout() {
+    if error.kind() == ErrorKind::RequestCancelled {
+        RequestErrorType::Cancelled
+    } else if error.is_timeout() {
         RequestErrorType::Timeout
     } else if error.is_unrecoverable_error() {
         RequestErrorType::Disconnect
-    } else if matches!(error.kind(), redis::ErrorKind::ExecAbortError) {
+    } else if matches!(error.kind(), ErrorKind::ExecAbortError) {
         RequestErrorType::ExecAbort
     } else {
         RequestErrorType::Unspecified
     }
 }
 
+pub fn error_kind(error: &RedisError) -> RequestErrorKind {
+    error.kind().into()
+}
+
+/// Returns the error code prefix sent by the server, e.g. `MOVED` or `WRONGTYPE`.
+pub fn server_error_code(error: &RedisError) -> Option<&str> {
+    error.code()
+}
+
+/// Returns the address of the node that a `MOVED` or `ASK` error redirects to.
+///
+/// Other errors, including connection errors, IO errors and timeouts, don't carry the address of the node
+/// the request was routed to, so no address is returned for them.
+pub fn error_address(error: &RedisError) -> Option<&str> {
+    error.redirect_node().map(|(address, _slot)| address)
+}
+
 pub fn error_message(error: &RedisError) -> String {
     let error_message = error.to_string();
     if matches!(error_type(error), RequestErrorType::Disconnect) {
@@ -30,3 +136,87 @@ pub fn error_message(error: &RedisError) -> String {
         error_message
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The mapping is part of the wrappers' API. A failure here means a value was changed, which breaks existing wrappers.
+    const ERROR_KIND_TABLE: &[(ErrorKind, u32)] = &[
+        (ErrorKind::ResponseError, 1),
+        (ErrorKind::ParseError, 2),
+        (ErrorKind::AuthenticationFailed, 3),
+        (ErrorKind::TypeError, 4),
+        (ErrorKind::ExecAbortError, 5),
+        (ErrorKind::BusyLoadingError, 6),
+        (ErrorKind::NoScriptError, 7),
+        (ErrorKind::InvalidClientConfig, 8),
+        (ErrorKind::Moved, 9),
+        (ErrorKind::Ask, 10),
+        (ErrorKind::TryAgain, 11),
+        (ErrorKind::ClusterDown, 12),
+        (ErrorKind::CrossSlot, 13),
+        (ErrorKind::MasterDown, 14),
+        (ErrorKind::IoError, 15),
+        (ErrorKind::FatalSendError, 16),
+        (ErrorKind::FatalReceiveError, 17),
+        (ErrorKind::ClientError, 18),
+        (ErrorKind::ExtensionError, 19),
+        (ErrorKind::ReadOnly, 20),
+        (ErrorKind::MasterNameNotFoundBySentinel, 21),
+        (ErrorKind::NoValidReplicasFoundBySentinel, 22),
+        (ErrorKind::EmptySentinelList, 23),
+        (ErrorKind::NotBusy, 24),
+        (ErrorKind::AllConnectionsUnavailable, 25),
+        (ErrorKind::ConnectionNotFoundForRoute, 26),
+        (ErrorKind::RESP3NotSupported, 27),
+        (ErrorKind::NotAllSlotsCovered, 28),
+        (ErrorKind::UserOperationError, 29),
+        (ErrorKind::RequestCancelled, 30),
+    ];
+
+    #[test]
+    fn test_error_kind_mapping_is_stable() {
+        for (kind, code) in ERROR_KIND_TABLE {
+            assert_eq!(
+                RequestErrorKind::from(*kind) as u32,
+                *code,
+                "unexpected code for {kind:?}"
+            );
+        }
+        assert_eq!(RequestErrorKind::InflightRequestsLimit as u32, 31);
+    }
+
+    #[cfg(feature = "socket-layer")]
+    #[test]
+    fn test_error_kind_mapping_matches_protobuf() {
+        use crate::response::ErrorKind as ProtobufErrorKind;
+        use protobuf::Enum;
+
+        for (kind, code) in ERROR_KIND_TABLE {
+            let protobuf_kind = ProtobufErrorKind::from_i32(*code as i32)
+                .unwrap_or_else(|| panic!("missing protobuf error kind for {kind:?}"));
+            assert_eq!(protobuf_kind.value(), RequestErrorKind::from(*kind) as i32);
+        }
+        assert_eq!(
+            ProtobufErrorKind::InflightRequestsLimit.value(),
+            RequestErrorKind::InflightRequestsLimit as i32
+        );
+    }
+
+    #[test]
+    fn test_server_error_details() {
+        let moved = redis::parse_redis_value(b"-MOVED 3999 127.0.0.1:6381\r\n").unwrap_err();
+        assert_eq!(error_kind(&moved), RequestErrorKind::Moved);
+        assert_eq!(server_error_code(&moved), Some("MOVED"));
+        assert_eq!(error_address(&moved), Some("127.0.0.1:6381"));
+
+        let wrong_type = redis::parse_redis_value(
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+        )
+        .unwrap_err();
+        assert_eq!(error_kind(&wrong_type), RequestErrorKind::ExtensionError);
+        assert_eq!(server_error_code(&wrong_type), Some("WRONGTYPE"));
+        assert_eq!(error_address(&wrong_type), None);
+    }
+}
@@ -3,10 +3,13 @@
 #[cfg(feature = "socket-layer")]
 include!(concat!(env!("OUT_DIR"), "/protobuf/mod.rs"));
 pub mod client;
+pub mod compression;
 mod retry_strategies;
 #[cfg(feature = "socket-layer")]
 pub mod rotating_buffer;
 #[cfg(feature = "socket-layer")]
+pub mod routes;
+#[cfg(feature = "socket-layer")]
 mod socket_listener;
 #[cfg(feature = "socket-layer")]
 pub use socket_listener::*;
@@ -15,4 +18,5 @@ pub mod scripts_container;
 pub use client::ConnectionRequest;
 pub mod cluster_scan_container;
 pub mod request_type;
+pub mod runtime_registry;
 pub use telemetrylib::Telemetry;
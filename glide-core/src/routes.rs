@@ -0,0 +1,156 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Conversion of the protobuf `Routes` message to the routing info of redis-rs,
+//! shared by the socket listener and the FFI based wrappers.
+
+use crate::command_request::{routes, Routes, SimpleRoutes, SlotTypes};
+use logger_core::log_warn;
+use redis::cluster_routing::{
+    MultipleNodeRoutingInfo, ResponsePolicy, Routable, Route, RoutingInfo, SingleNodeRoutingInfo,
+    SlotAddr,
+};
+use redis::{Cmd, ErrorKind, RedisError};
+use thiserror::Error;
+
+/// A route with values that don't match the protobuf definition.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("{0}")]
+pub struct InvalidRoute(String);
+
+impl From<InvalidRoute> for RedisError {
+    fn from(err: InvalidRoute) -> Self {
+        (ErrorKind::ClientError, "Received invalid route", err.0).into()
+    }
+}
+
+fn get_slot_addr(slot_type: &protobuf::EnumOrUnknown<SlotTypes>) -> Result<SlotAddr, InvalidRoute> {
+    slot_type
+        .enum_value()
+        .map(|slot_type| match slot_type {
+            SlotTypes::Primary => SlotAddr::Master,
+            SlotTypes::Replica => SlotAddr::ReplicaRequired,
+        })
+        .map_err(|id| InvalidRoute(format!("Received unexpected slot id type {id}")))
+}
+
+/// Returns the routing info of `route`, or `None` if the request should be routed by its keys.
+/// `cmd` is used to find the response policy of multi-node routes, and is `None` for transactions and scripts.
+pub fn get_route(
+    route: Option<Routes>,
+    cmd: Option<&Cmd>,
+) -> Result<Option<RoutingInfo>, InvalidRoute> {
+    let Some(route) = route.and_then(|route| route.value) else {
+        return Ok(None);
+    };
+    let get_response_policy = |cmd: Option<&Cmd>| {
+        cmd.and_then(|cmd| {
+            cmd.command()
+                .and_then(|cmd| ResponsePolicy::for_command(&cmd))
+        })
+    };
+    match route {
+        routes::Value::SimpleRoutes(simple_route) => {
+            let simple_route = simple_route.enum_value().map_err(|id| {
+                InvalidRoute(format!("Received unexpected simple route type {id}"))
+            })?;
+            match simple_route {
+                SimpleRoutes::AllNodes => Ok(Some(RoutingInfo::MultiNode((
+                    MultipleNodeRoutingInfo::AllNodes,
+                    get_response_policy(cmd),
+                )))),
+                SimpleRoutes::AllPrimaries => Ok(Some(RoutingInfo::MultiNode((
+                    MultipleNodeRoutingInfo::AllMasters,
+                    get_response_policy(cmd),
+                )))),
+                SimpleRoutes::Random => {
+                    Ok(Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)))
+                }
+            }
+        }
+        routes::Value::SlotKeyRoute(slot_key_route) => Ok(Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(Route::new(
+                redis::cluster_topology::get_slot(slot_key_route.slot_key.as_bytes()),
+                get_slot_addr(&slot_key_route.slot_type)?,
+            )),
+        ))),
+        routes::Value::SlotIdRoute(slot_id_route) => Ok(Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(Route::new(
+                slot_id_route.slot_id as u16,
+                get_slot_addr(&slot_id_route.slot_type)?,
+            )),
+        ))),
+        routes::Value::ByAddressRoute(by_address_route) => {
+            match u16::try_from(by_address_route.port) {
+                Ok(port) => Ok(Some(RoutingInfo::SingleNode(
+                    SingleNodeRoutingInfo::ByAddress {
+                        host: by_address_route.host.to_string(),
+                        port,
+                    },
+                ))),
+                Err(err) => {
+                    log_warn("get route", format!("Failed to parse port: {err:?}"));
+                    Ok(None)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_request::{ByAddressRoute, SlotIdRoute, SlotKeyRoute};
+
+    #[test]
+    fn test_get_route() {
+        assert_eq!(get_route(None, None), Ok(None));
+
+        let mut route = Routes::new();
+        route.set_simple_routes(SimpleRoutes::AllPrimaries);
+        let mut cmd = Cmd::new();
+        cmd.arg("DBSIZE");
+        assert_eq!(
+            get_route(Some(route), Some(&cmd)),
+            Ok(Some(RoutingInfo::MultiNode((
+                MultipleNodeRoutingInfo::AllMasters,
+                Some(ResponsePolicy::Aggregate(
+                    redis::cluster_routing::AggregateOp::Sum
+                ))
+            ))))
+        );
+
+        let mut route = Routes::new();
+        let mut slot_key_route = SlotKeyRoute::new();
+        slot_key_route.slot_key = "foo".into();
+        slot_key_route.slot_type = SlotTypes::Replica.into();
+        route.set_slot_key_route(slot_key_route);
+        assert_eq!(
+            get_route(Some(route), None),
+            Ok(Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(Route::new(12182, SlotAddr::ReplicaRequired))
+            )))
+        );
+
+        let mut route = Routes::new();
+        let mut slot_id_route = SlotIdRoute::new();
+        slot_id_route.slot_id = 100;
+        slot_id_route.slot_type = protobuf::EnumOrUnknown::from_i32(7);
+        route.set_slot_id_route(slot_id_route);
+        assert!(get_route(Some(route), None).is_err());
+
+        let mut route = Routes::new();
+        let mut by_address_route = ByAddressRoute::new();
+        by_address_route.host = "localhost".into();
+        by_address_route.port = 6379;
+        route.set_by_address_route(by_address_route);
+        assert_eq!(
+            get_route(Some(route), None),
+            Ok(Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::ByAddress {
+                    host: "localhost".to_string(),
+                    port: 6379
+                }
+            )))
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Process-wide Tokio runtimes, shared by the clients of the FFI wrappers.
+//!
+//! Runtimes are keyed by their thread name, so all the clients of a wrapper share a single runtime, instead of
+//! creating one runtime per client. A runtime is created when the first client acquires it, and is shut down
+//! once the last [`SharedRuntime`] holding it is dropped.
+
+use logger_core::{log_info, log_warn};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::runtime::{Builder, Handle, Runtime};
+
+/// The configuration of a shared runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    /// The name of the runtime's threads, which also identifies the runtime in the registry.
+    pub thread_name: String,
+    /// The number of worker threads. Defaults to the number of CPUs.
+    pub worker_threads: Option<usize>,
+}
+
+struct RegisteredRuntime {
+    runtime: Arc<Runtime>,
+    worker_threads: Option<usize>,
+    clients: usize,
+}
+
+static RUNTIMES: Lazy<Mutex<HashMap<String, RegisteredRuntime>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A reference to a shared runtime. The runtime is shut down once its last reference is dropped.
+pub struct SharedRuntime {
+    runtime: Option<Arc<Runtime>>,
+    thread_name: String,
+}
+
+impl SharedRuntime {
+    /// Returns the runtime named `config.thread_name`, creating it if it isn't running.
+    ///
+    /// If the runtime is already running, it is returned as is, even if it was created with a different
+    /// number of worker threads.
+    pub fn acquire(config: &RuntimeConfig) -> std::io::Result<SharedRuntime> {
+        let mut runtimes = RUNTIMES.lock().unwrap();
+        if let Some(registered) = runtimes.get_mut(&config.thread_name) {
+            if registered.worker_threads != config.worker_threads {
+                log_warn(
+                    "runtime registry",
+                    format!(
+                        "Runtime `{}` is already running with {:?} worker threads, ignoring the requested {:?}",
+                        config.thread_name, registered.worker_threads, config.worker_threads
+                    ),
+                );
+            }
+            registered.clients += 1;
+            return Ok(SharedRuntime {
+                runtime: Some(registered.runtime.clone()),
+                thread_name: config.thread_name.clone(),
+            });
+        }
+
+        let mut builder = Builder::new_multi_thread();
+        builder.enable_all().thread_name(config.thread_name.clone());
+        if let Some(worker_threads) = config.worker_threads {
+            builder.worker_threads(worker_threads);
+        }
+        let runtime = Arc::new(builder.build()?);
+        log_info(
+            "runtime registry",
+            format!("Started runtime `{}`", config.thread_name),
+        );
+        runtimes.insert(
+            config.thread_name.clone(),
+            RegisteredRuntime {
+                runtime: runtime.clone(),
+                worker_threads: config.worker_threads,
+                clients: 1,
+            },
+        );
+        Ok(SharedRuntime {
+            runtime: Some(runtime),
+            thread_name: config.thread_name.clone(),
+        })
+    }
+
+    pub fn handle(&self) -> &Handle {
+        self.runtime().handle()
+    }
+
+    pub fn spawn<F>(&self, future: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.runtime().spawn(future)
+    }
+
+    /// Runs `future` to completion on the calling thread. See [`Runtime::block_on`].
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime().block_on(future)
+    }
+
+    fn runtime(&self) -> &Runtime {
+        self.runtime
+            .as_ref()
+            .expect("The runtime is only taken when dropped")
+    }
+}
+
+impl Drop for SharedRuntime {
+    fn drop(&mut self) {
+        let Some(runtime) = self.runtime.take() else {
+            return;
+        };
+        {
+            let mut runtimes = RUNTIMES.lock().unwrap();
+            let Some(registered) = runtimes.get_mut(&self.thread_name) else {
+                return;
+            };
+            registered.clients -= 1;
+            if registered.clients > 0 {
+                return;
+            }
+            runtimes.remove(&self.thread_name);
+        }
+
+        // This was the last reference, so the runtime is shut down outside of the lock, which lets
+        // other clients start a new runtime with the same name in the meantime.
+        let Ok(runtime) = Arc::try_unwrap(runtime) else {
+            return;
+        };
+        log_info(
+            "runtime registry",
+            format!("Shutting down runtime `{}`", self.thread_name),
+        );
+        if Handle::try_current().is_ok() {
+            // Blocking on the worker threads from an async context would deadlock if this is one of
+            // them, so they are left to finish in the background.
+            runtime.shutdown_background();
+        } else {
+            drop(runtime);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(thread_name: &str) -> RuntimeConfig {
+        RuntimeConfig {
+            thread_name: thread_name.to_string(),
+            worker_threads: Some(1),
+        }
+    }
+
+    fn is_registered(thread_name: &str) -> bool {
+        RUNTIMES.lock().unwrap().contains_key(thread_name)
+    }
+
+    fn thread_name(runtime: &SharedRuntime) -> Option<String> {
+        runtime
+            .block_on(runtime.spawn(async { std::thread::current().name().map(str::to_string) }))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_clients_share_runtime_until_last_one_is_dropped() {
+        let first = SharedRuntime::acquire(&config("shared runtime test")).unwrap();
+        let second = SharedRuntime::acquire(&config("shared runtime test")).unwrap();
+        assert!(Arc::ptr_eq(
+            first.runtime.as_ref().unwrap(),
+            second.runtime.as_ref().unwrap()
+        ));
+        assert_eq!(thread_name(&second).as_deref(), Some("shared runtime test"));
+
+        drop(first);
+        assert!(is_registered("shared runtime test"));
+        assert_eq!(thread_name(&second).as_deref(), Some("shared runtime test"));
+
+        drop(second);
+        assert!(!is_registered("shared runtime test"));
+    }
+
+    #[test]
+    fn test_runtimes_are_separated_by_name() {
+        let first = SharedRuntime::acquire(&config("first runtime test")).unwrap();
+        let second = SharedRuntime::acquire(&config("second runtime test")).unwrap();
+        assert!(!Arc::ptr_eq(
+            first.runtime.as_ref().unwrap(),
+            second.runtime.as_ref().unwrap()
+        ));
+        assert_eq!(thread_name(&first).as_deref(), Some("first runtime test"));
+        assert_eq!(thread_name(&second).as_deref(), Some("second runtime test"));
+    }
+
+    #[test]
+    fn test_runtime_restarts_after_shutdown() {
+        drop(SharedRuntime::acquire(&config("restarted runtime test")).unwrap());
+        assert!(!is_registered("restarted runtime test"));
+
+        let runtime = SharedRuntime::acquire(&config("restarted runtime test")).unwrap();
+        assert!(is_registered("restarted runtime test"));
+        assert_eq!(
+            thread_name(&runtime).as_deref(),
+            Some("restarted runtime test")
+        );
+    }
+}
@@ -1,34 +1,38 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
 use super::rotating_buffer::RotatingBuffer;
-use crate::client::Client;
+use crate::client::{AdmissionError, Client, RequestKind};
 use crate::cluster_scan_container::get_cluster_scan_cursor;
 use crate::command_request::{
-    command, command_request, ClusterScan, Command, CommandRequest, Routes, SlotTypes, Transaction,
+    command, command_request, ClusterScan, Command, CommandRequest, Routes, Transaction,
+};
+use crate::connection_request::{ConnectionRequest, SocketAuthentication};
+use crate::errors::{
+    error_address, error_kind, error_message, error_type, server_error_code, RequestErrorType,
 };
-use crate::connection_request::ConnectionRequest;
-use crate::errors::{error_message, error_type, RequestErrorType};
 use crate::response;
 use crate::response::Response;
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use directories::BaseDirs;
 use logger_core::{log_debug, log_error, log_info, log_trace, log_warn};
 use once_cell::sync::Lazy;
-use protobuf::{Chars, Message};
-use redis::cluster_routing::{
-    MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
-};
-use redis::cluster_routing::{ResponsePolicy, Routable};
+use protobuf::{Chars, Enum, Message};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use redis::cluster_routing::RoutingInfo;
 use redis::{ClusterScanArgs, Cmd, PushInfo, RedisError, ScanStateRC, Value};
-use std::cell::Cell;
-use std::collections::HashSet;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::ptr::from_mut;
 use std::rc::Rc;
 use std::sync::RwLock;
+use std::time::Duration;
 use std::{env, str};
 use std::{io, thread};
 use thiserror::Error;
-use tokio::net::{UnixListener, UnixStream};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
 use tokio::runtime::Builder;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::{channel, Sender};
@@ -52,15 +56,199 @@ pub const ZSET: &str = "zset";
 pub const HASH: &str = "hash";
 pub const STREAM: &str = "stream";
 
-/// struct containing all objects needed to read from a unix stream.
-struct UnixStreamListener {
-    read_socket: Rc<UnixStream>,
+/// How long a connection to the TCP listener has to authenticate before it's closed.
+pub const SOCKET_AUTHENTICATION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The token that connections to the TCP listener authenticate with. It's generated once per process.
+static AUTH_TOKEN: Lazy<String> = Lazy::new(|| {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+});
+
+/// The transport that the socket listener accepts connections on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketTransport {
+    /// A Unix domain socket, at [`get_socket_path`].
+    UnixSocket,
+    /// A TCP socket on 127.0.0.1, with a random port. See [`ListenerAddress::TcpLoopback`].
+    TcpLoopback,
+}
+
+/// The address of a running socket listener, as reported to its init callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenerAddress {
+    /// The path of the Unix domain socket.
+    UnixSocket(String),
+    /// The address of the TCP socket, and the token that connections must authenticate with.
+    /// The first request on each connection must be a `SocketAuthentication` with this token, which is answered with `OK`.
+    /// Only then can the `ConnectionRequest` be sent. Connections that fail to authenticate are closed with a closing error.
+    TcpLoopback {
+        address: SocketAddr,
+        auth_token: String,
+    },
+}
+
+impl fmt::Display for ListenerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenerAddress::UnixSocket(socket_path) => write!(f, "{socket_path}"),
+            ListenerAddress::TcpLoopback { address, .. } => write!(f, "{address}"),
+        }
+    }
+}
+
+/// Identifies a socket listener in the process. There's at most one TCP listener, and one listener per socket path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ListenerId {
+    UnixSocket(String),
+    TcpLoopback,
+}
+
+enum SocketListener {
+    Unix(UnixListener),
+    Tcp(TcpListener),
+}
+
+impl SocketListener {
+    async fn bind(id: &ListenerId) -> io::Result<(Self, ListenerAddress)> {
+        match id {
+            ListenerId::UnixSocket(socket_path) => Ok((
+                SocketListener::Unix(UnixListener::bind(socket_path)?),
+                ListenerAddress::UnixSocket(socket_path.clone()),
+            )),
+            ListenerId::TcpLoopback => {
+                let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+                let address = listener.local_addr()?;
+                Ok((
+                    SocketListener::Tcp(listener),
+                    ListenerAddress::TcpLoopback {
+                        address,
+                        auth_token: AUTH_TOKEN.clone(),
+                    },
+                ))
+            }
+        }
+    }
+
+    async fn accept(&self) -> io::Result<ClientStream> {
+        match self {
+            SocketListener::Unix(listener) => {
+                let (stream, _addr) = listener.accept().await?;
+                Ok(ClientStream::Unix(stream))
+            }
+            SocketListener::Tcp(listener) => {
+                let (stream, _addr) = listener.accept().await?;
+                if let Err(err) = stream.set_nodelay(true) {
+                    log_warn(
+                        "listen_on_socket",
+                        format!("Failed to set TCP_NODELAY on connection: {err}"),
+                    );
+                }
+                Ok(ClientStream::Tcp(stream))
+            }
+        }
+    }
+
+    /// The token that connections must authenticate with, if any.
+    fn auth_token(&self) -> Option<&'static str> {
+        match self {
+            SocketListener::Unix(_) => None,
+            SocketListener::Tcp(_) => Some(AUTH_TOKEN.as_str()),
+        }
+    }
+}
+
+/// A connection accepted by the socket listener.
+enum ClientStream {
+    Unix(UnixStream),
+    Tcp(TcpStream),
+}
+
+impl ClientStream {
+    async fn readable(&self) -> io::Result<()> {
+        match self {
+            ClientStream::Unix(stream) => stream.readable().await,
+            ClientStream::Tcp(stream) => stream.readable().await,
+        }
+    }
+
+    fn try_read_buf(&self, buf: &mut BytesMut) -> io::Result<usize> {
+        match self {
+            ClientStream::Unix(stream) => stream.try_read_buf(buf),
+            ClientStream::Tcp(stream) => stream.try_read_buf(buf),
+        }
+    }
+
+    async fn writable(&self) -> io::Result<()> {
+        match self {
+            ClientStream::Unix(stream) => stream.writable().await,
+            ClientStream::Tcp(stream) => stream.writable().await,
+        }
+    }
+
+    fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            ClientStream::Unix(stream) => stream.try_write(buf),
+            ClientStream::Tcp(stream) => stream.try_write(buf),
+        }
+    }
+}
+
+/// The clients hosted by a socket connection. The client created by the connection's `ConnectionRequest` has the id 0,
+/// and the clients created by `CreateClient` requests get increasing ids.
+struct SocketClients {
+    clients: RefCell<HashMap<u32, Client>>,
+    next_client_id: Cell<u32>,
+}
+
+impl SocketClients {
+    fn new(client: Client) -> Self {
+        Self {
+            clients: RefCell::new(HashMap::from([(0, client)])),
+            next_client_id: Cell::new(1),
+        }
+    }
+
+    fn get(&self, client_id: u32) -> Option<Client> {
+        self.clients.borrow().get(&client_id).cloned()
+    }
+
+    /// Adds a client to the connection, and returns its id, or `None` if all the ids are in use.
+    ///
+    /// Ids are reused once they wrap around, skipping the ids of the clients that are still open.
+    /// Id 0 belongs to the connection's first client, and is never reused.
+    fn add(&self, client: Client) -> Option<u32> {
+        let mut clients = self.clients.borrow_mut();
+        let added_clients = clients.len() - usize::from(clients.contains_key(&0));
+        if added_clients >= u32::MAX as usize {
+            return None;
+        }
+        let mut client_id = self.next_client_id.get();
+        while client_id == 0 || clients.contains_key(&client_id) {
+            client_id = client_id.wrapping_add(1);
+        }
+        self.next_client_id.set(client_id.wrapping_add(1));
+        clients.insert(client_id, client);
+        Some(client_id)
+    }
+
+    fn remove(&self, client_id: u32) {
+        self.clients.borrow_mut().remove(&client_id);
+    }
+}
+
+/// struct containing all objects needed to read from a client stream.
+struct ClientStreamListener {
+    read_socket: Rc<ClientStream>,
     rotating_buffer: RotatingBuffer,
 }
 
 /// struct containing all objects needed to write to a socket.
 struct Writer {
-    socket: Rc<UnixStream>,
+    socket: Rc<ClientStream>,
     lock: Mutex<()>,
     accumulated_outputs: Cell<Vec<u8>>,
     closing_sender: Sender<ClosingReason>,
@@ -77,8 +265,8 @@ impl<T: Message> From<ClosingReason> for PipeListeningResult<T> {
     }
 }
 
-impl UnixStreamListener {
-    fn new(read_socket: Rc<UnixStream>) -> Self {
+impl ClientStreamListener {
+    fn new(read_socket: Rc<ClientStream>) -> Self {
         // if the logger has been initialized by the user (external or internal) on info level this log will be shown
         log_debug("connection", "new socket listener initiated");
         let rotating_buffer = RotatingBuffer::new(65_536);
@@ -175,14 +363,79 @@ async fn write_closing_error(
     write_to_writer(response, writer).await
 }
 
-/// Create response and write it to the writer
+/// Returns the size of the payload of `value`, if it's simple enough to be encoded inline.
+fn inline_payload_size(value: &Value) -> Option<usize> {
+    match value {
+        Value::Int(_) => Some(std::mem::size_of::<i64>()),
+        Value::Array(values) => values
+            .iter()
+            .try_fold(0, |size, value| Some(size + inline_string_size(value)?)),
+        value => inline_string_size(value),
+    }
+}
+
+fn inline_string_size(value: &Value) -> Option<usize> {
+    match value {
+        Value::BulkString(bytes) => Some(bytes.len()),
+        Value::SimpleString(string) => Some(string.len()),
+        Value::VerbatimString { text, .. } => Some(text.len()),
+        Value::Okay => Some("OK".len()),
+        _ => None,
+    }
+}
+
+fn into_inline_string(value: Value) -> Option<(Bytes, response::StringKind)> {
+    match value {
+        Value::BulkString(bytes) => Some((bytes.into(), response::StringKind::BulkString)),
+        Value::SimpleString(string) => Some((string.into(), response::StringKind::SimpleString)),
+        Value::VerbatimString { text, .. } => {
+            Some((text.into(), response::StringKind::VerbatimString))
+        }
+        Value::Okay => Some((Bytes::from_static(b"OK"), response::StringKind::Okay)),
+        _ => None,
+    }
+}
+
+fn inline_value(value: Value, max_size: u32) -> Result<response::InlineValue, Value> {
+    let fits = max_size > 0
+        && matches!(inline_payload_size(&value), Some(size) if size <= max_size as usize);
+    if !fits {
+        return Err(value);
+    }
+    let mut inline_value = response::InlineValue::new();
+    inline_value.value = Some(match value {
+        Value::Int(int) => response::inline_value::Value::IntValue(int),
+        Value::Array(values) => {
+            let mut array = response::StringArray::new();
+            for (bytes, kind) in values.into_iter().filter_map(into_inline_string) {
+                array.values.push(bytes);
+                array.kinds.push(kind.into());
+            }
+            response::inline_value::Value::StringArray(array)
+        }
+        value => match into_inline_string(value) {
+            Some((bytes, kind)) => {
+                inline_value.string_kind = kind.into();
+                response::inline_value::Value::StringValue(bytes)
+            }
+            None => unreachable!("Only strings are left after the payload check"),
+        },
+    });
+    Ok(inline_value)
+}
+
+/// Create response and write it to the writer.
+/// Simple values whose payload is up to `inline_response_max_size` bytes are encoded in the response, instead of by pointer.
 async fn write_result(
     resp_result: ClientUsageResult<Value>,
     callback_index: u32,
+    client_id: u32,
+    inline_response_max_size: u32,
     writer: &Rc<Writer>,
 ) -> Result<(), io::Error> {
     let mut response = Response::new();
     response.callback_idx = callback_index;
+    response.client_id = client_id;
     response.is_push = false;
     response.value = match resp_result {
         Ok(Value::Okay) => Some(response::response::Value::ConstantResponse(
@@ -191,10 +444,15 @@ async fn write_result(
         Ok(value) => {
             if value != Value::Nil {
                 // Since null values don't require any additional data, they can be sent without any extra effort.
-                // Move the value to the heap and leak it. The wrapper should use `Box::from_raw` to recreate the box, use the value, and drop the allocation.
-                let reference = Box::leak(Box::new(value));
-                let raw_pointer = from_mut(reference);
-                Some(response::response::Value::RespPointer(raw_pointer as u64))
+                match inline_value(value, inline_response_max_size) {
+                    Ok(inline_value) => Some(response::response::Value::InlineValue(inline_value)),
+                    Err(value) => {
+                        // Move the value to the heap and leak it. The wrapper should use `Box::from_raw` to recreate the box, use the value, and drop the allocation.
+                        let reference = Box::leak(Box::new(value));
+                        let raw_pointer = from_mut(reference);
+                        Some(response::response::Value::RespPointer(raw_pointer as u64))
+                    }
+                }
             } else {
                 None
             }
@@ -214,6 +472,26 @@ async fn write_result(
             };
             Some(response::response::Value::RequestError(request_error))
         }
+        Err(ClientUsageError::Connection(err)) => {
+            let error_message = format!("Failed to create client: {err:?}");
+            log_warn("client creation", &error_message);
+            let request_error = response::RequestError {
+                type_: response::RequestErrorType::Disconnect.into(),
+                message: error_message.into(),
+                ..Default::default()
+            };
+            Some(response::response::Value::RequestError(request_error))
+        }
+        Err(ClientUsageError::InflightRequestsLimit(error_message)) => {
+            log_warn("inflight requests limit", &error_message);
+            let request_error = response::RequestError {
+                type_: response::RequestErrorType::Unspecified.into(),
+                message: error_message.into(),
+                kind: response::ErrorKind::InflightRequestsLimit.into(),
+                ..Default::default()
+            };
+            Some(response::response::Value::RequestError(request_error))
+        }
         Err(ClientUsageError::Redis(err)) => {
             let error_message = error_message(&err);
             log_warn("received error", error_message.as_str());
@@ -224,9 +502,15 @@ async fn write_result(
                     RequestErrorType::ExecAbort => response::RequestErrorType::ExecAbort,
                     RequestErrorType::Timeout => response::RequestErrorType::Timeout,
                     RequestErrorType::Disconnect => response::RequestErrorType::Disconnect,
+                    RequestErrorType::Cancelled => response::RequestErrorType::Cancelled,
                 }
                 .into(),
                 message: error_message.into(),
+                kind: response::ErrorKind::from_i32(error_kind(&err) as i32)
+                    .unwrap_or_default()
+                    .into(),
+                server_error_code: server_error_code(&err).unwrap_or_default().into(),
+                address: error_address(&err).unwrap_or_default().into(),
                 ..Default::default()
             };
             Some(response::response::Value::RequestError(request_error))
@@ -388,90 +672,52 @@ async fn send_transaction(
         .map_err(|err| err.into())
 }
 
-fn get_slot_addr(slot_type: &protobuf::EnumOrUnknown<SlotTypes>) -> ClientUsageResult<SlotAddr> {
-    slot_type
-        .enum_value()
-        .map(|slot_type| match slot_type {
-            SlotTypes::Primary => SlotAddr::Master,
-            SlotTypes::Replica => SlotAddr::ReplicaRequired,
-        })
-        .map_err(|id| ClientUsageError::Internal(format!("Received unexpected slot id type {id}")))
-}
-
 fn get_route(
     route: Option<Box<Routes>>,
     cmd: Option<&Cmd>,
 ) -> ClientUsageResult<Option<RoutingInfo>> {
-    use crate::command_request::routes::Value;
-    let Some(route) = route.and_then(|route| route.value) else {
-        return Ok(None);
-    };
-    let get_response_policy = |cmd: Option<&Cmd>| {
-        cmd.and_then(|cmd| {
-            cmd.command()
-                .and_then(|cmd| ResponsePolicy::for_command(&cmd))
-        })
-    };
-    match route {
-        Value::SimpleRoutes(simple_route) => {
-            let simple_route = simple_route.enum_value().map_err(|id| {
-                ClientUsageError::Internal(format!("Received unexpected simple route type {id}"))
-            })?;
-            match simple_route {
-                crate::command_request::SimpleRoutes::AllNodes => Ok(Some(RoutingInfo::MultiNode(
-                    (MultipleNodeRoutingInfo::AllNodes, get_response_policy(cmd)),
-                ))),
-                crate::command_request::SimpleRoutes::AllPrimaries => {
-                    Ok(Some(RoutingInfo::MultiNode((
-                        MultipleNodeRoutingInfo::AllMasters,
-                        get_response_policy(cmd),
-                    ))))
-                }
-                crate::command_request::SimpleRoutes::Random => {
-                    Ok(Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)))
-                }
-            }
+    crate::routes::get_route(route.map(|route| *route), cmd)
+        .map_err(|err| ClientUsageError::Internal(err.to_string()))
+}
+
+fn admission_error(err: AdmissionError) -> ClientUsageError {
+    match err {
+        AdmissionError::LimitReached => {
+            ClientUsageError::InflightRequestsLimit("Reached maximum inflight requests".to_string())
         }
-        Value::SlotKeyRoute(slot_key_route) => Ok(Some(RoutingInfo::SingleNode(
-            SingleNodeRoutingInfo::SpecificNode(Route::new(
-                redis::cluster_topology::get_slot(slot_key_route.slot_key.as_bytes()),
-                get_slot_addr(&slot_key_route.slot_type)?,
-            )),
-        ))),
-        Value::SlotIdRoute(slot_id_route) => Ok(Some(RoutingInfo::SingleNode(
-            SingleNodeRoutingInfo::SpecificNode(Route::new(
-                slot_id_route.slot_id as u16,
-                get_slot_addr(&slot_id_route.slot_type)?,
-            )),
-        ))),
-        Value::ByAddressRoute(by_address_route) => match u16::try_from(by_address_route.port) {
-            Ok(port) => Ok(Some(RoutingInfo::SingleNode(
-                SingleNodeRoutingInfo::ByAddress {
-                    host: by_address_route.host.to_string(),
-                    port,
-                },
-            ))),
-            Err(err) => {
-                log_warn("get route", format!("Failed to parse port: {err:?}"));
-                Ok(None)
-            }
-        },
+        AdmissionError::QueueFull => ClientUsageError::InflightRequestsLimit(
+            "Reached maximum inflight requests, and the admission queue is full".to_string(),
+        ),
+        AdmissionError::QueueTimeout => ClientUsageError::Redis(
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                "Timed out waiting for an inflight request slot",
+            )
+            .into(),
+        ),
     }
 }
 
-fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer>) {
+fn handle_request(
+    request: CommandRequest,
+    mut client: Client,
+    clients: Rc<SocketClients>,
+    writer: Rc<Writer>,
+) {
     task::spawn_local(async move {
         let mut updated_inflight_counter = true;
         let client_clone = client.clone();
 
-        let result = match client.reserve_inflight_request() {
-            false => {
+        let request_kind = match request.command {
+            Some(command_request::Command::Transaction(_)) => RequestKind::Transaction,
+            _ => RequestKind::Command,
+        };
+        let result = match client.admit_inflight_request(request_kind).await {
+            Err(err) => {
                 updated_inflight_counter = false;
-                Err(ClientUsageError::User(
-                    "Reached maximum inflight requests".to_string(),
-                ))
+                Err(admission_error(err))
             }
-            true => match request.command {
+            Ok(()) => match request.command {
                 Some(action) => match action {
                     command_request::Command::ClusterScan(cluster_scan_command) => {
                         cluster_scan(cluster_scan_command, client).await
@@ -531,6 +777,26 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                         )
                         .await
                         .map_err(|err| err.into()),
+                    command_request::Command::CloseClient(_) if request.client_id == 0 => {
+                        Err(ClientUsageError::User(
+                            "The connection's first client can't be closed, close the connection instead"
+                                .to_string(),
+                        ))
+                    }
+                    command_request::Command::CloseClient(close_client_command) => {
+                        client
+                            .close(
+                                close_client_command
+                                    .drain_timeout
+                                    .map(|timeout| Duration::from_millis(timeout.into())),
+                            )
+                            .await;
+                        clients.remove(request.client_id);
+                        Ok(Value::Okay)
+                    }
+                    command_request::Command::CreateClient(_) => Err(ClientUsageError::Internal(
+                        "Client creation requests aren't sent to a client".to_string(),
+                    )),
                 },
                 None => {
                     log_debug(
@@ -551,17 +817,97 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
             client_clone.release_inflight_request();
         }
 
-        let _res = write_result(result, request.callback_idx, &writer).await;
+        let _res = write_result(
+            result,
+            request.callback_idx,
+            request.client_id,
+            request.inline_response_max_size,
+            &writer,
+        )
+        .await;
+    });
+}
+
+/// Creates a client on the connection, and reports its id in the response.
+fn handle_create_client_request(
+    request: CommandRequest,
+    clients: Rc<SocketClients>,
+    writer: Rc<Writer>,
+) {
+    task::spawn_local(async move {
+        let Some(command_request::Command::CreateClient(create_client)) = request.command else {
+            return;
+        };
+        let connection_request =
+            match ConnectionRequest::parse_from_tokio_bytes(&create_client.connection_request) {
+                Ok(connection_request) => connection_request,
+                Err(err) => {
+                    let _res = write_result(
+                        Err(ClientUsageError::User(format!(
+                            "Invalid connection request: {err}"
+                        ))),
+                        request.callback_idx,
+                        0,
+                        0,
+                        &writer,
+                    )
+                    .await;
+                    return;
+                }
+            };
+        let (push_tx, push_rx) = mpsc::unbounded_channel();
+        let (result, client_id) = match Client::new(connection_request.into(), Some(push_tx)).await
+        {
+            Ok(client) => match clients.add(client) {
+                Some(client_id) => {
+                    task::spawn_local(push_manager_loop(push_rx, writer.clone(), client_id));
+                    (Ok(Value::Okay), client_id)
+                }
+                None => (
+                    Err(ClientUsageError::User(
+                        "Too many clients on the connection".to_string(),
+                    )),
+                    0,
+                ),
+            },
+            Err(err) => (Err(ClientUsageError::Connection(err)), 0),
+        };
+        let _res = write_result(result, request.callback_idx, client_id, 0, &writer).await;
     });
 }
 
 async fn handle_requests(
     received_requests: Vec<CommandRequest>,
-    client: &Client,
+    clients: &Rc<SocketClients>,
     writer: &Rc<Writer>,
 ) {
     for request in received_requests {
-        handle_request(request, client.clone(), writer.clone());
+        if matches!(
+            request.command,
+            Some(command_request::Command::CreateClient(_))
+        ) {
+            handle_create_client_request(request, clients.clone(), writer.clone());
+            continue;
+        }
+        match clients.get(request.client_id) {
+            Some(client) => handle_request(request, client, clients.clone(), writer.clone()),
+            None => {
+                let writer = writer.clone();
+                task::spawn_local(async move {
+                    let _res = write_result(
+                        Err(ClientUsageError::User(format!(
+                            "Unknown client id {}",
+                            request.client_id
+                        ))),
+                        request.callback_idx,
+                        request.client_id,
+                        0,
+                        &writer,
+                    )
+                    .await;
+                });
+            }
+        }
     }
     // Yield to ensure that the subtasks aren't starved.
     task::yield_now().await;
@@ -581,12 +927,52 @@ async fn create_client(
         Ok(client) => client,
         Err(err) => return Err(ClientCreationError::ConnectionError(err)),
     };
-    write_result(Ok(Value::Okay), 0, writer).await?;
+    write_result(Ok(Value::Okay), 0, 0, 0, writer).await?;
     Ok(client)
 }
 
+/// Compares the tokens in constant time, so that the time it takes doesn't reveal how much of the token was guessed.
+fn tokens_match(received: &[u8], expected: &[u8]) -> bool {
+    received.len() == expected.len()
+        && received
+            .iter()
+            .zip(expected)
+            .fold(0, |diff, (received, expected)| diff | (received ^ expected))
+            == 0
+}
+
+/// Waits for the connection's `SocketAuthentication` request, and acknowledges it if its token is `auth_token`.
+/// Connections that don't send it within [`SOCKET_AUTHENTICATION_TIMEOUT`] fail to authenticate.
+async fn authenticate_connection(
+    client_listener: &mut ClientStreamListener,
+    writer: &Rc<Writer>,
+    auth_token: &str,
+) -> Result<(), ClientCreationError> {
+    let received = tokio::time::timeout(
+        SOCKET_AUTHENTICATION_TIMEOUT,
+        client_listener.next_values::<SocketAuthentication>(),
+    )
+    .await
+    .map_err(|_| ClientCreationError::AuthenticationTimedOut)?;
+    match received {
+        Closed(reason) => Err(ClientCreationError::SocketListenerClosed(reason)),
+        ReceivedValues(requests) => {
+            // The connection request must wait for the acknowledgement, so a valid handshake is a single request.
+            let authenticated = matches!(
+                requests.as_slice(),
+                [request] if tokens_match(request.token.as_bytes(), auth_token.as_bytes())
+            );
+            if !authenticated {
+                return Err(ClientCreationError::AuthenticationFailed);
+            }
+            write_result(Ok(Value::Okay), 0, 0, 0, writer).await?;
+            Ok(())
+        }
+    }
+}
+
 async fn wait_for_connection_configuration_and_create_client(
-    client_listener: &mut UnixStreamListener,
+    client_listener: &mut ClientStreamListener,
     writer: &Rc<Writer>,
     push_tx: Option<mpsc::UnboundedSender<PushInfo>>,
 ) -> Result<Client, ClientCreationError> {
@@ -606,8 +992,8 @@ async fn wait_for_connection_configuration_and_create_client(
 }
 
 async fn read_values_loop(
-    mut client_listener: UnixStreamListener,
-    client: &Client,
+    mut client_listener: ClientStreamListener,
+    clients: &Rc<SocketClients>,
     writer: Rc<Writer>,
 ) -> ClosingReason {
     loop {
@@ -616,24 +1002,33 @@ async fn read_values_loop(
                 return reason;
             }
             ReceivedValues(received_requests) => {
-                handle_requests(received_requests, client, &writer).await;
+                handle_requests(received_requests, clients, &writer).await;
             }
         }
     }
 }
 
-async fn push_manager_loop(mut push_rx: mpsc::UnboundedReceiver<PushInfo>, writer: Rc<Writer>) {
+async fn push_manager_loop(
+    mut push_rx: mpsc::UnboundedReceiver<PushInfo>,
+    writer: Rc<Writer>,
+    client_id: u32,
+) {
     loop {
         let result = push_rx.recv().await;
         match result {
             None => {
-                log_error("push manager loop", "got None from push manager");
+                // The senders are dropped along with the client's connections.
+                log_debug(
+                    "push manager loop",
+                    format!("push manager of client {client_id} closed"),
+                );
                 return;
             }
             Some(push_msg) => {
                 log_debug("push manager loop", format!("got PushInfo: {:?}", push_msg));
                 let mut response = Response::new();
                 response.callback_idx = 0; // callback_idx is not used with push notifications
+                response.client_id = client_id;
                 response.is_push = true;
                 response.value = {
                     let push_val = Value::Push {
@@ -651,11 +1046,11 @@ async fn push_manager_loop(mut push_rx: mpsc::UnboundedReceiver<PushInfo>, write
     }
 }
 
-async fn listen_on_client_stream(socket: UnixStream) {
+async fn listen_on_client_stream(socket: ClientStream, auth_token: Option<&'static str>) {
     let socket = Rc::new(socket);
     // Spawn a new task to listen on this client's stream
     let write_lock = Mutex::new(());
-    let mut client_listener = UnixStreamListener::new(socket.clone());
+    let mut client_listener = ClientStreamListener::new(socket.clone());
     let accumulated_outputs = Cell::new(Vec::new());
     let (sender, mut receiver) = channel(1);
     let (push_tx, push_rx) = tokio::sync::mpsc::unbounded_channel();
@@ -665,11 +1060,17 @@ async fn listen_on_client_stream(socket: UnixStream) {
         accumulated_outputs,
         closing_sender: sender,
     });
-    let client_creation = wait_for_connection_configuration_and_create_client(
-        &mut client_listener,
-        &writer,
-        Some(push_tx),
-    );
+    let client_creation = async {
+        if let Some(auth_token) = auth_token {
+            authenticate_connection(&mut client_listener, &writer, auth_token).await?;
+        }
+        wait_for_connection_configuration_and_create_client(
+            &mut client_listener,
+            &writer,
+            Some(push_tx),
+        )
+        .await
+    };
     let client = match client_creation.await {
         Ok(conn) => conn,
         Err(ClientCreationError::SocketListenerClosed(ClosingReason::ReadSocketClosed)) => {
@@ -692,6 +1093,8 @@ async fn listen_on_client_stream(socket: UnixStream) {
             return;
         }
         Err(e @ ClientCreationError::UnhandledError(_))
+        | Err(e @ ClientCreationError::AuthenticationFailed)
+        | Err(e @ ClientCreationError::AuthenticationTimedOut)
         | Err(e @ ClientCreationError::IO(_))
         | Err(e @ ClientCreationError::ConnectionError(_)) => {
             let err_message = e.to_string();
@@ -707,8 +1110,11 @@ async fn listen_on_client_stream(socket: UnixStream) {
         }
     };
     log_info("connection", "new connection started");
+    // Each client has its own push manager, which ends once the client is closed, without closing the connection.
+    task::spawn_local(push_manager_loop(push_rx, writer.clone(), 0));
+    let clients = Rc::new(SocketClients::new(client));
     tokio::select! {
-            reader_closing = read_values_loop(client_listener, &client, writer.clone()) => {
+            reader_closing = read_values_loop(client_listener, &clients, writer.clone()) => {
                 if let ClosingReason::UnhandledError(err) = reader_closing {
                     let _res = write_closing_error(ClosingError{err_message: err.to_string()}, u32::MAX, &writer, "client closing").await;
                 };
@@ -721,9 +1127,6 @@ async fn listen_on_client_stream(socket: UnixStream) {
                     log_trace("client closing", "writer closed");
                 }
             },
-            _ = push_manager_loop(push_rx, writer.clone()) => {
-                log_trace("client closing", "push manager closed");
-            }
     }
     log_trace("client closing", "closing connection");
 }
@@ -756,6 +1159,12 @@ enum ClientCreationError {
     SocketListenerClosed(ClosingReason),
     #[error("Connection error: {0:?}")]
     ConnectionError(crate::client::ConnectionError),
+    /// The connection didn't start with a valid `SocketAuthentication` request.
+    #[error("Socket authentication failed")]
+    AuthenticationFailed,
+    /// The connection didn't send a `SocketAuthentication` request in time.
+    #[error("Socket authentication timed out")]
+    AuthenticationTimedOut,
 }
 
 /// Enum describing errors received during client usage.
@@ -769,6 +1178,12 @@ enum ClientUsageError {
     /// An error that stems from wrong behavior of the user.
     #[error("User error: {0}")]
     User(String),
+    /// The request was rejected because the inflight requests limit was reached.
+    #[error("Inflight requests limit error: {0}")]
+    InflightRequestsLimit(String),
+    /// A client created by a `CreateClient` request failed to connect.
+    #[error("Connection error: {0:?}")]
+    Connection(crate::client::ConnectionError),
 }
 
 type ClientUsageResult<T> = Result<T, ClientUsageError>;
@@ -817,33 +1232,44 @@ pub fn start_socket_listener_internal<InitCallback>(
 ) where
     InitCallback: FnOnce(Result<String, String>) + Send + Clone + 'static,
 {
-    static INITIALIZED_SOCKETS: Lazy<RwLock<HashSet<String>>> =
-        Lazy::new(|| RwLock::new(HashSet::new()));
-
     let socket_path = socket_path.unwrap_or_else(get_socket_path);
+    start_listener(
+        ListenerId::UnixSocket(socket_path),
+        move |result: Result<ListenerAddress, String>| {
+            init_callback(result.map(|address| address.to_string()))
+        },
+    );
+}
+
+fn start_listener<InitCallback>(listener_id: ListenerId, init_callback: InitCallback)
+where
+    InitCallback: FnOnce(Result<ListenerAddress, String>) + Send + Clone + 'static,
+{
+    static INITIALIZED_LISTENERS: Lazy<RwLock<HashMap<ListenerId, ListenerAddress>>> =
+        Lazy::new(|| RwLock::new(HashMap::new()));
 
     {
         // Optimize for already initialized
-        let initialized_sockets = INITIALIZED_SOCKETS
+        let initialized_listeners = INITIALIZED_LISTENERS
             .read()
-            .expect("Failed to acquire sockets db read guard");
-        if initialized_sockets.contains(&socket_path) {
-            init_callback(Ok(socket_path.clone()));
+            .expect("Failed to acquire listeners db read guard");
+        if let Some(address) = initialized_listeners.get(&listener_id) {
+            init_callback(Ok(address.clone()));
             return;
         }
     }
 
     // Retry with write lock, will be dropped upon the function completion
-    let mut sockets_write_guard = INITIALIZED_SOCKETS
+    let mut listeners_write_guard = INITIALIZED_LISTENERS
         .write()
-        .expect("Failed to acquire sockets db write guard");
-    if sockets_write_guard.contains(&socket_path) {
-        init_callback(Ok(socket_path.clone()));
+        .expect("Failed to acquire listeners db write guard");
+    if let Some(address) = listeners_write_guard.get(&listener_id) {
+        init_callback(Ok(address.clone()));
         return;
     }
 
     let (tx, rx) = std::sync::mpsc::channel();
-    let socket_path_cloned = socket_path.clone();
+    let listener_id_cloned = listener_id.clone();
     let init_callback_cloned = init_callback.clone();
     let tx_cloned = tx.clone();
     thread::Builder::new()
@@ -862,29 +1288,32 @@ pub fn start_socket_listener_internal<InitCallback>(
                 };
 
                 runtime.block_on(async move {
-                    let listener_socket = match UnixListener::bind(socket_path_cloned.clone()) {
-                        Err(err) => {
-                            log_error(
-                                "listen_on_socket",
-                                format!("Error failed to bind listening socket: {err}"),
-                            );
-                            return Err(err);
-                        }
-                        Ok(listener_socket) => listener_socket,
-                    };
+                    let (listener_socket, address) =
+                        match SocketListener::bind(&listener_id_cloned).await {
+                            Err(err) => {
+                                log_error(
+                                    "listen_on_socket",
+                                    format!("Error failed to bind listening socket: {err}"),
+                                );
+                                return Err(err);
+                            }
+                            Ok(bound) => bound,
+                        };
 
                     // Signal initialization is successful.
                     // IMPORTANT:
                     // tx.send() must be called before init_callback_cloned() to ensure runtimes, such as Python, can properly complete the main function
-                    let _ = tx.send(true);
-                    init_callback_cloned(Ok(socket_path_cloned.clone()));
+                    let _ = tx.send(Some(address.clone()));
+                    init_callback_cloned(Ok(address));
 
+                    let auth_token = listener_socket.auth_token();
                     let local_set_pool = LocalPoolHandle::new(num_cpus::get());
                     loop {
                         match listener_socket.accept().await {
-                            Ok((stream, _addr)) => {
-                                local_set_pool
-                                    .spawn_pinned(move || listen_on_client_stream(stream));
+                            Ok(stream) => {
+                                local_set_pool.spawn_pinned(move || {
+                                    listen_on_client_stream(stream, auth_token)
+                                });
                             }
                             Err(err) => {
                                 log_error(
@@ -898,31 +1327,31 @@ pub fn start_socket_listener_internal<InitCallback>(
 
                     // ensure socket file removal
                     drop(listener_socket);
-                    let _ = std::fs::remove_file(socket_path_cloned.clone());
+                    if let ListenerId::UnixSocket(socket_path) = &listener_id_cloned {
+                        let _ = std::fs::remove_file(socket_path);
+                    }
 
-                    // no more listening on socket - update the sockets db
-                    let mut sockets_write_guard = INITIALIZED_SOCKETS
+                    // no more listening on socket - update the listeners db
+                    let mut listeners_write_guard = INITIALIZED_LISTENERS
                         .write()
-                        .expect("Failed to acquire sockets db write guard");
-                    sockets_write_guard.remove(&socket_path_cloned);
+                        .expect("Failed to acquire listeners db write guard");
+                    listeners_write_guard.remove(&listener_id_cloned);
                     Ok(())
                 })
             };
 
             if let Err(err) = init_result {
                 init_callback(Err(err.to_string()));
-                let _ = tx_cloned.send(false);
+                let _ = tx_cloned.send(None);
             }
             Ok(())
         })
         .expect("Thread spawn failed. Cannot report error because callback was moved.");
 
     // wait for thread initialization signaling, callback invocation is done in the thread
-    let _ = rx.recv().map(|res| {
-        if res {
-            sockets_write_guard.insert(socket_path);
-        }
-    });
+    if let Ok(Some(address)) = rx.recv() {
+        listeners_write_guard.insert(listener_id, address);
+    }
 }
 
 /// Creates a new thread with a main loop task listening on the socket for new connections.
@@ -936,3 +1365,18 @@ where
 {
     start_socket_listener_internal(init_callback, None);
 }
+
+/// Like [`start_socket_listener`], but listens on the given `transport`.
+/// `init_callback` is called with the address of the listener once it's listening, or with the reason it failed to start.
+pub fn start_socket_listener_with_transport<InitCallback>(
+    init_callback: InitCallback,
+    transport: SocketTransport,
+) where
+    InitCallback: FnOnce(Result<ListenerAddress, String>) + Send + Clone + 'static,
+{
+    let listener_id = match transport {
+        SocketTransport::UnixSocket => ListenerId::UnixSocket(get_socket_path()),
+        SocketTransport::TcpLoopback => ListenerId::TcpLoopback,
+    };
+    start_listener(listener_id, init_callback);
+}
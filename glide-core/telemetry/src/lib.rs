@@ -1,11 +1,14 @@
 use lazy_static::lazy_static;
 use serde::Serialize;
 use std::sync::RwLock as StdRwLock;
+use std::time::Duration;
 mod open_telemetry;
 mod open_telemetry_exporter_file;
+mod traffic_stats;
 
 pub use open_telemetry::*;
 pub use open_telemetry_exporter_file::SpanExporterFile;
+pub use traffic_stats::{HotKey, TrafficCounters, TrafficStats, TrafficStatsSnapshot};
 
 #[derive(Default, Serialize)]
 #[allow(dead_code)]
@@ -14,6 +17,18 @@ pub struct Telemetry {
     total_connections: usize,
     /// Total number of GLIDE clients
     total_clients: usize,
+    /// Number of requests currently waiting in the inflight requests admission queue
+    admission_queue_depth: usize,
+    /// Total number of requests that were admitted after waiting in the admission queue
+    total_queued_requests: usize,
+    /// Accumulated time, in microseconds, requests spent waiting in the admission queue
+    total_queue_wait_time_us: u128,
+    /// Number of slots currently observed as migrating between nodes
+    migrating_slots: usize,
+    /// Total number of requests redirected with `ASK` to the node a slot is migrated to
+    total_ask_redirections: usize,
+    /// Total number of requests retried after a `TRYAGAIN` error
+    total_tryagain_retries: usize,
 }
 
 lazy_static! {
@@ -56,6 +71,59 @@ impl Telemetry {
         t.total_clients
     }
 
+    /// Increment the number of requests waiting for admission by `incr_by`
+    /// Return the queue depth after the increment
+    pub fn incr_admission_queue_depth(incr_by: usize) -> usize {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        t.admission_queue_depth = t.admission_queue_depth.saturating_add(incr_by);
+        t.admission_queue_depth
+    }
+
+    /// Decrease the number of requests waiting for admission by `decr_by`
+    /// Return the queue depth after the decrease
+    pub fn decr_admission_queue_depth(decr_by: usize) -> usize {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        t.admission_queue_depth = t.admission_queue_depth.saturating_sub(decr_by);
+        t.admission_queue_depth
+    }
+
+    /// Record that a request was admitted after waiting `wait_time` in the admission queue
+    pub fn record_queue_wait_time(wait_time: Duration) {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        t.total_queued_requests = t.total_queued_requests.saturating_add(1);
+        t.total_queue_wait_time_us = t
+            .total_queue_wait_time_us
+            .saturating_add(wait_time.as_micros());
+    }
+
+    /// Increment the number of migrating slots by `incr_by`
+    /// Return the number of migrating slots after the increment
+    pub fn incr_migrating_slots(incr_by: usize) -> usize {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        t.migrating_slots = t.migrating_slots.saturating_add(incr_by);
+        t.migrating_slots
+    }
+
+    /// Decrease the number of migrating slots by `decr_by`
+    /// Return the number of migrating slots after the decrease
+    pub fn decr_migrating_slots(decr_by: usize) -> usize {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        t.migrating_slots = t.migrating_slots.saturating_sub(decr_by);
+        t.migrating_slots
+    }
+
+    /// Increment the total number of `ASK` redirections by `incr_by`
+    pub fn incr_total_ask_redirections(incr_by: usize) {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        t.total_ask_redirections = t.total_ask_redirections.saturating_add(incr_by);
+    }
+
+    /// Increment the total number of `TRYAGAIN` retries by `incr_by`
+    pub fn incr_total_tryagain_retries(incr_by: usize) {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        t.total_tryagain_retries = t.total_tryagain_retries.saturating_add(incr_by);
+    }
+
     /// Return the number of active connections
     pub fn total_connections() -> usize {
         TELEMETRY.read().expect(MUTEX_READ_ERR).total_connections
@@ -66,6 +134,52 @@ impl Telemetry {
         TELEMETRY.read().expect(MUTEX_READ_ERR).total_clients
     }
 
+    /// Return the number of requests currently waiting for admission
+    pub fn admission_queue_depth() -> usize {
+        TELEMETRY
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .admission_queue_depth
+    }
+
+    /// Return the number of requests that were admitted after waiting in the admission queue
+    pub fn total_queued_requests() -> usize {
+        TELEMETRY
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .total_queued_requests
+    }
+
+    /// Return the accumulated time requests spent waiting in the admission queue
+    pub fn total_queue_wait_time() -> Duration {
+        let wait_time_us = TELEMETRY
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .total_queue_wait_time_us;
+        Duration::from_micros(wait_time_us.try_into().unwrap_or(u64::MAX))
+    }
+
+    /// Return the number of slots currently observed as migrating
+    pub fn migrating_slots() -> usize {
+        TELEMETRY.read().expect(MUTEX_READ_ERR).migrating_slots
+    }
+
+    /// Return the number of requests redirected with `ASK`
+    pub fn total_ask_redirections() -> usize {
+        TELEMETRY
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .total_ask_redirections
+    }
+
+    /// Return the number of requests retried after a `TRYAGAIN` error
+    pub fn total_tryagain_retries() -> usize {
+        TELEMETRY
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .total_tryagain_retries
+    }
+
     /// Reset the telemetry collected thus far
     pub fn reset() {
         *TELEMETRY.write().expect(MUTEX_WRITE_ERR) = Telemetry::default();
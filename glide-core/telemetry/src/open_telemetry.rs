@@ -12,6 +12,12 @@ use url::Url;
 const SPAN_WRITE_LOCK_ERR: &str = "Failed to get span write lock";
 const SPAN_READ_LOCK_ERR: &str = "Failed to get span read lock";
 const TRACE_SCOPE: &str = "valkey_glide";
+const PROVIDER_LOCK_ERR: &str = "Failed to get the tracer provider lock";
+
+lazy_static::lazy_static! {
+    // The provider installed by `initialise`, kept so that its spans can be flushed without shutting it down.
+    static ref TRACER_PROVIDER: RwLock<Option<TracerProvider>> = RwLock::new(None);
+}
 
 /// Default interval in milliseconds for flushing open telemetry data to the collector.
 pub const DEFAULT_FLUSH_SPAN_INTERVAL_MS: u64 = 5000;
@@ -303,6 +309,7 @@ impl GlideOpenTelemetry {
         let provider = TracerProvider::builder()
             .with_span_processor(trace_exporter)
             .build();
+        *TRACER_PROVIDER.write().expect(PROVIDER_LOCK_ERR) = Some(provider.clone());
         global::set_tracer_provider(provider);
     }
 
@@ -317,8 +324,18 @@ impl GlideOpenTelemetry {
 
     /// Trigger a shutdown procedure flushing all remaining traces
     pub fn shutdown() {
+        TRACER_PROVIDER.write().expect(PROVIDER_LOCK_ERR).take();
         global::shutdown_tracer_provider();
     }
+
+    /// Export all remaining traces, while keeping the tracer provider running for the rest of the process.
+    ///
+    /// This method blocks until the traces are exported.
+    pub fn flush() {
+        if let Some(provider) = TRACER_PROVIDER.read().expect(PROVIDER_LOCK_ERR).as_ref() {
+            provider.force_flush();
+        }
+    }
 }
 
 #[cfg(test)]
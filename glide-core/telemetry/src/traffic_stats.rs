@@ -0,0 +1,340 @@
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::hash_map::RandomState;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+
+const MUTEX_WRITE_ERR: &str = "Failed to obtain write lock for mutex. Poisoned mutex";
+const MUTEX_READ_ERR: &str = "Failed to obtain read lock for mutex. Poisoned mutex";
+
+/// The traffic sent to a slot or a node.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TrafficCounters {
+    /// Number of requests sent
+    pub requests: u64,
+    /// Number of bytes in the arguments of the requests
+    pub bytes: u64,
+    /// Number of requests that failed
+    pub errors: u64,
+}
+
+#[derive(Default)]
+struct AtomicTrafficCounters {
+    requests: AtomicU64,
+    bytes: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl AtomicTrafficCounters {
+    fn record(&self, requests: u64, bytes: u64, failed: bool) {
+        self.requests.fetch_add(requests, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        if failed {
+            self.errors.fetch_add(requests, Ordering::Relaxed);
+        }
+    }
+
+    fn load(&self) -> TrafficCounters {
+        TrafficCounters {
+            requests: self.requests.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A frequently accessed key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HotKey {
+    pub key: String,
+    /// The estimated number of requests to the key, extrapolated from the sampled requests
+    pub estimated_requests: u64,
+}
+
+/// A point-in-time copy of the collected [`TrafficStats`].
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct TrafficStatsSnapshot {
+    pub slots: HashMap<u16, TrafficCounters>,
+    pub nodes: HashMap<String, TrafficCounters>,
+    /// The most requested keys, from the most requested one. Empty unless hot keys are tracked.
+    pub hot_keys: Vec<HotKey>,
+}
+
+/// The number of shards of a [`HotKeyTracker`]. Each key is tracked by a single shard, so requests to different keys
+/// rarely contend on the same lock.
+const HOT_KEY_SHARDS: usize = 16;
+
+/// A Space-Saving summary of the keys of one shard.
+#[derive(Default)]
+struct HotKeyShard {
+    counts: HashMap<Vec<u8>, u64>,
+    /// Holds every tracked key, with a count that is at most its count in `counts`. The counts aren't updated on
+    /// every request, but only when a stale entry reaches the top of the heap.
+    min_heap: BinaryHeap<Reverse<(u64, Vec<u8>)>>,
+}
+
+impl HotKeyShard {
+    fn sample(&mut self, key: &[u8], capacity: usize) {
+        if let Some(count) = self.counts.get_mut(key) {
+            *count += 1;
+            return;
+        }
+        if self.counts.len() < capacity {
+            self.counts.insert(key.to_vec(), 1);
+            self.min_heap.push(Reverse((1, key.to_vec())));
+            return;
+        }
+        // Replace the least requested key. The new key inherits its count, which bounds the overestimation.
+        while let Some(Reverse((heap_count, evicted_key))) = self.min_heap.pop() {
+            let count = self.counts[&evicted_key];
+            if count != heap_count {
+                self.min_heap.push(Reverse((count, evicted_key)));
+                continue;
+            }
+            self.counts.remove(&evicted_key);
+            self.counts.insert(key.to_vec(), count + 1);
+            self.min_heap.push(Reverse((count + 1, key.to_vec())));
+            return;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.counts.clear();
+        self.min_heap.clear();
+    }
+}
+
+/// Tracks the most requested keys of a sample of the requests, using the Space-Saving algorithm.
+/// The keys are sharded by their hash, and every shard keeps up to `capacity` keys, so the most requested keys of all
+/// the shards are at least as accurate as those of a single summary.
+struct HotKeyTracker {
+    capacity: usize,
+    sample_rate: u64,
+    seen_requests: AtomicU64,
+    hasher: RandomState,
+    shards: Vec<Mutex<HotKeyShard>>,
+}
+
+impl HotKeyTracker {
+    fn new(capacity: usize, sample_rate: u32, shards: usize) -> Self {
+        Self {
+            capacity,
+            sample_rate: sample_rate.max(1).into(),
+            seen_requests: AtomicU64::new(0),
+            hasher: RandomState::new(),
+            shards: (0..shards.max(1)).map(|_| Mutex::default()).collect(),
+        }
+    }
+
+    fn sample(&self, key: &[u8]) {
+        if !self
+            .seen_requests
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(self.sample_rate)
+        {
+            return;
+        }
+        let shard = self.hasher.hash_one(key) as usize % self.shards.len();
+        self.shards[shard]
+            .lock()
+            .expect(MUTEX_WRITE_ERR)
+            .sample(key, self.capacity);
+    }
+
+    fn hot_keys(&self) -> Vec<HotKey> {
+        let mut hot_keys: Vec<HotKey> = Vec::new();
+        for shard in &self.shards {
+            let shard = shard.lock().expect(MUTEX_READ_ERR);
+            hot_keys.extend(shard.counts.iter().map(|(key, count)| HotKey {
+                key: String::from_utf8_lossy(key).into_owned(),
+                estimated_requests: count.saturating_mul(self.sample_rate),
+            }));
+        }
+        hot_keys.sort_by_key(|hot_key| Reverse(hot_key.estimated_requests));
+        hot_keys.truncate(self.capacity);
+        hot_keys
+    }
+
+    fn clear(&self) {
+        for shard in &self.shards {
+            shard.lock().expect(MUTEX_WRITE_ERR).clear();
+        }
+    }
+}
+
+/// Counts the requests, bytes and errors per slot and per node, and optionally tracks the hot keys.
+#[derive(Default)]
+pub struct TrafficStats {
+    slots: RwLock<HashMap<u16, AtomicTrafficCounters>>,
+    nodes: RwLock<HashMap<String, AtomicTrafficCounters>>,
+    hot_keys: Option<HotKeyTracker>,
+}
+
+fn record<K, Q>(
+    counters: &RwLock<HashMap<K, AtomicTrafficCounters>>,
+    key: &Q,
+    requests: u64,
+    bytes: u64,
+    failed: bool,
+) where
+    K: std::borrow::Borrow<Q> + std::hash::Hash + Eq,
+    Q: ToOwned<Owned = K> + std::hash::Hash + Eq + ?Sized,
+{
+    if let Some(entry) = counters.read().expect(MUTEX_READ_ERR).get(key) {
+        entry.record(requests, bytes, failed);
+        return;
+    }
+    counters
+        .write()
+        .expect(MUTEX_WRITE_ERR)
+        .entry(key.to_owned())
+        .or_default()
+        .record(requests, bytes, failed);
+}
+
+fn load<K: Clone + std::hash::Hash + Eq>(
+    counters: &RwLock<HashMap<K, AtomicTrafficCounters>>,
+) -> HashMap<K, TrafficCounters> {
+    counters
+        .read()
+        .expect(MUTEX_READ_ERR)
+        .iter()
+        .map(|(key, counters)| (key.clone(), counters.load()))
+        .collect()
+}
+
+impl TrafficStats {
+    /// Creates stats that also track the `capacity` most requested keys, out of one in every `sample_rate` requests.
+    pub fn with_hot_keys(capacity: usize, sample_rate: u32) -> Self {
+        Self {
+            hot_keys: Some(HotKeyTracker::new(capacity, sample_rate, HOT_KEY_SHARDS)),
+            ..Default::default()
+        }
+    }
+
+    /// Records `requests` requests with `bytes` bytes of arguments sent to the node at `address`,
+    /// and to `slot` if they target a single slot.
+    pub fn record_requests(
+        &self,
+        slot: Option<u16>,
+        address: &str,
+        requests: u64,
+        bytes: u64,
+        failed: bool,
+    ) {
+        if let Some(slot) = slot {
+            record(&self.slots, &slot, requests, bytes, failed);
+        }
+        record(&self.nodes, address, requests, bytes, failed);
+    }
+
+    /// Records a request to `key`, if hot keys are tracked.
+    pub fn sample_key(&self, key: &[u8]) {
+        if let Some(hot_keys) = &self.hot_keys {
+            hot_keys.sample(key);
+        }
+    }
+
+    pub fn snapshot(&self) -> TrafficStatsSnapshot {
+        TrafficStatsSnapshot {
+            slots: load(&self.slots),
+            nodes: load(&self.nodes),
+            hot_keys: self
+                .hot_keys
+                .as_ref()
+                .map(HotKeyTracker::hot_keys)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Clears the stats collected thus far.
+    pub fn reset(&self) {
+        self.slots.write().expect(MUTEX_WRITE_ERR).clear();
+        self.nodes.write().expect(MUTEX_WRITE_ERR).clear();
+        if let Some(hot_keys) = &self.hot_keys {
+            hot_keys.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_per_slot_and_node() {
+        let stats = TrafficStats::default();
+        stats.record_requests(Some(7), "node1:6379", 1, 10, false);
+        stats.record_requests(Some(7), "node1:6379", 1, 5, true);
+        stats.record_requests(None, "node2:6379", 3, 30, false);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(
+            snapshot.slots,
+            HashMap::from([(
+                7,
+                TrafficCounters {
+                    requests: 2,
+                    bytes: 15,
+                    errors: 1
+                }
+            )])
+        );
+        assert_eq!(snapshot.nodes["node2:6379"].requests, 3);
+        assert!(snapshot.hot_keys.is_empty());
+
+        stats.reset();
+        assert!(stats.snapshot().nodes.is_empty());
+    }
+
+    #[test]
+    fn test_hot_keys_keep_the_most_requested_keys() {
+        let tracker = HotKeyTracker::new(2, 1, 1);
+        for _ in 0..5 {
+            tracker.sample(b"hot");
+        }
+        for _ in 0..3 {
+            tracker.sample(b"warm");
+        }
+        tracker.sample(b"cold");
+
+        let hot_keys = tracker.hot_keys();
+        assert_eq!(hot_keys.len(), 2);
+        assert_eq!(
+            hot_keys[0],
+            HotKey {
+                key: "hot".to_string(),
+                estimated_requests: 5
+            }
+        );
+        // `cold` replaced `warm`, inheriting its count.
+        assert_eq!(hot_keys[1].key, "cold");
+        assert_eq!(hot_keys[1].estimated_requests, 4);
+
+        tracker.clear();
+        assert!(tracker.hot_keys().is_empty());
+    }
+
+    #[test]
+    fn test_hot_keys_of_all_shards_are_merged() {
+        let stats = TrafficStats::with_hot_keys(2, 1);
+        for key in 0..10 {
+            stats.sample_key(format!("cold{key}").as_bytes());
+        }
+        for _ in 0..50 {
+            stats.sample_key(b"hot");
+        }
+        for _ in 0..20 {
+            stats.sample_key(b"warm");
+        }
+
+        let hot_keys = stats.snapshot().hot_keys;
+        assert_eq!(hot_keys.len(), 2);
+        assert_eq!(hot_keys[0].key, "hot");
+        assert!(hot_keys[0].estimated_requests >= 50);
+        assert_eq!(hot_keys[1].key, "warm");
+        assert!(hot_keys[1].estimated_requests >= 20);
+    }
+}
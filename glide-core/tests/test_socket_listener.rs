@@ -24,7 +24,9 @@ mod socket_listener {
     use command_request::{CommandRequest, RequestType};
     use glide_core::command_request::command::{Args, ArgsArray};
     use glide_core::command_request::{Command, Transaction};
-    use glide_core::response::{response, ConstantResponse, Response};
+    use glide_core::response::{
+        inline_value, response, ConstantResponse, InlineValue, Response, StringArray, StringKind,
+    };
     use glide_core::scripts_container::add_script;
     use protobuf::{EnumOrUnknown, Message};
     use redis::{Cmd, ConnectionAddr, FromRedisValue, Value};
@@ -821,6 +823,96 @@ mod socket_listener {
         assert_null_response(&mut buffer, &mut test_basics.socket, CALLBACK_INDEX);
     }
 
+    /// Sends `GET key` with the given `inline_response_max_size` to a mock that replies with `mock_response`,
+    /// and returns the response.
+    fn get_with_inline_response_max_size(
+        mock_response: &str,
+        inline_response_max_size: u32,
+    ) -> Response {
+        const CALLBACK_INDEX: u32 = 99;
+        let key = generate_random_string(KEY_LENGTH);
+        let mut expected_command = Cmd::new();
+        expected_command.arg("GET").arg(key.clone());
+        let mut test_basics = setup_mocked_test_basics(None);
+        test_basics
+            .server_mock
+            .add_response(&expected_command, mock_response.to_string());
+        let mut request = get_command_request(
+            CALLBACK_INDEX,
+            vec![key.into()],
+            RequestType::Get.into(),
+            false,
+        );
+        request.inline_response_max_size = inline_response_max_size;
+        let mut buffer = Vec::with_capacity(100);
+        write_request(&mut buffer, &mut test_basics.socket, request);
+
+        let response = get_response(&mut buffer, Some(&mut test_basics.socket));
+        assert_eq!(response.callback_idx, CALLBACK_INDEX);
+        response
+    }
+
+    #[rstest]
+    #[case::bulk_string("$5\r\nvalue\r\n", "value", StringKind::BulkString)]
+    #[case::simple_string("+PONG\r\n", "PONG", StringKind::SimpleString)]
+    #[case::verbatim_string("=9\r\ntxt:value\r\n", "value", StringKind::VerbatimString)]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_inlines_strings_with_their_kind(
+        #[case] mock_response: &str,
+        #[case] expected_value: &str,
+        #[case] expected_kind: StringKind,
+    ) {
+        let response = get_with_inline_response_max_size(mock_response, 5);
+        let mut expected = InlineValue::new();
+        expected.value = Some(inline_value::Value::StringValue(
+            expected_value.to_string().into(),
+        ));
+        expected.string_kind = expected_kind.into();
+        assert_eq!(response.value, Some(response::Value::InlineValue(expected)));
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_inlines_ints_and_arrays_of_strings() {
+        let response = get_with_inline_response_max_size(":-7\r\n", 8);
+        let mut expected = InlineValue::new();
+        expected.value = Some(inline_value::Value::IntValue(-7));
+        assert_eq!(response.value, Some(response::Value::InlineValue(expected)));
+
+        let response =
+            get_with_inline_response_max_size("*3\r\n$5\r\nfirst\r\n+second\r\n+OK\r\n", 13);
+        let mut array = StringArray::new();
+        array.values = vec!["first".into(), "second".into(), "OK".into()];
+        array.kinds = vec![
+            StringKind::BulkString.into(),
+            StringKind::SimpleString.into(),
+            StringKind::Okay.into(),
+        ];
+        let mut expected = InlineValue::new();
+        expected.value = Some(inline_value::Value::StringArray(array));
+        assert_eq!(response.value, Some(response::Value::InlineValue(expected)));
+    }
+
+    #[rstest]
+    // Too large for the requested size.
+    #[case::too_large("$5\r\nvalue\r\n", 4)]
+    // Inlining is disabled.
+    #[case::disabled("$5\r\nvalue\r\n", 0)]
+    // Not simple values.
+    #[case::array_with_nil("*2\r\n$5\r\nvalue\r\n$-1\r\n", 100)]
+    #[case::double(",1.5\r\n", 100)]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_passes_values_by_pointer_when_not_inlined(
+        #[case] mock_response: &str,
+        #[case] inline_response_max_size: u32,
+    ) {
+        let response = get_with_inline_response_max_size(mock_response, inline_response_max_size);
+        let Some(response::Value::RespPointer(pointer)) = response.value else {
+            panic!("Expected a pointer, received {response:?}");
+        };
+        let _value = pointer_to_value(pointer);
+    }
+
     #[rstest]
     #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
     fn test_socket_report_error() {
@@ -1117,6 +1209,130 @@ mod socket_listener {
         );
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_multiple_clients_on_one_socket() {
+        use glide_core::command_request::{CloseClient, CreateClient};
+
+        let mut test_basics = setup_server_test_basics(Tls::NoTls, TestServer::Shared);
+        let key = generate_random_string(KEY_LENGTH);
+        let value = generate_random_string(10);
+        let mut buffer = Vec::with_capacity(300);
+
+        // Create a second client, connected to the same server.
+        let connection_request = create_connection_request(
+            &[get_shared_server_address(false)],
+            &TestConfiguration {
+                request_timeout: Some(REQUEST_TIMEOUT_MS),
+                ..Default::default()
+            },
+        );
+        let mut request = CommandRequest::new();
+        request.callback_idx = 1;
+        request.command = Some(command_request::command_request::Command::CreateClient(
+            CreateClient {
+                connection_request: connection_request.write_to_bytes().unwrap().into(),
+                ..Default::default()
+            },
+        ));
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        let response = assert_response(
+            &mut buffer,
+            Some(&mut test_basics.socket),
+            1,
+            Some(Value::Okay),
+            ResponseType::Value,
+        );
+        let client_id = response.client_id;
+        assert_ne!(client_id, 0);
+
+        // A value set by the second client is visible to the first client.
+        buffer.clear();
+        let mut request = get_command_request(
+            2,
+            vec![key.clone().into(), value.clone().into()],
+            RequestType::Set.into(),
+            false,
+        );
+        request.client_id = client_id;
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        let response = assert_response(
+            &mut buffer,
+            Some(&mut test_basics.socket),
+            2,
+            Some(Value::Okay),
+            ResponseType::Value,
+        );
+        assert_eq!(response.client_id, client_id);
+
+        buffer.clear();
+        write_get(&mut buffer, &mut test_basics.socket, 3, key.as_str(), false);
+        let response = assert_value_response(
+            &mut buffer,
+            Some(&mut test_basics.socket),
+            3,
+            Value::BulkString(value.clone().into_bytes()),
+        );
+        assert_eq!(response.client_id, 0);
+
+        // Once closed, the second client no longer accepts requests, while the first client keeps working.
+        buffer.clear();
+        let mut request = CommandRequest::new();
+        request.callback_idx = 4;
+        request.client_id = client_id;
+        request.command = Some(command_request::command_request::Command::CloseClient(
+            CloseClient::new(),
+        ));
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        assert_ok_response(&mut buffer, &mut test_basics.socket, 4);
+
+        buffer.clear();
+        let mut request =
+            get_command_request(5, vec![key.clone().into()], RequestType::Get.into(), false);
+        request.client_id = client_id;
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        assert_error_response(
+            &mut buffer,
+            &mut test_basics.socket,
+            5,
+            ResponseType::RequestError,
+        );
+
+        buffer.clear();
+        write_get(&mut buffer, &mut test_basics.socket, 6, key.as_str(), false);
+        assert_value_response(
+            &mut buffer,
+            Some(&mut test_basics.socket),
+            6,
+            Value::BulkString(value.clone().into_bytes()),
+        );
+
+        // The first client can't be closed, and keeps working.
+        buffer.clear();
+        let mut request = CommandRequest::new();
+        request.callback_idx = 7;
+        request.command = Some(command_request::command_request::Command::CloseClient(
+            CloseClient::new(),
+        ));
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        assert_error_response(
+            &mut buffer,
+            &mut test_basics.socket,
+            7,
+            ResponseType::RequestError,
+        );
+
+        buffer.clear();
+        write_get(&mut buffer, &mut test_basics.socket, 8, key.as_str(), false);
+        assert_value_response(
+            &mut buffer,
+            Some(&mut test_basics.socket),
+            8,
+            Value::BulkString(value.into_bytes()),
+        );
+    }
+
     #[rstest]
     #[serial_test::serial]
     #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
@@ -1275,4 +1491,111 @@ mod socket_listener {
             ResponseType::RequestError,
         );
     }
+
+    fn start_tcp_listener() -> (std::net::SocketAddr, String) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        start_socket_listener_with_transport(
+            move |result| tx.send(result).unwrap(),
+            SocketTransport::TcpLoopback,
+        );
+        match rx.recv().unwrap().unwrap() {
+            ListenerAddress::TcpLoopback {
+                address,
+                auth_token,
+            } => (address, auth_token),
+            address => panic!("Unexpected listener address {address:?}"),
+        }
+    }
+
+    fn connect_to_tcp_listener() -> (std::net::TcpStream, String) {
+        let (address, auth_token) = start_tcp_listener();
+        let stream = std::net::TcpStream::connect(address).unwrap();
+        stream
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        (stream, auth_token)
+    }
+
+    fn read_tcp_response(stream: &mut std::net::TcpStream) -> Option<response::Value> {
+        let mut buffer = rotating_buffer::RotatingBuffer::new(1024);
+        loop {
+            let mut bytes = [0; 1024];
+            let read = stream.read(&mut bytes).unwrap();
+            assert_ne!(
+                read, 0,
+                "The listener closed the connection without a response"
+            );
+            buffer.current_buffer().extend_from_slice(&bytes[..read]);
+            if let Some(response) = buffer.get_requests::<Response>().unwrap().pop() {
+                return response.value;
+            }
+        }
+    }
+
+    fn authenticate(stream: &mut std::net::TcpStream, token: &str) -> Option<response::Value> {
+        let mut request = connection_request::SocketAuthentication::new();
+        request.token = token.into();
+        stream
+            .write_all(&request.write_length_delimited_to_bytes().unwrap())
+            .unwrap();
+        read_tcp_response(stream)
+    }
+
+    fn assert_connection_closed(stream: &mut std::net::TcpStream) {
+        let mut bytes = [0; 1024];
+        assert_eq!(stream.read(&mut bytes).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_tcp_listener_is_started_once() {
+        let (first_address, first_token) = start_tcp_listener();
+        let (second_address, second_token) = start_tcp_listener();
+        assert!(first_address.ip().is_loopback());
+        assert_eq!(first_address, second_address);
+        assert_eq!(first_token, second_token);
+        assert_eq!(first_token.len(), 32);
+    }
+
+    #[test]
+    fn test_tcp_connection_authenticates_with_token() {
+        let (mut stream, auth_token) = connect_to_tcp_listener();
+        assert_eq!(
+            authenticate(&mut stream, &auth_token),
+            Some(response::Value::ConstantResponse(
+                ConstantResponse::OK.into()
+            ))
+        );
+    }
+
+    #[rstest]
+    fn test_tcp_connection_with_wrong_token_is_closed(
+        #[values("wrong token", "", "prefix", "suffix", "different")] token: &str,
+    ) {
+        let (mut stream, auth_token) = connect_to_tcp_listener();
+        let token = match token {
+            "prefix" => auth_token[..auth_token.len() - 1].to_string(),
+            "suffix" => format!("{auth_token}0"),
+            "different" => auth_token.chars().rev().collect(),
+            token => token.to_string(),
+        };
+        assert_eq!(
+            authenticate(&mut stream, &token),
+            Some(response::Value::ClosingError(
+                "Socket authentication failed".into()
+            ))
+        );
+        assert_connection_closed(&mut stream);
+    }
+
+    #[test]
+    fn test_tcp_connection_that_does_not_authenticate_is_closed() {
+        let (mut stream, _) = connect_to_tcp_listener();
+        assert_eq!(
+            read_tcp_response(&mut stream),
+            Some(response::Value::ClosingError(
+                "Socket authentication timed out".into()
+            ))
+        );
+        assert_connection_closed(&mut stream);
+    }
 }
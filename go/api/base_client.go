@@ -6,13 +6,16 @@ package api
 // #include "../lib.h"
 //
 // void successCallback(void *channelPtr, struct CommandResponse *message);
-// void failureCallback(void *channelPtr, char *errMessage, RequestErrorType errType);
+// void failureCallback(void *channelPtr, char *errMessage, RequestErrorType errType, RequestErrorKind errKind, char *errCode,
+//     char *errAddress);
+// void pushCallback(uintptr_t clientPtr, struct CommandResponse *message);
 import "C"
 
 import (
 	"fmt"
 	"math"
 	"strconv"
+	"sync"
 	"unsafe"
 
 	"github.com/valkey-io/valkey-glide/go/glide/api/config"
@@ -53,15 +56,66 @@ func successCallback(channelPtr unsafe.Pointer, cResponse *C.struct_CommandRespo
 }
 
 //export failureCallback
-func failureCallback(channelPtr unsafe.Pointer, cErrorMessage *C.char, cErrorType C.RequestErrorType) {
+func failureCallback(
+	channelPtr unsafe.Pointer,
+	cErrorMessage *C.char,
+	cErrorType C.RequestErrorType,
+	cErrorKind C.RequestErrorKind,
+	cErrorCode *C.char,
+	cErrorAddress *C.char,
+) {
 	defer C.free_error_message(cErrorMessage)
 	msg := C.GoString(cErrorMessage)
+	details := errors.ErrorDetails{
+		Kind:    errors.RequestErrorKind(cErrorKind),
+		Code:    takeErrorDetail(cErrorCode),
+		Address: takeErrorDetail(cErrorAddress),
+	}
 	resultChannel := *(*chan payload)(channelPtr)
-	resultChannel <- payload{value: nil, error: errors.GoError(uint32(cErrorType), msg)}
+	resultChannel <- payload{value: nil, error: errors.GoError(uint32(cErrorType), msg, details)}
+}
+
+// Copies and frees an error code or address passed to the failure callback, which is null if it's missing.
+func takeErrorDetail(cDetail *C.char) string {
+	if cDetail == nil {
+		return ""
+	}
+	defer C.free_error_message(cDetail)
+	return C.GoString(cDetail)
+}
+
+// The push handlers of the open clients, by the handle of their core client.
+var (
+	pushHandlersLock sync.RWMutex
+	pushHandlers     = map[uintptr]PushHandler{}
+)
+
+//export pushCallback
+func pushCallback(clientPtr C.uintptr_t, cResponse *C.struct_CommandResponse) {
+	defer C.free_command_response(cResponse)
+	pushHandlersLock.RLock()
+	handler := pushHandlers[uintptr(clientPtr)]
+	pushHandlersLock.RUnlock()
+	if handler == nil {
+		return
+	}
+
+	kind := C.GoStringN(cResponse.kind_value, C.int(cResponse.kind_value_len))
+	data, err := parseArray(cResponse)
+	if err != nil {
+		return
+	}
+	notification := PushNotification{Kind: kind}
+	if data != nil {
+		notification.Data = data.([]interface{})
+	}
+	handler(notification)
 }
 
 type clientConfiguration interface {
 	toProtobuf() *protobuf.ConnectionRequest
+	getPushHandler() PushHandler
+	getWorkerThreads() int
 }
 
 type baseClient struct {
@@ -81,12 +135,23 @@ func createClient(config clientConfiguration) (*baseClient, error) {
 
 	byteCount := len(msg)
 	requestBytes := C.CBytes(msg)
+	var cPushCallback C.PushCallback
+	pushHandler := config.getPushHandler()
+	if pushHandler != nil {
+		cPushCallback = (C.PushCallback)(unsafe.Pointer(C.pushCallback))
+		// Notifications are delivered once the client is created, so the push callback waits for the lock until the handler
+		// is registered.
+		pushHandlersLock.Lock()
+		defer pushHandlersLock.Unlock()
+	}
 	cResponse := (*C.struct_ConnectionResponse)(
 		C.create_client(
 			(*C.uchar)(requestBytes),
 			C.uintptr_t(byteCount),
 			(C.SuccessCallback)(unsafe.Pointer(C.successCallback)),
 			(C.FailureCallback)(unsafe.Pointer(C.failureCallback)),
+			cPushCallback,
+			C.uintptr_t(config.getWorkerThreads()),
 		),
 	)
 	defer C.free_connection_response(cResponse)
@@ -97,6 +162,9 @@ func createClient(config clientConfiguration) (*baseClient, error) {
 		return nil, &errors.ConnectionError{Msg: message}
 	}
 
+	if pushHandler != nil {
+		pushHandlers[uintptr(cResponse.conn_ptr)] = pushHandler
+	}
 	return &baseClient{cResponse.conn_ptr}, nil
 }
 
@@ -107,6 +175,9 @@ func (client *baseClient) Close() {
 	}
 
 	C.close_client(client.coreClient)
+	pushHandlersLock.Lock()
+	delete(pushHandlers, uintptr(client.coreClient))
+	pushHandlersLock.Unlock()
 	client.coreClient = nil
 }
 
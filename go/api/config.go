@@ -77,6 +77,9 @@ type baseClientConfiguration struct {
 	readFrom       ReadFrom
 	requestTimeout int
 	clientName     string
+	subscriptions  pubSubSubscriptions
+	pushHandler    PushHandler
+	workerThreads  int
 }
 
 func (config *baseClientConfiguration) toProtobuf() *protobuf.ConnectionRequest {
@@ -104,9 +107,28 @@ func (config *baseClientConfiguration) toProtobuf() *protobuf.ConnectionRequest
 		request.ClientName = config.clientName
 	}
 
+	if len(config.subscriptions) > 0 {
+		request.PubsubSubscriptions = config.subscriptions.toProtobuf()
+	}
+
 	return &request
 }
 
+func (config *baseClientConfiguration) getPushHandler() PushHandler {
+	return config.pushHandler
+}
+
+func (config *baseClientConfiguration) getWorkerThreads() int {
+	return config.workerThreads
+}
+
+func (config *baseClientConfiguration) addSubscription(channelType PubSubChannelType, channelOrPattern string) {
+	if config.subscriptions == nil {
+		config.subscriptions = pubSubSubscriptions{}
+	}
+	config.subscriptions[channelType] = append(config.subscriptions[channelType], channelOrPattern)
+}
+
 // BackoffStrategy represents the strategy used to determine how and when to reconnect, in case of connection failures. The
 // time between attempts grows exponentially, to the formula:
 //
@@ -234,6 +256,32 @@ func (config *GlideClientConfiguration) WithDatabaseId(id int) *GlideClientConfi
 	return config
 }
 
+// WithSubscription subscribes the client to a channel or pattern when it connects, and after reconnections. The messages
+// are delivered to the [PushHandler] set with WithPushHandler. Only [ExactChannelMode] and [PatternChannelMode] are
+// supported in standalone mode.
+func (config *GlideClientConfiguration) WithSubscription(
+	channelType PubSubChannelType,
+	channelOrPattern string,
+) *GlideClientConfiguration {
+	config.addSubscription(channelType, channelOrPattern)
+	return config
+}
+
+// WithPushHandler sets the [PushHandler] that receives the client's push notifications, such as Pub/Sub messages. If not
+// set, push notifications are dropped.
+func (config *GlideClientConfiguration) WithPushHandler(handler PushHandler) *GlideClientConfiguration {
+	config.pushHandler = handler
+	return config
+}
+
+// WithWorkerThreads sets the number of worker threads of the runtime shared by all the clients. The runtime is started by
+// the first client, and its number of worker threads can't be changed while any client is open. If not set, a worker
+// thread per CPU is used.
+func (config *GlideClientConfiguration) WithWorkerThreads(workerThreads int) *GlideClientConfiguration {
+	config.workerThreads = workerThreads
+	return config
+}
+
 // GlideClusterClientConfiguration represents the configuration settings for a Cluster Glide client.
 // Note: Currently, the reconnection strategy in cluster mode is not configurable, and exponential backoff with fixed values is
 // used.
@@ -310,3 +358,28 @@ func (config *GlideClusterClientConfiguration) WithClientName(clientName string)
 	config.clientName = clientName
 	return config
 }
+
+// WithSubscription subscribes the client to a channel or pattern when it connects, and after reconnections and topology
+// changes. The messages are delivered to the [PushHandler] set with WithPushHandler.
+func (config *GlideClusterClientConfiguration) WithSubscription(
+	channelType PubSubChannelType,
+	channelOrPattern string,
+) *GlideClusterClientConfiguration {
+	config.addSubscription(channelType, channelOrPattern)
+	return config
+}
+
+// WithPushHandler sets the [PushHandler] that receives the client's push notifications, such as Pub/Sub messages. If not
+// set, push notifications are dropped.
+func (config *GlideClusterClientConfiguration) WithPushHandler(handler PushHandler) *GlideClusterClientConfiguration {
+	config.pushHandler = handler
+	return config
+}
+
+// WithWorkerThreads sets the number of worker threads of the runtime shared by all the clients. The runtime is started by
+// the first client, and its number of worker threads can't be changed while any client is open. If not set, a worker
+// thread per CPU is used.
+func (config *GlideClusterClientConfiguration) WithWorkerThreads(workerThreads int) *GlideClusterClientConfiguration {
+	config.workerThreads = workerThreads
+	return config
+}
@@ -14,9 +14,58 @@ type ConnectionError struct {
 
 func (e *ConnectionError) Error() string { return e.Msg }
 
+// RequestErrorKind is a machine-readable kind of a request error. Its values match `RequestErrorKind` in lib.h.
+type RequestErrorKind uint32
+
+const (
+	UnknownErrorKind RequestErrorKind = iota
+	ResponseErrorKind
+	ParseErrorKind
+	AuthenticationFailedErrorKind
+	TypeErrorKind
+	ExecAbortErrorKind
+	BusyLoadingErrorKind
+	NoScriptErrorKind
+	InvalidClientConfigErrorKind
+	MovedErrorKind
+	AskErrorKind
+	TryAgainErrorKind
+	ClusterDownErrorKind
+	CrossSlotErrorKind
+	MasterDownErrorKind
+	IoErrorKind
+	FatalSendErrorKind
+	FatalReceiveErrorKind
+	ClientErrorKind
+	ExtensionErrorKind
+	ReadOnlyErrorKind
+	MasterNameNotFoundBySentinelErrorKind
+	NoValidReplicasFoundBySentinelErrorKind
+	EmptySentinelListErrorKind
+	NotBusyErrorKind
+	AllConnectionsUnavailableErrorKind
+	ConnectionNotFoundForRouteErrorKind
+	Resp3NotSupportedErrorKind
+	NotAllSlotsCoveredErrorKind
+	UserOperationErrorKind
+	RequestCancelledErrorKind
+	InflightRequestsLimitErrorKind
+)
+
+// ErrorDetails are the details reported along with a request error.
+type ErrorDetails struct {
+	// Kind is the kind of the error.
+	Kind RequestErrorKind
+	// Code is the error code sent by the server, e.g. "MOVED" or "WRONGTYPE", or empty if there is none.
+	Code string
+	// Address is the address that a MOVED or ASK error redirects to, or empty for other errors.
+	Address string
+}
+
 // RequestError is a client error that occurs when an error is reported during a request.
 type RequestError struct {
 	Msg string
+	ErrorDetails
 }
 
 func (e *RequestError) Error() string { return e.Msg }
@@ -24,6 +73,7 @@ func (e *RequestError) Error() string { return e.Msg }
 // ExecAbortError is a client error that occurs when a transaction is aborted.
 type ExecAbortError struct {
 	msg string
+	ErrorDetails
 }
 
 func (e *ExecAbortError) Error() string { return e.msg }
@@ -31,6 +81,7 @@ func (e *ExecAbortError) Error() string { return e.msg }
 // TimeoutError is a client error that occurs when a request times out.
 type TimeoutError struct {
 	msg string
+	ErrorDetails
 }
 
 func (e *TimeoutError) Error() string { return e.msg }
@@ -38,10 +89,20 @@ func (e *TimeoutError) Error() string { return e.msg }
 // DisconnectError is a client error that indicates a connection problem between Glide and server.
 type DisconnectError struct {
 	msg string
+	ErrorDetails
 }
 
 func (e *DisconnectError) Error() string { return e.msg }
 
+// CancelledError is a client error that occurs when a request is cancelled because the client was closed before
+// the request completed.
+type CancelledError struct {
+	msg string
+	ErrorDetails
+}
+
+func (e *CancelledError) Error() string { return e.msg }
+
 // ClosingError is a client error that indicates that the client has closed and is no longer usable.
 type ClosingError struct {
 	Msg string
@@ -49,15 +110,17 @@ type ClosingError struct {
 
 func (e *ClosingError) Error() string { return e.Msg }
 
-func GoError(cErrorType uint32, errorMessage string) error {
+func GoError(cErrorType uint32, errorMessage string, details ErrorDetails) error {
 	switch cErrorType {
 	case C.ExecAbort:
-		return &ExecAbortError{errorMessage}
+		return &ExecAbortError{errorMessage, details}
 	case C.Timeout:
-		return &TimeoutError{errorMessage}
+		return &TimeoutError{errorMessage, details}
 	case C.Disconnect:
-		return &DisconnectError{errorMessage}
+		return &DisconnectError{errorMessage, details}
+	case C.Cancelled:
+		return &CancelledError{errorMessage, details}
 	default:
-		return &RequestError{errorMessage}
+		return &RequestError{errorMessage, details}
 	}
 }
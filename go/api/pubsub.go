@@ -0,0 +1,44 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+package api
+
+import "github.com/valkey-io/valkey-glide/go/glide/protobuf"
+
+// PubSubChannelType is the type of a Pub/Sub subscription.
+type PubSubChannelType int
+
+const (
+	// ExactChannelMode - Subscribes to a channel by its exact name.
+	ExactChannelMode PubSubChannelType = iota
+	// PatternChannelMode - Subscribes to every channel whose name matches a glob-style pattern.
+	PatternChannelMode
+	// ShardedChannelMode - Subscribes to a sharded channel. Only supported by cluster clients.
+	ShardedChannelMode
+)
+
+// PushNotification is a push notification received by the client, such as a Pub/Sub message.
+type PushNotification struct {
+	// Kind is the kind of the notification, e.g. "message", "pmessage" or "subscribe".
+	Kind string
+	// Data is the content of the notification. For a "message" notification, it holds the channel and the message.
+	Data []interface{}
+}
+
+// PushHandler is called for every push notification received by the client, in the order they were received from each
+// connection. It is called on one of the client's worker threads, so it should hand the notification off and return
+// quickly.
+type PushHandler func(notification PushNotification)
+
+type pubSubSubscriptions map[PubSubChannelType][]string
+
+func (subscriptions pubSubSubscriptions) toProtobuf() *protobuf.PubSubSubscriptions {
+	channelsOrPatternsByType := make(map[uint32]*protobuf.PubSubChannelsOrPatterns, len(subscriptions))
+	for channelType, channelsOrPatterns := range subscriptions {
+		channels := &protobuf.PubSubChannelsOrPatterns{}
+		for _, channelOrPattern := range channelsOrPatterns {
+			channels.ChannelsOrPatterns = append(channels.ChannelsOrPatterns, []byte(channelOrPattern))
+		}
+		channelsOrPatternsByType[uint32(channelType)] = channels
+	}
+	return &protobuf.PubSubSubscriptions{ChannelsOrPatternsByType: channelsOrPatternsByType}
+}
@@ -8,6 +8,7 @@ import "C"
 
 import (
 	"fmt"
+	"math/big"
 	"reflect"
 	"strconv"
 	"unsafe"
@@ -89,6 +90,13 @@ func parseInterface(response *C.struct_CommandResponse) (interface{}, error) {
 		return parseMap(response)
 	case C.Sets:
 		return parseSet(response)
+	case C.BigNumber:
+		return parseBigNumber(response)
+	case C.Attribute:
+		// Attributes are out-of-band metadata, so only the value they are attached to is returned.
+		return parseInterface(response.attribute_data)
+	case C.Push:
+		return parseArray(response)
 	}
 
 	return nil, &errors.RequestError{Msg: "Unexpected return type from Valkey"}
@@ -104,6 +112,18 @@ func parseString(response *C.struct_CommandResponse) (interface{}, error) {
 	return string(byteSlice), nil
 }
 
+func parseBigNumber(response *C.struct_CommandResponse) (interface{}, error) {
+	str, err := parseString(response)
+	if err != nil || str == nil {
+		return nil, err
+	}
+	number, ok := new(big.Int).SetString(str.(string), 10)
+	if !ok {
+		return nil, &errors.RequestError{Msg: fmt.Sprintf("Invalid big number from Valkey: %s", str)}
+	}
+	return number, nil
+}
+
 func parseArray(response *C.struct_CommandResponse) (interface{}, error) {
 	if response.array_value == nil {
 		return nil, nil
@@ -382,6 +382,20 @@ func (suite *GlideTestSuite) TestIncrCommands_TypeError() {
 	})
 }
 
+func (suite *GlideTestSuite) TestRequestError_Details() {
+	suite.runWithDefaultClients(func(client api.BaseClient) {
+		key := uuid.New().String()
+		suite.verifyOK(client.Set(key, "stringValue"))
+
+		_, err := client.LPush(key, []string{"value"})
+		assert.IsType(suite.T(), &errors.RequestError{}, err)
+		requestError := err.(*errors.RequestError)
+		assert.Equal(suite.T(), errors.ExtensionErrorKind, requestError.Kind)
+		assert.Equal(suite.T(), "WRONGTYPE", requestError.Code)
+		assert.Empty(suite.T(), requestError.Address)
+	})
+}
+
 func (suite *GlideTestSuite) TestDecrCommands_existingKey() {
 	suite.runWithDefaultClients(func(client api.BaseClient) {
 		key := uuid.New().String()
@@ -516,3 +516,34 @@ func (suite *GlideTestSuite) TestTime_Error() {
 	assert.Nil(suite.T(), results)
 	assert.IsType(suite.T(), &errors.ClosingError{}, err)
 }
+
+func (suite *GlideTestSuite) TestPushHandler_ReceivesPublishedMessage() {
+	channel := uuid.New().String()
+	notifications := make(chan api.PushNotification, 10)
+	config := api.NewGlideClientConfiguration().
+		WithAddress(&suite.standaloneHosts[0]).
+		WithUseTLS(suite.tls).
+		WithSubscription(api.ExactChannelMode, channel).
+		WithPushHandler(func(notification api.PushNotification) {
+			notifications <- notification
+		})
+	suite.client(config)
+
+	result, err := suite.defaultClient().CustomCommand([]string{"PUBLISH", channel, "hello"})
+	assert.Nil(suite.T(), err)
+	assert.Equal(suite.T(), int64(1), result)
+
+	timeout := time.After(5 * time.Second)
+	for {
+		select {
+		case notification := <-notifications:
+			if notification.Kind == "message" {
+				assert.Equal(suite.T(), []interface{}{channel, "hello"}, notification.Data)
+				return
+			}
+		case <-timeout:
+			assert.Fail(suite.T(), "The published message wasn't received")
+			return
+		}
+	}
+}
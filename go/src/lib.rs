@@ -2,121 +2,24 @@
 
 #![deny(unsafe_op_in_unsafe_fn)]
 use glide_core::client::Client as GlideClient;
-use glide_core::command_request::SimpleRoutes;
-use glide_core::command_request::{Routes, SlotTypes};
-use glide_core::connection_request;
-use glide_core::errors;
-use glide_core::errors::RequestErrorType;
+use glide_core::cluster_scan_container::remove_scan_state_cursor;
+use glide_core::command_request::{
+    ClusterScan, ScriptInvocation, Transaction, UpdateConnectionPassword,
+};
 use glide_core::request_type::RequestType;
-use glide_core::ConnectionRequest;
-use protobuf::Message;
-use redis::cluster_routing::{
-    MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
+use glide_ffi::{
+    bytes_from_raw, get_cluster_scan, get_command, get_route, get_transaction, parse_protobuf,
+    Callbacks, ClientAdapter, CommandResponse, CommandResult, FailureCallback, PushCallback,
+    ResponseType, RuntimeConfig, SuccessCallback,
 };
-use redis::cluster_routing::{ResponsePolicy, Routable};
-use redis::{Cmd, RedisResult, Value};
+use redis::cluster_routing::RoutingInfo;
+use redis::{Cmd, Pipeline, RedisResult, Value};
 use std::slice::from_raw_parts;
+use std::time::Duration;
 use std::{
-    ffi::{c_void, CString},
-    mem,
-    os::raw::{c_char, c_double, c_long, c_ulong},
+    ffi::{c_void, CStr, CString},
+    os::raw::{c_char, c_ulong},
 };
-use tokio::runtime::Builder;
-use tokio::runtime::Runtime;
-
-/// The struct represents the response of the command.
-///
-/// It will have one of the value populated depending on the return type of the command.
-///
-/// The struct is freed by the external caller by using `free_command_response` to avoid memory leaks.
-/// TODO: Add a type enum to validate what type of response is being sent in the CommandResponse.
-#[repr(C)]
-#[derive(Debug)]
-pub struct CommandResponse {
-    response_type: ResponseType,
-    int_value: c_long,
-    float_value: c_double,
-    bool_value: bool,
-
-    /// Below two values are related to each other.
-    /// `string_value` represents the string.
-    /// `string_value_len` represents the length of the string.
-    string_value: *mut c_char,
-    string_value_len: c_long,
-
-    /// Below two values are related to each other.
-    /// `array_value` represents the array of CommandResponse.
-    /// `array_value_len` represents the length of the array.
-    array_value: *mut CommandResponse,
-    array_value_len: c_long,
-
-    /// Below two values represent the Map structure inside CommandResponse.
-    /// The map is transformed into an array of (map_key: CommandResponse, map_value: CommandResponse) and passed to Go.
-    /// These are represented as pointers as the map can be null (optionally present).
-    map_key: *mut CommandResponse,
-    map_value: *mut CommandResponse,
-
-    /// Below two values are related to each other.
-    /// `sets_value` represents the set of CommandResponse.
-    /// `sets_value_len` represents the length of the set.
-    sets_value: *mut CommandResponse,
-    sets_value_len: c_long,
-}
-
-impl Default for CommandResponse {
-    fn default() -> Self {
-        CommandResponse {
-            response_type: ResponseType::default(),
-            int_value: 0,
-            float_value: 0.0,
-            bool_value: false,
-            string_value: std::ptr::null_mut(),
-            string_value_len: 0,
-            array_value: std::ptr::null_mut(),
-            array_value_len: 0,
-            map_key: std::ptr::null_mut(),
-            map_value: std::ptr::null_mut(),
-            sets_value: std::ptr::null_mut(),
-            sets_value_len: 0,
-        }
-    }
-}
-
-#[repr(C)]
-#[derive(Debug, Default)]
-pub enum ResponseType {
-    #[default]
-    Null = 0,
-    Int = 1,
-    Float = 2,
-    Bool = 3,
-    String = 4,
-    Array = 5,
-    Map = 6,
-    Sets = 7,
-}
-
-/// Success callback that is called when a command succeeds.
-///
-/// The success callback needs to copy the given string synchronously, since it will be dropped by Rust once the callback returns. The callback should be offloaded to a separate thread in order not to exhaust the client's thread pool.
-///
-/// `index_ptr` is a baton-pass back to the caller language to uniquely identify the promise.
-/// `message` is the value returned by the command. The 'message' is managed by Rust and is freed when the callback returns control back to the caller.
-pub type SuccessCallback =
-    unsafe extern "C" fn(index_ptr: usize, message: *const CommandResponse) -> ();
-
-/// Failure callback that is called when a command fails.
-///
-/// The failure callback needs to copy the given string synchronously, since it will be dropped by Rust once the callback returns. The callback should be offloaded to a separate thread in order not to exhaust the client's thread pool.
-///
-/// `index_ptr` is a baton-pass back to the caller language to uniquely identify the promise.
-/// `error_message` is the error message returned by server for the failed command. The 'error_message' is managed by Rust and is freed when the callback returns control back to the caller.
-/// `error_type` is the type of error returned by glide-core, depending on the `RedisError` returned.
-pub type FailureCallback = unsafe extern "C" fn(
-    index_ptr: usize,
-    error_message: *const c_char,
-    error_type: RequestErrorType,
-) -> ();
 
 /// The connection response.
 ///
@@ -129,44 +32,6 @@ pub struct ConnectionResponse {
     connection_error_message: *const c_char,
 }
 
-/// A `GlideClient` adapter.
-// TODO: Remove allow(dead_code) once connection logic is implemented
-#[allow(dead_code)]
-pub struct ClientAdapter {
-    client: GlideClient,
-    success_callback: SuccessCallback,
-    failure_callback: FailureCallback,
-    runtime: Runtime,
-}
-
-fn create_client_internal(
-    connection_request_bytes: &[u8],
-    success_callback: SuccessCallback,
-    failure_callback: FailureCallback,
-) -> Result<ClientAdapter, String> {
-    let request = connection_request::ConnectionRequest::parse_from_bytes(connection_request_bytes)
-        .map_err(|err| err.to_string())?;
-    // TODO: optimize this using multiple threads instead of a single worker thread (e.g. by pinning each go thread to a rust thread)
-    let runtime = Builder::new_multi_thread()
-        .enable_all()
-        .worker_threads(1)
-        .thread_name("Valkey-GLIDE Go thread")
-        .build()
-        .map_err(|err| {
-            let redis_error = err.into();
-            errors::error_message(&redis_error)
-        })?;
-    let client = runtime
-        .block_on(GlideClient::new(ConnectionRequest::from(request), None))
-        .map_err(|err| err.to_string())?;
-    Ok(ClientAdapter {
-        client,
-        success_callback,
-        failure_callback,
-        runtime,
-    })
-}
-
 /// Creates a new `ClientAdapter` with a new `GlideClient` configured using a Protobuf `ConnectionRequest`.
 ///
 /// The returned `ConnectionResponse` will only be freed by calling [`free_connection_response`].
@@ -175,6 +40,9 @@ fn create_client_internal(
 /// `connection_request_len` is the number of bytes in `connection_request_bytes`.
 /// `success_callback` is the callback that will be called when a command succeeds.
 /// `failure_callback` is the callback that will be called when a command fails.
+/// `push_callback` is the callback that will be called for every push notification, or null if push notifications aren't needed. See [`PushCallback`].
+/// `worker_threads` is the number of worker threads of the runtime shared by all the Go clients, or 0 for a worker thread per CPU.
+/// It only applies when the runtime is started by the first client, and can't be changed while the runtime is running.
 ///
 /// # Safety
 ///
@@ -182,7 +50,7 @@ fn create_client_internal(
 /// * `connection_request_len` must not be greater than the length of the connection request bytes array. It must also not be greater than the max value of a signed pointer-sized integer.
 /// * The `conn_ptr` pointer in the returned `ConnectionResponse` must live while the client is open/active and must be explicitly freed by calling [`close_client`].
 /// * The `connection_error_message` pointer in the returned `ConnectionResponse` must live until the returned `ConnectionResponse` pointer is passed to [`free_connection_response`].
-/// * Both the `success_callback` and `failure_callback` function pointers need to live while the client is open/active. The caller is responsible for freeing both callbacks.
+/// * The `success_callback`, `failure_callback` and `push_callback` function pointers need to live while the client is open/active. The caller is responsible for freeing the callbacks.
 // TODO: Consider making this async
 #[no_mangle]
 pub unsafe extern "C" fn create_client(
@@ -190,21 +58,32 @@ pub unsafe extern "C" fn create_client(
     connection_request_len: usize,
     success_callback: SuccessCallback,
     failure_callback: FailureCallback,
+    push_callback: Option<PushCallback>,
+    worker_threads: usize,
 ) -> *const ConnectionResponse {
-    let request_bytes =
-        unsafe { std::slice::from_raw_parts(connection_request_bytes, connection_request_len) };
-    let response = match create_client_internal(request_bytes, success_callback, failure_callback) {
-        Err(err) => ConnectionResponse {
-            conn_ptr: std::ptr::null(),
-            connection_error_message: CString::into_raw(
-                CString::new(err).expect("Couldn't convert error message to CString"),
-            ),
-        },
-        Ok(client) => ConnectionResponse {
-            conn_ptr: Box::into_raw(Box::new(client)) as *const c_void,
-            connection_error_message: std::ptr::null(),
-        },
+    let request_bytes = unsafe { bytes_from_raw(connection_request_bytes, connection_request_len) };
+    let callbacks = Callbacks {
+        success: success_callback,
+        failure: failure_callback,
+    };
+    // All the Go clients share a single runtime.
+    let runtime_config = RuntimeConfig {
+        thread_name: "Valkey-GLIDE Go thread".to_string(),
+        worker_threads: (worker_threads > 0).then_some(worker_threads),
     };
+    let response =
+        match ClientAdapter::create(request_bytes, callbacks, &runtime_config, push_callback) {
+            Err(err) => ConnectionResponse {
+                conn_ptr: std::ptr::null(),
+                connection_error_message: CString::into_raw(
+                    CString::new(err).expect("Couldn't convert error message to CString"),
+                ),
+            },
+            Ok(client_ptr) => ConnectionResponse {
+                conn_ptr: client_ptr,
+                connection_error_message: std::ptr::null(),
+            },
+        };
     Box::into_raw(Box::new(response))
 }
 
@@ -225,8 +104,29 @@ pub unsafe extern "C" fn create_client(
 // TODO: Ensure safety when command has not completed yet
 #[no_mangle]
 pub unsafe extern "C" fn close_client(client_adapter_ptr: *const c_void) {
-    assert!(!client_adapter_ptr.is_null());
-    drop(unsafe { Box::from_raw(client_adapter_ptr as *mut ClientAdapter) });
+    unsafe { ClientAdapter::destroy(client_adapter_ptr) };
+}
+
+/// Gracefully closes the given `GlideClient`, without freeing it.
+///
+/// New commands fail immediately. Inflight commands are given up to `drain_timeout_ms` milliseconds to complete, after which they fail with a `Cancelled` error type.
+/// A `drain_timeout_ms` of 0 waits for all inflight commands to complete. Once the client is drained, pending OpenTelemetry spans are flushed,
+/// the connections are closed, and the [`SuccessCallback`] is called with `channel` and a null `CommandResponse`.
+///
+/// # Safety
+///
+/// * `client_adapter_ptr` must be obtained from the `ConnectionResponse` returned from [`create_client`].
+/// * `client_adapter_ptr` must be valid until the success callback is called. [`close_client`] should be called afterwards to free the client.
+#[no_mangle]
+pub unsafe extern "C" fn drain_client(
+    client_adapter_ptr: *const c_void,
+    channel: usize,
+    drain_timeout_ms: u32,
+) {
+    let client_adapter = unsafe { ClientAdapter::from_ptr(client_adapter_ptr) };
+    let drain_timeout =
+        (drain_timeout_ms != 0).then(|| Duration::from_millis(drain_timeout_ms.into()));
+    client_adapter.drain(channel, drain_timeout);
 }
 
 /// Deallocates a `ConnectionResponse`.
@@ -271,78 +171,26 @@ pub extern "C" fn get_response_type_string(response_type: ResponseType) -> *cons
         ResponseType::Array => c"Array",
         ResponseType::Map => c"Map",
         ResponseType::Sets => c"Sets",
+        ResponseType::BigNumber => c"BigNumber",
+        ResponseType::Attribute => c"Attribute",
+        ResponseType::Push => c"Push",
     };
     c_str.as_ptr()
 }
 
-/// Deallocates a `CommandResponse`.
-///
-/// This function also frees the contained string_value and array_value. If the string_value and array_value are null pointers, the function returns and only the `CommandResponse` is freed.
+/// Deallocates a `CommandResponse`, along with its nested values. Null pointers are ignored.
 ///
 /// # Safety
 ///
 /// * `free_command_response` can only be called once per `CommandResponse`. Calling it twice is undefined behavior, since the address will be freed twice.
-/// * `command_response_ptr` must be obtained from the `CommandResponse` returned in [`SuccessCallback`] from [`command`].
+/// * `command_response_ptr` must be obtained from the `CommandResponse` returned in [`SuccessCallback`] from [`command`], or in [`PushCallback`].
 /// * `command_response_ptr` must be valid until `free_command_response` is called.
 #[no_mangle]
 pub unsafe extern "C" fn free_command_response(command_response_ptr: *mut CommandResponse) {
-    if !command_response_ptr.is_null() {
-        let command_response = unsafe { Box::from_raw(command_response_ptr) };
-        free_command_response_elements(*command_response);
-    }
+    unsafe { glide_ffi::free_command_response(command_response_ptr) };
 }
 
-/// Frees the nested elements of `CommandResponse`.
-/// TODO: Add a test case to check for memory leak.
-///
-/// # Safety
-///
-/// * `free_command_response_elements` can only be called once per `CommandResponse`. Calling it twice is undefined behavior, since the address will be freed twice.
-/// * The contained `string_value` must be obtained from the `CommandResponse` returned in [`SuccessCallback`] from [`command`].
-/// * The contained `string_value` must be valid until `free_command_response` is called and it must outlive the `CommandResponse` that contains it.
-/// * The contained `array_value` must be obtained from the `CommandResponse` returned in [`SuccessCallback`] from [`command`].
-/// * The contained `array_value` must be valid until `free_command_response` is called and it must outlive the `CommandResponse` that contains it.
-/// * The contained `map_key` must be obtained from the `CommandResponse` returned in [`SuccessCallback`] from [`command`].
-/// * The contained `map_key` must be valid until `free_command_response` is called and it must outlive the `CommandResponse` that contains it.
-/// * The contained `map_value` must be obtained from the `CommandResponse` returned in [`SuccessCallback`] from [`command`].
-/// * The contained `map_value` must be valid until `free_command_response` is called and it must outlive the `CommandResponse` that contains it.
-fn free_command_response_elements(command_response: CommandResponse) {
-    let string_value = command_response.string_value;
-    let string_value_len = command_response.string_value_len;
-    let array_value = command_response.array_value;
-    let array_value_len = command_response.array_value_len;
-    let map_key = command_response.map_key;
-    let map_value = command_response.map_value;
-    let sets_value = command_response.sets_value;
-    let sets_value_len = command_response.sets_value_len;
-    if !string_value.is_null() {
-        let len = string_value_len as usize;
-        unsafe { Vec::from_raw_parts(string_value, len, len) };
-    }
-    if !array_value.is_null() {
-        let len = array_value_len as usize;
-        let vec = unsafe { Vec::from_raw_parts(array_value, len, len) };
-        for element in vec.into_iter() {
-            free_command_response_elements(element);
-        }
-    }
-    if !map_key.is_null() {
-        unsafe { free_command_response(map_key) };
-    }
-    if !map_value.is_null() {
-        unsafe { free_command_response(map_value) };
-    }
-    if !sets_value.is_null() {
-        let len = sets_value_len as usize;
-        let vec = unsafe { Vec::from_raw_parts(sets_value, len, len) };
-        for element in vec.into_iter() {
-            free_command_response_elements(element);
-        }
-    }
-}
-
-/// Frees the error_message received on a command failure.
-/// TODO: Add a test case to check for memory leak.
+/// Frees the error_message, error_code or error_address received on a command failure.
 ///
 /// # Panics
 ///
@@ -355,7 +203,7 @@ fn free_command_response_elements(command_response: CommandResponse) {
 #[no_mangle]
 pub unsafe extern "C" fn free_error_message(error_message: *mut c_char) {
     assert!(!error_message.is_null());
-    drop(unsafe { CString::from_raw(error_message as *mut c_char) });
+    unsafe { glide_ffi::free_error_message(error_message) };
 }
 
 /// Converts a double pointer to a vec.
@@ -380,121 +228,28 @@ unsafe fn convert_double_pointer_to_vec<'a>(
     result
 }
 
-fn convert_vec_to_pointer<T>(mut vec: Vec<T>) -> (*mut T, c_long) {
-    vec.shrink_to_fit();
-    let vec_ptr = vec.as_mut_ptr();
-    let len = vec.len() as c_long;
-    mem::forget(vec);
-    (vec_ptr, len)
-}
-
-/// TODO: Avoid the use of expect and unwrap in the code and add a common error handling mechanism.
-fn valkey_value_to_command_response(value: Value) -> RedisResult<CommandResponse> {
-    let mut command_response = CommandResponse::default();
-    let result: RedisResult<CommandResponse> = match value {
-        Value::Nil => Ok(command_response),
-        Value::SimpleString(text) => {
-            let vec: Vec<u8> = text.into_bytes();
-            let (vec_ptr, len) = convert_vec_to_pointer(vec);
-            command_response.string_value = vec_ptr as *mut c_char;
-            command_response.string_value_len = len;
-            command_response.response_type = ResponseType::String;
-            Ok(command_response)
-        }
-        Value::BulkString(text) => {
-            let (vec_ptr, len) = convert_vec_to_pointer(text);
-            command_response.string_value = vec_ptr as *mut c_char;
-            command_response.string_value_len = len;
-            command_response.response_type = ResponseType::String;
-            Ok(command_response)
-        }
-        Value::VerbatimString { format: _, text } => {
-            let vec: Vec<u8> = text.into_bytes();
-            let (vec_ptr, len) = convert_vec_to_pointer(vec);
-            command_response.string_value = vec_ptr as *mut c_char;
-            command_response.string_value_len = len;
-            command_response.response_type = ResponseType::String;
-            Ok(command_response)
-        }
-        Value::Okay => {
-            let vec: Vec<u8> = String::from("OK").into_bytes();
-            let (vec_ptr, len) = convert_vec_to_pointer(vec);
-            command_response.string_value = vec_ptr as *mut c_char;
-            command_response.string_value_len = len;
-            command_response.response_type = ResponseType::String;
-            Ok(command_response)
-        }
-        Value::Int(num) => {
-            command_response.int_value = num;
-            command_response.response_type = ResponseType::Int;
-            Ok(command_response)
-        }
-        Value::Double(num) => {
-            command_response.float_value = num;
-            command_response.response_type = ResponseType::Float;
-            Ok(command_response)
-        }
-        Value::Boolean(boolean) => {
-            command_response.bool_value = boolean;
-            command_response.response_type = ResponseType::Bool;
-            Ok(command_response)
-        }
-        Value::Array(array) => {
-            let vec: Vec<CommandResponse> = array
-                .into_iter()
-                .map(|v| {
-                    valkey_value_to_command_response(v)
-                        .expect("Value couldn't be converted to CommandResponse")
-                })
-                .collect();
-            let (vec_ptr, len) = convert_vec_to_pointer(vec);
-            command_response.array_value = vec_ptr;
-            command_response.array_value_len = len;
-            command_response.response_type = ResponseType::Array;
-            Ok(command_response)
-        }
-        Value::Map(map) => {
-            let result: Vec<CommandResponse> = map
-                .into_iter()
-                .map(|(key, val)| {
-                    let mut map_response = CommandResponse::default();
-
-                    let map_key = valkey_value_to_command_response(key)
-                        .expect("Value couldn't be converted to CommandResponse");
-                    map_response.map_key = Box::into_raw(Box::new(map_key));
-
-                    let map_val = valkey_value_to_command_response(val)
-                        .expect("Value couldn't be converted to CommandResponse");
-                    map_response.map_value = Box::into_raw(Box::new(map_val));
-
-                    map_response
-                })
-                .collect::<Vec<_>>();
-
-            let (vec_ptr, len) = convert_vec_to_pointer(result);
-            command_response.array_value = vec_ptr;
-            command_response.array_value_len = len;
-            command_response.response_type = ResponseType::Map;
-            Ok(command_response)
-        }
-        Value::Set(array) => {
-            let vec: Vec<CommandResponse> = array
-                .into_iter()
-                .map(|v| {
-                    valkey_value_to_command_response(v)
-                        .expect("Value couldn't be converted to CommandResponse")
-                })
-                .collect();
-            let (vec_ptr, len) = convert_vec_to_pointer(vec);
-            command_response.sets_value = vec_ptr;
-            command_response.sets_value_len = len;
-            command_response.response_type = ResponseType::Sets;
-            Ok(command_response)
-        }
-        // TODO: Add support for other return types.
-        _ => todo!(),
-    };
-    result
+/// Builds a command from its arguments, and parses its Protobuf `Routes` object.
+///
+/// # Safety
+///
+/// * `args` and `args_len` must point to `arg_count` elements, and each argument must point to the matching number of bytes.
+/// * `route_bytes` must point to `route_bytes_len` consecutive properly initialized bytes, unless `route_bytes_len` is 0.
+unsafe fn parse_command(
+    command_type: RequestType,
+    arg_count: c_ulong,
+    args: *const usize,
+    args_len: *const c_ulong,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
+) -> RedisResult<(Cmd, Option<RoutingInfo>)> {
+    let arg_vec =
+        unsafe { convert_double_pointer_to_vec(args as *const *const c_void, arg_count, args_len) };
+    let cmd = get_command(command_type, arg_vec)?;
+    let route = get_route(
+        unsafe { bytes_from_raw(route_bytes, route_bytes_len) },
+        Some(&cmd),
+    )?;
+    Ok((cmd, route))
 }
 
 // TODO: Finish documentation
@@ -514,128 +269,360 @@ pub unsafe extern "C" fn command(
     route_bytes: *const u8,
     route_bytes_len: usize,
 ) {
-    let client_adapter =
-        unsafe { Box::leak(Box::from_raw(client_adapter_ptr as *mut ClientAdapter)) };
-    // The safety of this needs to be ensured by the calling code. Cannot dispose of the pointer before
-    // all operations have completed.
-    let ptr_address = client_adapter_ptr as usize;
-
-    let arg_vec =
-        unsafe { convert_double_pointer_to_vec(args as *const *const c_void, arg_count, args_len) };
-
-    let mut client_clone = client_adapter.client.clone();
+    let client_adapter = unsafe { ClientAdapter::from_ptr(client_adapter_ptr) };
 
     // Create the command outside of the task to ensure that the command arguments passed
     // from "go" are still valid
-    let mut cmd = command_type
-        .get_command()
-        .expect("Couldn't fetch command type");
-    for command_arg in arg_vec {
-        cmd.arg(command_arg);
+    let request = unsafe {
+        parse_command(
+            command_type,
+            arg_count,
+            args,
+            args_len,
+            route_bytes,
+            route_bytes_len,
+        )
+    };
+    let (cmd, route) = match request {
+        Ok(request) => request,
+        Err(err) => return client_adapter.report(channel, Err(err)),
+    };
+
+    client_adapter.execute(channel, |mut client| async move {
+        client.send_command(&cmd, route).await
+    });
+}
+
+/// Executes a command, and blocks the calling thread until it completes.
+///
+/// The arguments are the same as in [`command`]. Instead of calling the callbacks, the result is returned,
+/// and must be freed with [`free_command_result`]. The call is bounded by the client's request timeout.
+///
+/// Blocking calls can be made concurrently from any number of threads, but not from within the client's callbacks.
+///
+/// # Safety
+///
+/// * `client_adapter_ptr` must be obtained from the `ConnectionResponse` returned from [`create_client`], and must be valid until this function returns.
+/// * `args` and `args_len` must point to `arg_count` elements, and each argument must point to the matching number of bytes.
+/// * `route_bytes` must point to `route_bytes_len` consecutive properly initialized bytes, unless `route_bytes_len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn command_blocking(
+    client_adapter_ptr: *const c_void,
+    command_type: RequestType,
+    arg_count: c_ulong,
+    args: *const usize,
+    args_len: *const c_ulong,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
+) -> *mut CommandResult {
+    let client_adapter = unsafe { ClientAdapter::from_ptr(client_adapter_ptr) };
+    let result = unsafe {
+        parse_command(
+            command_type,
+            arg_count,
+            args,
+            args_len,
+            route_bytes,
+            route_bytes_len,
+        )
     }
+    .and_then(|(cmd, route)| {
+        client_adapter
+            .execute_blocking(|mut client| async move { client.send_command(&cmd, route).await })
+    });
+    Box::into_raw(Box::new(CommandResult::from(result)))
+}
 
-    let r_bytes = unsafe { std::slice::from_raw_parts(route_bytes, route_bytes_len) };
-
-    let route = Routes::parse_from_bytes(r_bytes).unwrap();
-
-    client_adapter.runtime.spawn(async move {
-        let result = client_clone
-            .send_command(&cmd, get_route(route, Some(&cmd)))
-            .await;
-        let client_adapter = unsafe { Box::leak(Box::from_raw(ptr_address as *mut ClientAdapter)) };
-        let value = match result {
-            Ok(value) => value,
-            Err(err) => {
-                let message = errors::error_message(&err);
-                let error_type = errors::error_type(&err);
-
-                let c_err_str = CString::into_raw(
-                    CString::new(message).expect("Couldn't convert error message to CString"),
-                );
-                unsafe { (client_adapter.failure_callback)(channel, c_err_str, error_type) };
-                return;
-            }
-        };
+/// Deallocates a `CommandResult` returned from one of the blocking calls, along with its response or error message.
+///
+/// # Safety
+///
+/// * `command_result_ptr` must be obtained from [`command_blocking`], [`transaction_blocking`] or [`invoke_script_blocking`].
+/// * `free_command_result` can only be called once per `CommandResult`.
+#[no_mangle]
+pub unsafe extern "C" fn free_command_result(command_result_ptr: *mut CommandResult) {
+    unsafe { glide_ffi::free_command_result(command_result_ptr) };
+}
 
-        let result: RedisResult<CommandResponse> = valkey_value_to_command_response(value);
-
-        unsafe {
-            match result {
-                Ok(message) => {
-                    (client_adapter.success_callback)(channel, Box::into_raw(Box::new(message)))
-                }
-                Err(err) => {
-                    let message = errors::error_message(&err);
-                    let error_type = errors::error_type(&err);
-
-                    let c_err_str = CString::into_raw(
-                        CString::new(message).expect("Couldn't convert error message to CString"),
-                    );
-                    (client_adapter.failure_callback)(channel, c_err_str, error_type);
-                }
-            };
-        }
+/// Parses a Protobuf request, followed by its Protobuf `Routes` object.
+///
+/// # Safety
+///
+/// * `request_bytes` and `route_bytes` must point to `request_bytes_len` and `route_bytes_len` consecutive properly initialized bytes, unless their length is 0.
+unsafe fn parse_routed_request<T: protobuf::MessageFull>(
+    request_bytes: *const u8,
+    request_bytes_len: usize,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
+) -> RedisResult<(T, Option<RoutingInfo>)> {
+    let request = parse_protobuf(unsafe { bytes_from_raw(request_bytes, request_bytes_len) })?;
+    let route = get_route(
+        unsafe { bytes_from_raw(route_bytes, route_bytes_len) },
+        None,
+    )?;
+    Ok((request, route))
+}
+
+/// Parses a Protobuf `Transaction` object into a pipeline, followed by its Protobuf `Routes` object.
+///
+/// # Safety
+///
+/// * `transaction_bytes` and `route_bytes` must point to `transaction_bytes_len` and `route_bytes_len` consecutive properly initialized bytes, unless their length is 0.
+unsafe fn parse_transaction(
+    transaction_bytes: *const u8,
+    transaction_bytes_len: usize,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
+) -> RedisResult<(Pipeline, Option<RoutingInfo>)> {
+    let (transaction, route) = unsafe {
+        parse_routed_request::<Transaction>(
+            transaction_bytes,
+            transaction_bytes_len,
+            route_bytes,
+            route_bytes_len,
+        )
+    }?;
+    Ok((get_transaction(&transaction)?, route))
+}
+
+/// Executes a transaction.
+///
+/// `transaction_bytes` is a Protobuf `Transaction` object, and `route_bytes` is a Protobuf `Routes` object, which may be empty.
+/// The result is an array with the response of each command in the transaction, or a null response if the transaction was aborted by a `WATCH`.
+/// The result is passed to the [`SuccessCallback`] or the [`FailureCallback`] along with `channel`.
+///
+/// # Safety
+///
+/// * `client_adapter_ptr` must be obtained from the `ConnectionResponse` returned from [`create_client`], and must be valid until the callback is called.
+/// * `transaction_bytes` must point to `transaction_bytes_len` consecutive properly initialized bytes, unless `transaction_bytes_len` is 0.
+/// * `route_bytes` must point to `route_bytes_len` consecutive properly initialized bytes, unless `route_bytes_len` is 0.
+/// * The byte arrays are owned by the caller, and are no longer accessed once this function returns.
+#[no_mangle]
+pub unsafe extern "C" fn transaction(
+    client_adapter_ptr: *const c_void,
+    channel: usize,
+    transaction_bytes: *const u8,
+    transaction_bytes_len: usize,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
+) {
+    let client_adapter = unsafe { ClientAdapter::from_ptr(client_adapter_ptr) };
+
+    let request = unsafe {
+        parse_transaction(
+            transaction_bytes,
+            transaction_bytes_len,
+            route_bytes,
+            route_bytes_len,
+        )
+    };
+    let (pipeline, route) = match request {
+        Ok(request) => request,
+        Err(err) => return client_adapter.report(channel, Err(err)),
+    };
+
+    client_adapter.execute(channel, |mut client| async move {
+        client.send_transaction(&pipeline, route).await
     });
 }
 
-fn get_route(route: Routes, cmd: Option<&Cmd>) -> Option<RoutingInfo> {
-    use glide_core::command_request::routes::Value;
-    let route = route.value?;
-    let get_response_policy = |cmd: Option<&Cmd>| {
-        cmd.and_then(|cmd| {
-            cmd.command()
-                .and_then(|cmd| ResponsePolicy::for_command(&cmd))
+/// Executes a transaction, and blocks the calling thread until it completes.
+///
+/// The arguments are the same as in [`transaction`]. Instead of calling the callbacks, the result is returned,
+/// and must be freed with [`free_command_result`]. See [`command_blocking`].
+///
+/// # Safety
+///
+/// * `client_adapter_ptr` must be obtained from the `ConnectionResponse` returned from [`create_client`], and must be valid until this function returns.
+/// * `transaction_bytes` and `route_bytes` must point to `transaction_bytes_len` and `route_bytes_len` consecutive properly initialized bytes, unless their length is 0.
+#[no_mangle]
+pub unsafe extern "C" fn transaction_blocking(
+    client_adapter_ptr: *const c_void,
+    transaction_bytes: *const u8,
+    transaction_bytes_len: usize,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
+) -> *mut CommandResult {
+    let client_adapter = unsafe { ClientAdapter::from_ptr(client_adapter_ptr) };
+    let result = unsafe {
+        parse_transaction(
+            transaction_bytes,
+            transaction_bytes_len,
+            route_bytes,
+            route_bytes_len,
+        )
+    }
+    .and_then(|(pipeline, route)| {
+        client_adapter.execute_blocking(|mut client| async move {
+            client.send_transaction(&pipeline, route).await
         })
+    });
+    Box::into_raw(Box::new(CommandResult::from(result)))
+}
+
+async fn run_script(
+    mut client: GlideClient,
+    script: ScriptInvocation,
+    route: Option<RoutingInfo>,
+) -> RedisResult<Value> {
+    let keys: Vec<&[u8]> = script.keys.iter().map(|key| key.as_ref()).collect();
+    let args: Vec<&[u8]> = script.args.iter().map(|arg| arg.as_ref()).collect();
+    client
+        .invoke_script(&script.hash, &keys, &args, route)
+        .await
+}
+
+/// Invokes a Lua script by its SHA1 hash, loading the script if it isn't cached by the server yet.
+///
+/// `script_bytes` is a Protobuf `ScriptInvocation` object, and `route_bytes` is a Protobuf `Routes` object, which may be empty.
+/// The script must have been stored with the scripts container of glide-core. The result is passed to the
+/// [`SuccessCallback`] or the [`FailureCallback`] along with `channel`.
+///
+/// # Safety
+///
+/// * `client_adapter_ptr` must be obtained from the `ConnectionResponse` returned from [`create_client`], and must be valid until the callback is called.
+/// * `script_bytes` must point to `script_bytes_len` consecutive properly initialized bytes, unless `script_bytes_len` is 0.
+/// * `route_bytes` must point to `route_bytes_len` consecutive properly initialized bytes, unless `route_bytes_len` is 0.
+/// * The byte arrays are owned by the caller, and are no longer accessed once this function returns.
+#[no_mangle]
+pub unsafe extern "C" fn invoke_script(
+    client_adapter_ptr: *const c_void,
+    channel: usize,
+    script_bytes: *const u8,
+    script_bytes_len: usize,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
+) {
+    let client_adapter = unsafe { ClientAdapter::from_ptr(client_adapter_ptr) };
+
+    let request = unsafe {
+        parse_routed_request::<ScriptInvocation>(
+            script_bytes,
+            script_bytes_len,
+            route_bytes,
+            route_bytes_len,
+        )
     };
-    match route {
-        Value::SimpleRoutes(simple_route) => {
-            let simple_route = simple_route.enum_value().unwrap();
-            match simple_route {
-                SimpleRoutes::AllNodes => Some(RoutingInfo::MultiNode((
-                    MultipleNodeRoutingInfo::AllNodes,
-                    get_response_policy(cmd),
-                ))),
-                SimpleRoutes::AllPrimaries => Some(RoutingInfo::MultiNode((
-                    MultipleNodeRoutingInfo::AllMasters,
-                    get_response_policy(cmd),
-                ))),
-                SimpleRoutes::Random => {
-                    Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
-                }
-            }
-        }
-        Value::SlotKeyRoute(slot_key_route) => Some(RoutingInfo::SingleNode(
-            SingleNodeRoutingInfo::SpecificNode(Route::new(
-                redis::cluster_topology::get_slot(slot_key_route.slot_key.as_bytes()),
-                get_slot_addr(&slot_key_route.slot_type),
-            )),
-        )),
-        Value::SlotIdRoute(slot_id_route) => Some(RoutingInfo::SingleNode(
-            SingleNodeRoutingInfo::SpecificNode(Route::new(
-                slot_id_route.slot_id as u16,
-                get_slot_addr(&slot_id_route.slot_type),
-            )),
-        )),
-        Value::ByAddressRoute(by_address_route) => match u16::try_from(by_address_route.port) {
-            Ok(port) => Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
-                host: by_address_route.host.to_string(),
-                port,
-            })),
-            Err(_) => {
-                // TODO: Handle error propagation.
-                None
-            }
-        },
-        _ => panic!("unknown route type"),
+    let (script, route) = match request {
+        Ok(request) => request,
+        Err(err) => return client_adapter.report(channel, Err(err)),
+    };
+
+    client_adapter.execute(channel, |client| run_script(client, script, route));
+}
+
+/// Invokes a Lua script, and blocks the calling thread until it completes.
+///
+/// The arguments are the same as in [`invoke_script`]. Instead of calling the callbacks, the result is returned,
+/// and must be freed with [`free_command_result`]. See [`command_blocking`].
+///
+/// # Safety
+///
+/// * `client_adapter_ptr` must be obtained from the `ConnectionResponse` returned from [`create_client`], and must be valid until this function returns.
+/// * `script_bytes` and `route_bytes` must point to `script_bytes_len` and `route_bytes_len` consecutive properly initialized bytes, unless their length is 0.
+#[no_mangle]
+pub unsafe extern "C" fn invoke_script_blocking(
+    client_adapter_ptr: *const c_void,
+    script_bytes: *const u8,
+    script_bytes_len: usize,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
+) -> *mut CommandResult {
+    let client_adapter = unsafe { ClientAdapter::from_ptr(client_adapter_ptr) };
+    let result = unsafe {
+        parse_routed_request::<ScriptInvocation>(
+            script_bytes,
+            script_bytes_len,
+            route_bytes,
+            route_bytes_len,
+        )
     }
+    .and_then(|(script, route)| {
+        client_adapter.execute_blocking(|client| run_script(client, script, route))
+    });
+    Box::into_raw(Box::new(CommandResult::from(result)))
 }
 
-fn get_slot_addr(slot_type: &protobuf::EnumOrUnknown<SlotTypes>) -> SlotAddr {
-    slot_type
-        .enum_value()
-        .map(|slot_type| match slot_type {
-            SlotTypes::Primary => SlotAddr::Master,
-            SlotTypes::Replica => SlotAddr::ReplicaRequired,
-        })
-        .expect("Received unexpected slot id type")
+/// Runs a single iteration of a cluster-wide `SCAN`.
+///
+/// `scan_bytes` is a Protobuf `ClusterScan` object. An empty cursor starts a new scan. The result is an array of
+/// the next cursor and an array of the keys found in this iteration. The cursor is `"finished"` once all slots were scanned.
+/// Cursors that weren't scanned to completion must be released with [`remove_cluster_scan_cursor`].
+/// The result is passed to the [`SuccessCallback`] or the [`FailureCallback`] along with `channel`.
+///
+/// # Safety
+///
+/// * `client_adapter_ptr` must be obtained from the `ConnectionResponse` returned from [`create_client`] for a cluster client,
+///   and must be valid until the callback is called.
+/// * `scan_bytes` must point to `scan_bytes_len` consecutive properly initialized bytes, unless `scan_bytes_len` is 0.
+///   The array is owned by the caller, and is no longer accessed once this function returns.
+#[no_mangle]
+pub unsafe extern "C" fn cluster_scan(
+    client_adapter_ptr: *const c_void,
+    channel: usize,
+    scan_bytes: *const u8,
+    scan_bytes_len: usize,
+) {
+    let client_adapter = unsafe { ClientAdapter::from_ptr(client_adapter_ptr) };
+
+    let request =
+        parse_protobuf::<ClusterScan>(unsafe { bytes_from_raw(scan_bytes, scan_bytes_len) })
+            .and_then(get_cluster_scan);
+    let (scan_state, scan_args) = match request {
+        Ok(request) => request,
+        Err(err) => return client_adapter.report(channel, Err(err)),
+    };
+
+    client_adapter.execute(channel, |mut client| async move {
+        client.cluster_scan(&scan_state, scan_args).await
+    });
+}
+
+/// Releases the scan state of a cluster scan cursor returned from [`cluster_scan`].
+///
+/// # Safety
+///
+/// * `cursor` must be a valid null-terminated C string, owned by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn remove_cluster_scan_cursor(cursor: *const c_char) {
+    assert!(!cursor.is_null());
+    let cursor = unsafe { CStr::from_ptr(cursor) };
+    remove_scan_state_cursor(cursor.to_string_lossy().into_owned());
+}
+
+/// Replaces the password used to authenticate new connections.
+///
+/// `request_bytes` is a Protobuf `UpdateConnectionPassword` object. An unset password removes the password.
+/// If `immediate_auth` is set, existing connections are re-authenticated with the new password before the request completes.
+/// The result is passed to the [`SuccessCallback`] or the [`FailureCallback`] along with `channel`.
+///
+/// # Safety
+///
+/// * `client_adapter_ptr` must be obtained from the `ConnectionResponse` returned from [`create_client`], and must be valid until the callback is called.
+/// * `request_bytes` must point to `request_bytes_len` consecutive properly initialized bytes, unless `request_bytes_len` is 0.
+///   The array is owned by the caller, and is no longer accessed once this function returns.
+#[no_mangle]
+pub unsafe extern "C" fn update_connection_password(
+    client_adapter_ptr: *const c_void,
+    channel: usize,
+    request_bytes: *const u8,
+    request_bytes_len: usize,
+) {
+    let client_adapter = unsafe { ClientAdapter::from_ptr(client_adapter_ptr) };
+
+    let request = match parse_protobuf::<UpdateConnectionPassword>(unsafe {
+        bytes_from_raw(request_bytes, request_bytes_len)
+    }) {
+        Ok(request) => request,
+        Err(err) => return client_adapter.report(channel, Err(err)),
+    };
+
+    client_adapter.execute(channel, |mut client| async move {
+        client
+            .update_connection_password(
+                request.password.map(|password| password.to_string()),
+                request.immediate_auth,
+            )
+            .await
+    });
 }
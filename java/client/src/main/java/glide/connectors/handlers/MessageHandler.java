@@ -103,6 +103,17 @@ public class MessageHandler {
                                                 .map(v -> GlideString.of(v).toString())
                                                 .collect(Collectors.joining(" "))));
                 break;
+            case TopologyChange:
+                Logger.log(
+                        Logger.Level.INFO,
+                        "topology change notification",
+                        () ->
+                                String.format(
+                                        "Cluster topology changed: %s",
+                                        Arrays.stream(values)
+                                                .map(v -> v == null ? "null" : GlideString.of(v).toString())
+                                                .collect(Collectors.joining(" "))));
+                break;
             default:
                 Logger.log(
                         Logger.Level.WARN,
@@ -153,6 +164,9 @@ public class MessageHandler {
         PSubscribe,
         /// `ssubscribe` is received when client subscribed to a shard channel.
         SSubscribe,
+        /// `topology_change` is sent from the **library** when a change in a cluster's topology is
+        // observed.
+        TopologyChange,
     }
 
     /**
@@ -1317,6 +1317,12 @@ export class BaseClient {
                 messageKind === "PUnsubscribe"
             ) {
                 // pass
+            } else if (messageKind === "TopologyChange") {
+                Logger.log(
+                    "info",
+                    "topology change notification",
+                    `Cluster topology changed: ${JSON.stringify(nextPushNotificationValue["values"])}`,
+                );
             } else {
                 Logger.log(
                     "error",
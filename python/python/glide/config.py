@@ -138,10 +138,18 @@ class AdvancedBaseClientConfiguration:
             This applies both during initial client creation and any reconnections that may occur during request processing.
             **Note**: A high connection timeout may lead to prolonged blocking of the entire command pipeline.
             If not explicitly set, a default value of 250 milliseconds will be used.
+        use_tcp_socket_listener (bool): If True, the client communicates with the native layer over a TCP socket on 127.0.0.1,
+            authenticated with a per-process token, instead of a Unix domain socket.
+            This is meant for environments where Unix domain sockets aren't available. If not set, a Unix domain socket is used.
     """
 
-    def __init__(self, connection_timeout: Optional[int] = None):
+    def __init__(
+        self,
+        connection_timeout: Optional[int] = None,
+        use_tcp_socket_listener: bool = False,
+    ):
         self.connection_timeout = connection_timeout
+        self.use_tcp_socket_listener = use_tcp_socket_listener
 
     def _create_a_protobuf_conn_request(
         self, request: ConnectionRequest
@@ -264,9 +272,13 @@ class AdvancedGlideClientConfiguration(AdvancedBaseClientConfiguration):
     Represents the advanced configuration settings for a Standalone Glide client.
     """
 
-    def __init__(self, connection_timeout: Optional[int] = None):
+    def __init__(
+        self,
+        connection_timeout: Optional[int] = None,
+        use_tcp_socket_listener: bool = False,
+    ):
 
-        super().__init__(connection_timeout)
+        super().__init__(connection_timeout, use_tcp_socket_listener)
 
 
 class GlideClientConfiguration(BaseClientConfiguration):
@@ -423,8 +435,12 @@ class AdvancedGlideClusterClientConfiguration(AdvancedBaseClientConfiguration):
     Represents the advanced configuration settings for a Glide Cluster client.
     """
 
-    def __init__(self, connection_timeout: Optional[int] = None):
-        super().__init__(connection_timeout)
+    def __init__(
+        self,
+        connection_timeout: Optional[int] = None,
+        use_tcp_socket_listener: bool = False,
+    ):
+        super().__init__(connection_timeout, use_tcp_socket_listener)
 
 
 class GlideClusterClientConfiguration(BaseClientConfiguration):
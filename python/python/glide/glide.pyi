@@ -27,7 +27,9 @@ class ClusterScanCursor:
     def get_cursor(self) -> str: ...
     def is_finished(self) -> bool: ...
 
-def start_socket_listener_external(init_callback: Callable) -> None: ...
+def start_socket_listener_external(
+    init_callback: Callable, use_tcp_loopback: bool = False
+) -> None: ...
 def value_from_pointer(pointer: int) -> TResult: ...
 def create_leaked_value(message: str) -> int: ...
 def create_leaked_bytes_vec(args_vec: List[bytes]) -> int: ...
@@ -22,7 +22,10 @@ from glide.exceptions import (
 from glide.logger import Level as LogLevel
 from glide.logger import Logger as ClientLogger
 from glide.protobuf.command_request_pb2 import Command, CommandRequest, RequestType
-from glide.protobuf.connection_request_pb2 import ConnectionRequest
+from glide.protobuf.connection_request_pb2 import (
+    ConnectionRequest,
+    SocketAuthentication,
+)
 from glide.protobuf.response_pb2 import RequestErrorType, Response
 from glide.protobuf_codec import PartialMessageException, ProtobufCodec
 from glide.routes import Route, set_protobuf_route
@@ -70,6 +73,7 @@ class BaseClient(CoreCommands):
         self._buffered_requests: List[TRequest] = list()
         self._writer_lock = threading.Lock()
         self.socket_path: Optional[str] = None
+        self._socket_auth_token: Optional[str] = None
         self._reader_task: Optional[asyncio.Task] = None
         self._is_closed: bool = False
         self._pubsub_futures: List[asyncio.Future] = []
@@ -92,7 +96,9 @@ class BaseClient(CoreCommands):
         init_future: asyncio.Future = asyncio.Future()
         loop = asyncio.get_event_loop()
 
-        def init_callback(socket_path: Optional[str], err: Optional[str]):
+        def init_callback(
+            socket_path: Optional[str], err: Optional[str], auth_token: Optional[str]
+        ):
             if err is not None:
                 raise ClosingError(err)
             elif socket_path is None:
@@ -100,19 +106,29 @@ class BaseClient(CoreCommands):
                     "Socket initialization error: Missing valid socket path."
                 )
             else:
-                # Received socket path
+                # Received socket path, or the address and token of a TCP listener
                 self.socket_path = socket_path
+                self._socket_auth_token = auth_token
                 loop.call_soon_threadsafe(init_future.set_result, True)
 
-        start_socket_listener_external(init_callback=init_callback)
+        use_tcp_socket_listener = (
+            config.advanced_config is not None
+            and config.advanced_config.use_tcp_socket_listener
+        )
+        start_socket_listener_external(
+            init_callback=init_callback, use_tcp_loopback=use_tcp_socket_listener
+        )
 
         # will log if the logger was created (wrapper or costumer) on info
         # level or higher
         ClientLogger.log(LogLevel.INFO, "connection info", "new connection established")
         # Wait for the socket listener to complete its initialization
         await init_future
-        # Create UDS connection
-        await self._create_uds_connection()
+        if self._socket_auth_token is None:
+            # Create UDS connection
+            await self._create_uds_connection()
+        else:
+            await self._create_tcp_connection(self._socket_auth_token)
         # Start the reader loop as a background task
         self._reader_task = asyncio.create_task(self._reader_loop())
         # Set the client configurations
@@ -132,6 +148,42 @@ class BaseClient(CoreCommands):
             await self.close(f"Failed to create UDS connection: {e}")
             raise
 
+    async def _create_tcp_connection(self, auth_token: str) -> None:
+        try:
+            host, port = cast(str, self.socket_path).rsplit(":", 1)
+            async with async_timeout.timeout(DEFAULT_TIMEOUT_IN_MILLISECONDS):
+                reader, writer = await asyncio.open_connection(host=host, port=port)
+            self._reader = reader
+            self._writer = writer
+            await self._authenticate_tcp_connection(auth_token)
+        except Exception as e:
+            await self.close(f"Failed to create TCP connection: {e}")
+            raise
+
+    async def _authenticate_tcp_connection(self, auth_token: str) -> None:
+        # The listener answers the authentication before the reader loop is started,
+        # so the response is read directly from the socket.
+        b_arr = bytearray()
+        ProtobufCodec.encode_delimited(b_arr, SocketAuthentication(token=auth_token))
+        self._writer.write(b_arr)
+        await self._writer.drain()
+        read_bytes = bytearray()
+        while True:
+            chunk = await self._reader.read(DEFAULT_READ_BYTES_SIZE)
+            if len(chunk) == 0:
+                raise ClosingError("The socket listener closed the connection.")
+            read_bytes += chunk
+            try:
+                response, _ = ProtobufCodec.decode_delimited(
+                    read_bytes, memoryview(read_bytes), 0, Response
+                )
+            except PartialMessageException:
+                continue
+            response = cast(Response, response)
+            if response.HasField("closing_error"):
+                raise ClosingError(response.closing_error)
+            return
+
     def __del__(self) -> None:
         try:
             if self._reader_task:
@@ -426,6 +478,12 @@ class BaseClient(CoreCommands):
             or message_kind == "SUnsubscribe"
         ):
             pass
+        elif message_kind == "TopologyChange":
+            ClientLogger.log(
+                LogLevel.INFO,
+                "topology change notification",
+                f"Cluster topology changed: {push_notification['values']}",
+            )
         else:
             ClientLogger.log(
                 LogLevel.WARN,
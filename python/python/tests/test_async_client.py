@@ -141,6 +141,20 @@ class TestGlideClients:
         assert await glide_client.get(key) == value.encode()
         await glide_client.close()
 
+    @pytest.mark.parametrize("cluster_mode", [True, False])
+    @pytest.mark.parametrize("protocol", [ProtocolVersion.RESP2, ProtocolVersion.RESP3])
+    async def test_client_with_tcp_socket_listener(self, request, cluster_mode, protocol):
+        glide_client = await create_client(
+            request,
+            cluster_mode=cluster_mode,
+            protocol=protocol,
+            use_tcp_socket_listener=True,
+        )
+        key = get_random_string(10)
+        assert await glide_client.set(key, "value") == OK
+        assert await glide_client.get(key) == b"value"
+        await glide_client.close()
+
     @pytest.mark.parametrize("cluster_mode", [True, False])
     @pytest.mark.parametrize("protocol", [ProtocolVersion.RESP2, ProtocolVersion.RESP3])
     async def test_send_and_receive_non_ascii_unicode(self, glide_client: TGlideClient):
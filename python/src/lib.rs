@@ -2,9 +2,9 @@
 
 use bytes::Bytes;
 use glide_core::client::FINISHED_SCAN_CURSOR;
-use glide_core::start_socket_listener;
 use glide_core::Telemetry;
 use glide_core::MAX_REQUEST_ARGS_LENGTH;
+use glide_core::{start_socket_listener_with_transport, ListenerAddress, SocketTransport};
 use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::*;
 use pyo3::types::{PyAny, PyBool, PyBytes, PyDict, PyFloat, PyList, PySet, PyString};
@@ -160,25 +160,58 @@ fn glide(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     fn py_init(level: Option<Level>, file_name: Option<&str>) -> Level {
         init(level, file_name)
     }
+    /// Starts the socket listener, on a TCP socket on 127.0.0.1 if `use_tcp_loopback` is set, or on a Unix socket otherwise.
+    /// `init_callback` is called with the listener's address, the error if it failed to start, and the token that
+    /// connections to a TCP listener must authenticate with.
     #[pyfunction]
-    fn start_socket_listener_external(init_callback: PyObject) -> PyResult<PyObject> {
+    #[pyo3(signature = (init_callback, use_tcp_loopback=false))]
+    fn start_socket_listener_external(
+        init_callback: PyObject,
+        use_tcp_loopback: bool,
+    ) -> PyResult<PyObject> {
         let init_callback = Arc::new(init_callback);
-        start_socket_listener({
-            let init_callback = Arc::clone(&init_callback);
-            move |socket_path| {
+        let transport = if use_tcp_loopback {
+            SocketTransport::TcpLoopback
+        } else {
+            SocketTransport::UnixSocket
+        };
+        start_socket_listener_with_transport(
+            {
                 let init_callback = Arc::clone(&init_callback);
-                Python::with_gil(|py| {
-                    match socket_path {
-                        Ok(path) => {
-                            let _ = init_callback.call_bound(py, (path, py.None()), None);
-                        }
-                        Err(error_message) => {
-                            let _ = init_callback.call_bound(py, (py.None(), error_message), None);
-                        }
-                    };
-                });
-            }
-        });
+                move |address| {
+                    let init_callback = Arc::clone(&init_callback);
+                    Python::with_gil(|py| {
+                        match address {
+                            Ok(ListenerAddress::UnixSocket(path)) => {
+                                let _ = init_callback.call_bound(
+                                    py,
+                                    (path, py.None(), py.None()),
+                                    None,
+                                );
+                            }
+                            Ok(ListenerAddress::TcpLoopback {
+                                address,
+                                auth_token,
+                            }) => {
+                                let _ = init_callback.call_bound(
+                                    py,
+                                    (address.to_string(), py.None(), auth_token),
+                                    None,
+                                );
+                            }
+                            Err(error_message) => {
+                                let _ = init_callback.call_bound(
+                                    py,
+                                    (py.None(), error_message, py.None()),
+                                    None,
+                                );
+                            }
+                        };
+                    });
+                }
+            },
+            transport,
+        );
         Ok(Python::with_gil(|py| "OK".into_py(py)))
     }
 